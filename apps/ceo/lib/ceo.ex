@@ -26,6 +26,13 @@ defmodule Ceo do
   4. review_organizational_health - Get status of all agents and systems
   5. initiate_decision - Start a new organizational decision process
   6. override_decision - Override a decision made by subordinate agents
+  7. list_dead_letters - Inspect messages that exhausted their retries
+  8. requeue_dead_letter - Send a dead-lettered message again
+  9. discard_dead_letter - Permanently drop a dead-lettered message
+  10. list_human_approvals - List decisions waiting on a human
+  11. show_human_approval - Inspect one approval and its decision
+  12. resolve_human_approval - Approve or reject, resuming paused workflows
+  13. comment_human_approval - Comment on an approval
   """
 
   use EchoShared.MCP.Server
@@ -36,6 +43,7 @@ defmodule Ceo do
   alias EchoShared.Schemas.Decision
   alias EchoShared.Repo
   alias EchoShared.LLM.DecisionHelper
+  alias EchoShared.Decisions.{Escalation, HumanApprovals, Voting}
 
   @impl true
   def agent_info do
@@ -75,6 +83,17 @@ defmodule Ceo do
             }
           },
           required: ["initiative_id", "rationale"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{type: "string"},
+            status: %{type: "string"},
+            rationale: %{type: "string"},
+            budget_allocated: %{type: "number"},
+            conditions: %{type: "array", items: %{type: "string"}}
+          },
+          required: ["decision_id", "status", "rationale", "conditions"]
         }
       },
       %{
@@ -101,6 +120,18 @@ defmodule Ceo do
             }
           },
           required: ["recipient_role", "amount", "purpose"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            recipient_role: %{type: "string"},
+            amount: %{type: "number"},
+            purpose: %{type: "string"},
+            duration: %{type: "string"},
+            allocated_by: %{type: "string"},
+            allocated_at: %{type: "string"}
+          },
+          required: ["recipient_role", "amount", "purpose", "allocated_by", "allocated_at"]
         }
       },
       %{
@@ -128,6 +159,16 @@ defmodule Ceo do
             }
           },
           required: ["decision_id", "reason", "urgency"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{type: "string"},
+            status: %{type: "string"},
+            urgency: %{type: "string"},
+            reason: %{type: "string"}
+          },
+          required: ["decision_id", "status", "urgency", "reason"]
         }
       },
       %{
@@ -145,6 +186,49 @@ defmodule Ceo do
               description: "Time range for metrics (e.g., '24h', '7d', '30d', default: '24h')"
             }
           }
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            overall_status: %{type: "string", enum: ["Healthy", "Good", "Degraded", "Critical"]},
+            health_score: %{type: "number", minimum: 0, maximum: 100},
+            agents: %{
+              type: "array",
+              items: %{
+                type: "object",
+                properties: %{
+                  role: %{type: "string"},
+                  status: %{type: "string"},
+                  last_heartbeat: %{type: "string"}
+                },
+                required: ["role", "status"]
+              }
+            },
+            recent_decisions: %{
+              type: "array",
+              items: %{
+                type: "object",
+                properties: %{
+                  decision_id: %{type: "string"},
+                  decision_type: %{type: "string"},
+                  status: %{type: "string"},
+                  initiator_role: %{type: "string"},
+                  inserted_at: %{type: "string"}
+                },
+                required: ["decision_id", "decision_type", "status"]
+              }
+            },
+            metrics: %{
+              type: "object",
+              properties: %{
+                decision_count: %{type: "integer"},
+                completed_decisions: %{type: "integer"},
+                completion_rate: %{type: "number"},
+                avg_decision_time_hours: %{type: "number"}
+              }
+            }
+          },
+          required: ["overall_status", "health_score", "agents", "recent_decisions"]
         }
       },
       %{
@@ -174,9 +258,28 @@ defmodule Ceo do
             deadline: %{
               type: "string",
               description: "ISO 8601 deadline for decision (optional)"
+            },
+            timeout_policy: %{
+              type: "string",
+              enum: ["close", "escalate", "approve", "reject"],
+              description:
+                "What happens if the decision is still pending at its deadline (default: close - decide on the votes cast, or escalate if not put to a vote)"
             }
           },
           required: ["decision_type", "mode", "context"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{type: "string"},
+            decision_type: %{type: "string"},
+            mode: %{type: "string"},
+            status: %{type: "string"},
+            participants: %{type: "array", items: %{type: "string"}},
+            deadline: %{type: ["string", "null"]},
+            timeout_policy: %{type: "string"}
+          },
+          required: ["decision_id", "decision_type", "mode", "status", "participants"]
         }
       },
       %{
@@ -204,6 +307,212 @@ defmodule Ceo do
             }
           },
           required: ["decision_id", "override_rationale", "new_outcome"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{type: "string"},
+            original_outcome: %{type: "object"},
+            new_outcome: %{type: "object"},
+            override_rationale: %{type: "string"},
+            notified_agents: %{type: "array", items: %{type: "string"}}
+          },
+          required: ["decision_id", "new_outcome", "override_rationale", "notified_agents"]
+        }
+      },
+      %{
+        name: "list_dead_letters",
+        description: "List inter-agent messages that failed processing too many times",
+        inputSchema: %{
+          type: "object",
+          properties: %{
+            to_role: %{
+              type: "string",
+              description: "Only messages addressed to this role (optional)"
+            },
+            limit: %{
+              type: "integer",
+              minimum: 1,
+              maximum: 200,
+              description: "Maximum number of dead letters (default: 50)"
+            }
+          }
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            dead_letters: %{
+              type: "array",
+              items: %{
+                type: "object",
+                properties: %{
+                  id: %{type: "integer"},
+                  message_id: %{type: "integer"},
+                  from_role: %{type: "string"},
+                  to_role: %{type: "string"},
+                  type: %{type: "string"},
+                  subject: %{type: "string"},
+                  attempts: %{type: "integer"},
+                  last_error: %{type: "string"},
+                  inserted_at: %{type: "string"}
+                },
+                required: ["id", "message_id", "from_role", "to_role", "subject", "attempts"]
+              }
+            }
+          },
+          required: ["dead_letters"]
+        }
+      },
+      %{
+        name: "requeue_dead_letter",
+        description: "Redeliver a dead-lettered message to its recipient with a fresh retry budget",
+        inputSchema: %{
+          type: "object",
+          properties: %{
+            dead_letter_id: %{
+              type: "integer",
+              description: "The dead letter ID (from list_dead_letters)"
+            }
+          },
+          required: ["dead_letter_id"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            dead_letter_id: %{type: "integer"},
+            message_id: %{type: "integer"}
+          },
+          required: ["dead_letter_id", "message_id"]
+        }
+      },
+      %{
+        name: "discard_dead_letter",
+        description: "Permanently discard a dead-lettered message",
+        inputSchema: %{
+          type: "object",
+          properties: %{
+            dead_letter_id: %{
+              type: "integer",
+              description: "The dead letter ID (from list_dead_letters)"
+            }
+          },
+          required: ["dead_letter_id"]
+        }
+      },
+      %{
+        name: "list_human_approvals",
+        description: "List decisions waiting on a human (escalations and paused workflows)",
+        inputSchema: %{
+          type: "object",
+          properties: %{
+            status: %{
+              type: "string",
+              enum: ["pending", "approved", "rejected", "all"],
+              description: "Which approvals to list (default: pending)"
+            },
+            limit: %{
+              type: "integer",
+              minimum: 1,
+              maximum: 200,
+              description: "Maximum number of approvals (default: 50)"
+            }
+          }
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            approvals: %{
+              type: "array",
+              items: %{
+                type: "object",
+                properties: %{
+                  id: %{type: "integer"},
+                  decision_id: %{type: "string"},
+                  decision_type: %{type: "string"},
+                  status: %{type: "string"},
+                  requested_by: %{type: "string"},
+                  reason: %{type: "string"},
+                  urgency: %{type: "string"},
+                  inserted_at: %{type: "string"}
+                },
+                required: ["id", "decision_id", "status", "requested_by"]
+              }
+            }
+          },
+          required: ["approvals"]
+        }
+      },
+      %{
+        name: "show_human_approval",
+        description: "Inspect a human approval: its decision, escalation history and comments",
+        inputSchema: %{
+          type: "object",
+          properties: %{
+            approval_id: %{
+              type: "integer",
+              description: "The approval ID (from list_human_approvals)"
+            }
+          },
+          required: ["approval_id"]
+        }
+      },
+      %{
+        name: "resolve_human_approval",
+        description: "Approve or reject a decision waiting on a human; paused workflows resume automatically",
+        inputSchema: %{
+          type: "object",
+          properties: %{
+            approval_id: %{
+              type: "integer",
+              description: "The approval ID (from list_human_approvals)"
+            },
+            outcome: %{
+              type: "string",
+              enum: ["approve", "reject"],
+              description: "The human's decision"
+            },
+            note: %{
+              type: "string",
+              description: "Rationale recorded on the decision (optional)"
+            },
+            resolved_by: %{
+              type: "string",
+              description: "Who decided (default: human)"
+            }
+          },
+          required: ["approval_id", "outcome"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            approval_id: %{type: "integer"},
+            decision_id: %{type: "string"},
+            status: %{type: "string"},
+            resumed: %{type: "boolean"}
+          },
+          required: ["approval_id", "decision_id", "status", "resumed"]
+        }
+      },
+      %{
+        name: "comment_human_approval",
+        description: "Comment on a human approval; the requesting agent receives the comment",
+        inputSchema: %{
+          type: "object",
+          properties: %{
+            approval_id: %{
+              type: "integer",
+              description: "The approval ID (from list_human_approvals)"
+            },
+            comment: %{
+              type: "string",
+              description: "The comment"
+            },
+            by: %{
+              type: "string",
+              description: "Who is commenting (default: human)"
+            }
+          },
+          required: ["approval_id", "comment"]
         }
       },
       %{
@@ -227,6 +536,15 @@ defmodule Ceo do
             }
           },
           required: ["query_type", "question"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            query_type: %{type: "string"},
+            llm_enabled: %{type: "boolean"},
+            response: %{type: "string"}
+          },
+          required: ["query_type", "llm_enabled"]
         }
       },
       %{
@@ -262,20 +580,35 @@ defmodule Ceo do
             }
           },
           required: ["question"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            response: %{type: "string"},
+            session_id: %{type: "string"},
+            turn_count: %{type: "integer"},
+            estimated_tokens: %{type: "integer"},
+            model: %{type: "string"},
+            agent: %{type: "string"},
+            warnings: %{type: "array", items: %{type: "string"}}
+          },
+          required: ["response", "session_id", "turn_count", "model", "agent"]
         }
       }
     ]
   end
 
   @impl true
-  def execute_tool("approve_strategic_initiative", args) do
-    with {:ok, initiative_id} <- validate_required_string(args, "initiative_id"),
-         {:ok, rationale} <- validate_required_string(args, "rationale"),
-         {:ok, decision} <- load_decision(initiative_id),
-         {:ok, approved_decision} <- approve_decision(decision, rationale, args) do
-      # Notify relevant agents
-      notify_approval(approved_decision)
-
+  def execute_tool(
+        "approve_strategic_initiative",
+        %{"initiative_id" => initiative_id, "rationale" => rationale} = args
+      ) do
+    with {:ok, decision} <- load_decision(initiative_id),
+         {:ok, approved_decision} <-
+           update_and_notify(
+             fn -> approve_decision(decision, rationale, args) end,
+             &notify_approval/1
+           ) do
       result = """
       Strategic Initiative Approved
 
@@ -288,17 +621,24 @@ defmodule Ceo do
       The decision has been recorded and all relevant agents have been notified.
       """
 
-      {:ok, result}
+      {:ok, result,
+       %{
+         decision_id: approved_decision.id,
+         status: approved_decision.status,
+         rationale: rationale,
+         budget_allocated: args["budget_allocated"],
+         conditions: args["conditions"] || []
+       }}
     else
       {:error, reason} -> {:error, "Failed to approve initiative: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("allocate_budget", args) do
-    with {:ok, recipient_role} <- validate_required_string(args, "recipient_role"),
-         {:ok, amount} <- validate_required_number(args, "amount"),
-         {:ok, purpose} <- validate_required_string(args, "purpose"),
-         :ok <- validate_budget_authority(amount),
+  def execute_tool(
+        "allocate_budget",
+        %{"recipient_role" => recipient_role, "amount" => amount, "purpose" => purpose} = args
+      ) do
+    with :ok <- validate_budget_authority(amount),
          {:ok, allocation} <- create_budget_allocation(recipient_role, amount, purpose, args) do
       # Send budget notification via message bus
       MessageBus.publish_message(
@@ -320,7 +660,7 @@ defmodule Ceo do
       The budget has been allocated and #{recipient_role} has been notified.
       """
 
-      {:ok, result}
+      {:ok, result, allocation}
     else
       {:error, :budget_limit_exceeded} ->
         {:error, "Budget amount exceeds CEO autonomous authority limit. Requires board approval."}
@@ -330,18 +670,27 @@ defmodule Ceo do
     end
   end
 
-  def execute_tool("escalate_to_human", args) do
-    with {:ok, decision_id} <- validate_required_string(args, "decision_id"),
-         {:ok, reason} <- validate_required_string(args, "reason"),
-         {:ok, urgency} <- validate_required_string(args, "urgency"),
-         {:ok, decision} <- load_decision(decision_id),
-         {:ok, escalated} <- escalate_decision(decision, reason, urgency, args) do
+  def execute_tool(
+        "escalate_to_human",
+        %{"decision_id" => decision_id, "reason" => reason, "urgency" => urgency} = args
+      ) do
+    # The CEO reports to the human decision-maker, the top of the chain
+    with {:ok, decision} <- load_decision(decision_id),
+         {:ok, escalated} <-
+           Escalation.escalate(decision, reason, from: :ceo, urgency: urgency, context: args["context"]) do
       # Broadcast escalation notification
       MessageBus.broadcast_message(
         :ceo,
         :escalation,
         "Human Judgment Required: #{decision_id}",
-        escalated
+        %{
+          decision_id: escalated.id,
+          decision_type: escalated.decision_type,
+          reason: reason,
+          urgency: urgency,
+          escalated_to: escalated.escalated_to,
+          hops: escalated.escalation_hops
+        }
       )
 
       result = """
@@ -351,11 +700,12 @@ defmodule Ceo do
       Urgency: #{urgency}
       Reason: #{reason}
 
-      The decision has been flagged for human review. All agents have been notified.
-      Next steps: Await human decision-maker input.
+      The decision has been added to the human approval inbox. All agents have been notified.
+      Next steps: Review it with list_human_approvals and resolve_human_approval.
       """
 
-      {:ok, result}
+      {:ok, result,
+       %{decision_id: escalated.id, status: escalated.status, urgency: urgency, reason: reason}}
     else
       {:error, reason} -> {:error, "Failed to escalate decision: #{inspect(reason)}"}
     end
@@ -369,19 +719,19 @@ defmodule Ceo do
          {:ok, recent_decisions} <- get_recent_decisions(time_range),
          {:ok, metrics} <- get_organizational_metrics(time_range, include_metrics) do
       result = format_health_report(agent_status, recent_decisions, metrics, include_metrics)
-      {:ok, result}
+      {:ok, result, health_data(agent_status, recent_decisions, metrics, include_metrics)}
     else
       {:error, reason} -> {:error, "Failed to generate health report: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("initiate_decision", args) do
-    with {:ok, decision_type} <- validate_required_string(args, "decision_type"),
-         {:ok, mode} <- validate_required_string(args, "mode"),
-         {:ok, context} <- validate_required(args, "context"),
-         {:ok, decision} <- create_decision(decision_type, mode, context, args) do
+  def execute_tool(
+        "initiate_decision",
+        %{"decision_type" => decision_type, "mode" => mode, "context" => context} = args
+      ) do
+    with {:ok, decision} <- create_decision(decision_type, mode, context, args) do
       # Publish decision event
-      MessageBus.publish_decision_event(:new, %{
+      MessageBus.publish_decision_event(:ceo, :new, %{
         decision_id: decision.id,
         type: decision_type,
         mode: mode,
@@ -393,6 +743,11 @@ defmodule Ceo do
         notify_participants(decision, args["participants"] || [])
       end
 
+      # Collaborative decisions are settled by the participants' votes
+      if mode == "collaborative" do
+        Voting.open_vote(decision)
+      end
+
       result = """
       Decision Initiated
 
@@ -400,27 +755,43 @@ defmodule Ceo do
       Type: #{decision_type}
       Mode: #{mode}
       Participants: #{format_list(args["participants"])}
-      Deadline: #{args["deadline"] || "Not specified"}
+      Deadline: #{decision.deadline || "Not specified"}
+      On timeout: #{decision.timeout_policy}
 
       The decision process has been initiated. Participants have been notified.
       """
 
-      {:ok, result}
+      {:ok, result,
+       %{
+         decision_id: decision.id,
+         decision_type: decision_type,
+         mode: mode,
+         status: decision.status,
+         participants: args["participants"] || [],
+         deadline: decision.deadline,
+         timeout_policy: decision.timeout_policy
+       }}
     else
       {:error, reason} -> {:error, "Failed to initiate decision: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("override_decision", args) do
-    with {:ok, decision_id} <- validate_required_string(args, "decision_id"),
-         {:ok, override_rationale} <- validate_required_string(args, "override_rationale"),
-         {:ok, new_outcome} <- validate_required(args, "new_outcome"),
-         {:ok, decision} <- load_decision(decision_id),
-         {:ok, overridden} <- override_decision_record(decision, override_rationale, new_outcome) do
-      # Notify affected agents
-      agents_to_notify = args["notify_agents"] || []
-      notify_override(overridden, agents_to_notify)
-
+  def execute_tool(
+        "override_decision",
+        %{
+          "decision_id" => decision_id,
+          "override_rationale" => override_rationale,
+          "new_outcome" => new_outcome
+        } = args
+      ) do
+    agents_to_notify = args["notify_agents"] || []
+
+    with {:ok, decision} <- load_decision(decision_id),
+         {:ok, _overridden} <-
+           update_and_notify(
+             fn -> override_decision_record(decision, override_rationale, new_outcome) end,
+             &notify_override(&1, agents_to_notify)
+           ) do
       result = """
       Decision Overridden by CEO
 
@@ -432,68 +803,238 @@ defmodule Ceo do
       The decision has been overridden and all affected agents have been notified.
       """
 
-      {:ok, result}
+      {:ok, result,
+       %{
+         decision_id: decision_id,
+         original_outcome: decision.outcome,
+         new_outcome: new_outcome,
+         override_rationale: override_rationale,
+         notified_agents: agents_to_notify
+       }}
     else
       {:error, reason} -> {:error, "Failed to override decision: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("ai_consult", args) do
-    with {:ok, query_type} <- validate_required_string(args, "query_type"),
-         {:ok, question} <- validate_required_string(args, "question") do
+  def execute_tool("list_dead_letters", args) do
+    opts =
+      [limit: Map.get(args, "limit", 50), to_role: args["to_role"]]
+      |> Enum.reject(fn {_key, value} -> is_nil(value) end)
 
-      context = args["context"] || %{}
+    dead_letters = MessageBus.list_dead_letters(opts)
 
-      result = case query_type do
-        "decision_analysis" ->
-          decision_context = Map.merge(context, %{
-            decision_type: context["decision_type"] || "strategic",
-            context: question
-          })
-          consult_llm_for_decision(decision_context)
+    result =
+      if dead_letters == [] do
+        "No dead-lettered messages."
+      else
+        lines =
+          Enum.map_join(dead_letters, "\n", fn d ->
+            "- ##{d.id} #{d.from_role} -> #{d.to_role}: #{d.subject} (#{d.attempts} attempts, last error: #{d.last_error})"
+          end)
 
-        "option_evaluation" ->
-          evaluation_context = %{
-            question: question,
-            options: context["options"] || [],
-            criteria: context["criteria"]
-          }
-          consult_llm_for_evaluation(evaluation_context)
+        """
+        Dead-Lettered Messages (#{length(dead_letters)})
 
-        "rationale_generation" ->
-          decision_details = %{
-            decision: question,
-            factors: context["factors"] || [],
-            outcome: context["outcome"]
-          }
-          consult_llm_for_rationale(decision_details)
+        #{lines}
 
-        "strategic_question" ->
-          consult_llm_simple(question, context["additional_context"])
+        Use requeue_dead_letter to retry a message or discard_dead_letter to drop it.
+        """
+      end
+
+    {:ok, result, %{dead_letters: Enum.map(dead_letters, &dead_letter_data/1)}}
+  end
+
+  def execute_tool("requeue_dead_letter", %{"dead_letter_id" => dead_letter_id}) do
+    case MessageBus.requeue_dead_letter(dead_letter_id) do
+      {:ok, message_id} ->
+        {:ok, "Dead letter #{dead_letter_id} requeued as message #{message_id}.",
+         %{dead_letter_id: dead_letter_id, message_id: message_id}}
+
+      {:error, :not_found} ->
+        {:error, "Dead letter not found: #{dead_letter_id}"}
+
+      {:error, reason} ->
+        {:error, "Failed to requeue dead letter: #{inspect(reason)}"}
+    end
+  end
+
+  def execute_tool("discard_dead_letter", %{"dead_letter_id" => dead_letter_id}) do
+    case MessageBus.discard_dead_letter(dead_letter_id) do
+      {:ok, dead_letter} ->
+        {:ok, "Dead letter #{dead_letter_id} (#{dead_letter.subject} to #{dead_letter.to_role}) discarded."}
+
+      {:error, :not_found} ->
+        {:error, "Dead letter not found: #{dead_letter_id}"}
+
+      {:error, reason} ->
+        {:error, "Failed to discard dead letter: #{inspect(reason)}"}
+    end
+  end
 
-        _ ->
-          {:error, "Unknown query type: #{query_type}"}
+  def execute_tool("list_human_approvals", args) do
+    status =
+      case Map.get(args, "status", "pending") do
+        "approved" -> :approved
+        "rejected" -> :rejected
+        "all" -> :all
+        _pending -> :pending
       end
 
-      case result do
-        {:ok, response} ->
-          {:ok, """
-          AI Consultation Result (Query Type: #{query_type})
+    approvals = HumanApprovals.list(status: status, limit: Map.get(args, "limit", 50))
 
-          #{response}
+    result =
+      if approvals == [] do
+        "No #{if status == :all, do: "", else: "#{status} "}human approvals."
+      else
+        lines =
+          Enum.map_join(approvals, "\n", fn a ->
+            "- ##{a.id} [#{a.status}] #{a.decision.decision_type} from #{a.requested_by}" <>
+              "#{if a.urgency, do: " (#{a.urgency})", else: ""}: #{a.reason || "No reason given"}"
+          end)
 
-          ---
-          Note: This is AI-generated advice. Use your judgment and validate with organizational context.
-          """}
+        """
+        Human Approvals (#{length(approvals)})
 
-        {:error, :llm_disabled} ->
-          {:ok, "AI consultation is currently disabled for CEO role. Enable with CEO_LLM_ENABLED=true"}
+        #{lines}
 
-        {:error, reason} ->
-          {:error, "AI consultation failed: #{inspect(reason)}"}
+        Use show_human_approval for details, resolve_human_approval to approve or reject.
+        """
       end
-    else
-      {:error, reason} -> {:error, "Invalid ai_consult request: #{inspect(reason)}"}
+
+    {:ok, result, %{approvals: Enum.map(approvals, &approval_data/1)}}
+  end
+
+  def execute_tool("show_human_approval", %{"approval_id" => approval_id}) do
+    case HumanApprovals.get(approval_id) do
+      {:ok, approval} ->
+        decision = approval.decision
+
+        hops =
+          Enum.map_join(decision.escalation_hops || [], "\n", fn hop ->
+            "- #{hop["from"]} -> #{hop["to"]}: #{hop["reason"]}"
+          end)
+
+        comments =
+          Enum.map_join(approval.comments || [], "\n", fn comment ->
+            "- #{comment["by"]} (#{comment["at"]}): #{comment["text"]}"
+          end)
+
+        result = """
+        Human Approval ##{approval.id} [#{approval.status}]
+
+        Decision ID: #{decision.id}
+        Type: #{decision.decision_type}
+        Mode: #{decision.mode}
+        Decision Status: #{decision.status}
+        Requested By: #{approval.requested_by}
+        Urgency: #{approval.urgency || "Not specified"}
+        Reason: #{approval.reason || "No reason given"}
+        Paused Execution: #{approval.workflow_execution_id || approval.flow_execution_id || "None"}
+
+        Context:
+        #{inspect(decision.context, pretty: true)}
+
+        Escalation History:
+        #{if hops == "", do: "None", else: hops}
+
+        Comments:
+        #{if comments == "", do: "None", else: comments}
+        """
+
+        {:ok, result}
+
+      {:error, :not_found} ->
+        {:error, "Human approval not found: #{approval_id}"}
+    end
+  end
+
+  def execute_tool("resolve_human_approval", %{"approval_id" => approval_id, "outcome" => outcome} = args) do
+    status = if outcome == "approve", do: :approved, else: :rejected
+
+    case HumanApprovals.resolve(approval_id, status, by: args["resolved_by"], note: args["note"]) do
+      {:ok, approval} ->
+        paused? = approval.workflow_execution_id || approval.flow_execution_id
+        resumed? = approval.resumed_at != nil
+
+        follow_up =
+          cond do
+            resumed? -> "The paused execution has resumed."
+            paused? -> "The paused workflow resumes on the node running the workflow engine."
+            true -> "#{String.upcase(approval.requested_by)} has been notified."
+          end
+
+        {:ok, "Decision #{approval.decision_id} #{status} (approval ##{approval.id}). #{follow_up}",
+         %{approval_id: approval.id, decision_id: approval.decision_id, status: status, resumed: resumed?}}
+
+      {:error, :not_found} ->
+        {:error, "Human approval not found: #{approval_id}"}
+
+      {:error, reason} ->
+        {:error, "Failed to resolve human approval: #{inspect(reason)}"}
+    end
+  end
+
+  def execute_tool("comment_human_approval", %{"approval_id" => approval_id, "comment" => comment} = args) do
+    case HumanApprovals.comment(approval_id, comment, by: args["by"]) do
+      {:ok, approval} ->
+        {:ok, "Comment added to approval ##{approval.id} (#{length(approval.comments)} comments)."}
+
+      {:error, :not_found} ->
+        {:error, "Human approval not found: #{approval_id}"}
+    end
+  end
+
+  def execute_tool("ai_consult", %{"query_type" => query_type, "question" => question} = args) do
+    context = args["context"] || %{}
+
+    result = case query_type do
+      "decision_analysis" ->
+        decision_context = Map.merge(context, %{
+          decision_type: context["decision_type"] || "strategic",
+          context: question
+        })
+        consult_llm_for_decision(decision_context)
+
+      "option_evaluation" ->
+        evaluation_context = %{
+          question: question,
+          options: context["options"] || [],
+          criteria: context["criteria"]
+        }
+        consult_llm_for_evaluation(evaluation_context)
+
+      "rationale_generation" ->
+        decision_details = %{
+          decision: question,
+          factors: context["factors"] || [],
+          outcome: context["outcome"]
+        }
+        consult_llm_for_rationale(decision_details)
+
+      "strategic_question" ->
+        consult_llm_simple(question, context["additional_context"])
+
+      _ ->
+        {:error, "Unknown query type: #{query_type}"}
+    end
+
+    case result do
+      {:ok, response} ->
+        {:ok, """
+        AI Consultation Result (Query Type: #{query_type})
+
+        #{response}
+
+        ---
+        Note: This is AI-generated advice. Use your judgment and validate with organizational context.
+        """, %{query_type: query_type, llm_enabled: true, response: response}}
+
+      {:error, :llm_disabled} ->
+        {:ok, "AI consultation is currently disabled for CEO role. Enable with CEO_LLM_ENABLED=true",
+         %{query_type: query_type, llm_enabled: false}}
+
+      {:error, reason} ->
+        {:error, "AI consultation failed: #{inspect(reason)}"}
     end
   end
 
@@ -533,6 +1074,21 @@ defmodule Ceo do
     end
   end
 
+  # The decision change and its notifications commit together; the
+  # messages go out through the MessageBus outbox once committed
+  defp update_and_notify(update, notify) do
+    Repo.transaction(fn ->
+      case update.() do
+        {:ok, decision} ->
+          notify.(decision)
+          decision
+
+        {:error, reason} ->
+          Repo.rollback(reason)
+      end
+    end)
+  end
+
   defp approve_decision(decision, rationale, args) do
     attrs = %{
       status: :approved,
@@ -574,23 +1130,6 @@ defmodule Ceo do
     {:ok, allocation}
   end
 
-  defp escalate_decision(decision, reason, urgency, args) do
-    attrs = %{
-      status: :escalated,
-      outcome: %{
-        escalated_by: "ceo",
-        reason: reason,
-        urgency: urgency,
-        context: args["context"],
-        escalated_at: DateTime.utc_now()
-      }
-    }
-
-    decision
-    |> Decision.changeset(attrs)
-    |> Repo.update()
-  end
-
   defp get_all_agent_status do
     # Query agent_status table for all agents
     query =
@@ -695,8 +1234,39 @@ defmodule Ceo do
     """
   end
 
+  defp health_data(agent_status, recent_decisions, metrics, include_metrics) do
+    data = %{
+      overall_status: calculate_overall_health(agent_status, metrics),
+      health_score: calculate_health_score(agent_status),
+      agents:
+        Enum.map(agent_status, fn agent ->
+          %{role: agent.role, status: agent.status, last_heartbeat: agent.last_heartbeat}
+        end),
+      recent_decisions:
+        Enum.map(recent_decisions, fn d ->
+          %{
+            decision_id: d.id,
+            decision_type: d.decision_type,
+            status: d.status,
+            initiator_role: d.initiator_role,
+            inserted_at: d.inserted_at
+          }
+        end)
+    }
+
+    if include_metrics, do: Map.put(data, :metrics, metrics), else: data
+  end
+
+  # Percentage of agents currently running
+  defp calculate_health_score([]), do: 0.0
+
+  defp calculate_health_score(agent_status) do
+    running_agents = Enum.count(agent_status, &(&1.status == "running"))
+    Float.round(running_agents / length(agent_status) * 100, 1)
+  end
+
   defp calculate_overall_health(agent_status, metrics) do
-    running_agents = Enum.count(agent_status, &(&1.status == :running))
+    running_agents = Enum.count(agent_status, &(&1.status == "running"))
     total_agents = Enum.count(agent_status)
 
     cond do
@@ -707,6 +1277,35 @@ defmodule Ceo do
     end
   end
 
+  defp dead_letter_data(dead_letter) do
+    %{
+      id: dead_letter.id,
+      message_id: dead_letter.message_id,
+      from_role: dead_letter.from_role,
+      to_role: dead_letter.to_role,
+      type: dead_letter.type,
+      subject: dead_letter.subject,
+      attempts: dead_letter.attempts,
+      last_error: dead_letter.last_error,
+      inserted_at: dead_letter.inserted_at
+    }
+    |> Map.reject(fn {_key, value} -> is_nil(value) end)
+  end
+
+  defp approval_data(approval) do
+    %{
+      id: approval.id,
+      decision_id: approval.decision_id,
+      decision_type: approval.decision.decision_type,
+      status: approval.status,
+      requested_by: approval.requested_by,
+      reason: approval.reason,
+      urgency: approval.urgency,
+      inserted_at: approval.inserted_at
+    }
+    |> Map.reject(fn {_key, value} -> is_nil(value) end)
+  end
+
   defp create_decision(decision_type, mode, context, args) do
     attrs = %{
       decision_type: decision_type,
@@ -715,8 +1314,9 @@ defmodule Ceo do
       participants: args["participants"] || [],
       context: context,
       status: :pending,
+      deadline: args["deadline"],
+      timeout_policy: args["timeout_policy"] || :close,
       metadata: %{
-        deadline: args["deadline"],
         initiated_at: DateTime.utc_now()
       }
     }
@@ -736,7 +1336,8 @@ defmodule Ceo do
         %{
           decision_id: decision.id,
           type: decision.decision_type,
-          context: decision.context
+          context: decision.context,
+          deadline: decision.deadline
         }
       )
     end)
@@ -772,7 +1373,7 @@ defmodule Ceo do
       end)
     end
 
-    MessageBus.publish_decision_event(:completed, %{
+    MessageBus.publish_decision_event(:ceo, :completed, %{
       decision_id: decision.id,
       status: decision.status
     })
@@ -822,29 +1423,6 @@ defmodule Ceo do
     Calendar.strftime(dt, "%Y-%m-%d %H:%M UTC")
   end
 
-  defp validate_required_number(args, key) do
-    case Map.get(args, key) do
-      nil -> {:error, "Missing required field: #{key}"}
-      value when is_number(value) -> {:ok, value}
-      _ -> {:error, "Field #{key} must be a number"}
-    end
-  end
-
-  defp validate_required(args, key) do
-    case Map.get(args, key) do
-      nil -> {:error, "Missing required field: #{key}"}
-      value -> {:ok, value}
-    end
-  end
-
-  defp validate_required_string(args, key) do
-    case Map.get(args, key) do
-      nil -> {:error, "Missing required field: #{key}"}
-      value when is_binary(value) -> {:ok, value}
-      _ -> {:error, "Field #{key} must be a string"}
-    end
-  end
-
   ## LLM Consultation Helpers
 
   defp consult_llm_for_decision(decision_context) do
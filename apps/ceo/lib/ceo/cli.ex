@@ -2,8 +2,9 @@ defmodule Ceo.CLI do
   @moduledoc """
   Escript entry point for the CEO agent.
 
-  Supports two modes:
+  Supports three modes:
   - MCP mode (default): Runs as MCP server listening on stdin/stdout
+  - HTTP mode (--http [port]): Runs as MCP server over streamable HTTP, serving many clients
   - Autonomous mode (--autonomous): Runs indefinitely processing Redis messages
   """
 
@@ -20,6 +21,14 @@ defmodule Ceo.CLI do
         IO.puts("CEO application started successfully")
         Process.sleep(:infinity)
 
+      ["--http" | rest] ->
+        # Run as a long-lived MCP server over HTTP
+        opts = if port = List.first(rest), do: [port: String.to_integer(port)], else: []
+        {:ok, _} = Ceo.start_http(opts)
+
+        IO.puts("CEO MCP HTTP server started")
+        Process.sleep(:infinity)
+
       _ ->
         # Run as MCP server
         Ceo.start()
@@ -36,7 +36,7 @@ defmodule Ceo.MessageHandler do
     end
 
     # Subscribe to decision events
-    case Redix.PubSub.subscribe(:redix_pubsub, ["decisions:new", "decisions:escalated"], self()) do
+    case MessageBus.subscribe(["decisions:new", "decisions:escalated"]) do
       {:ok, ref} ->
         Logger.info("Subscribed to decision events (ref: #{inspect(ref)})")
       error ->
@@ -50,9 +50,16 @@ defmodule Ceo.MessageHandler do
   def handle_info({:redix_pubsub, _pid, _ref, :message, %{channel: channel, payload: payload}}, state) do
     Logger.info("Received Redis message on channel: #{channel}")
 
-    new_state = case Jason.decode(payload) do
+    new_state = case MessageBus.decode_message(channel, payload) do
       {:ok, message} ->
-        handle_message(channel, message, state)
+        updated_state = handle_message(channel, message, state)
+
+        # Record this role's receipt so the broadcast isn't replayed on restart
+        if channel == "messages:all" and message["db_id"] do
+          MessageBus.mark_message_processed(message["db_id"], :ceo)
+        end
+
+        updated_state
 
       {:error, reason} ->
         Logger.error("Failed to decode message: #{inspect(reason)}")
@@ -138,56 +145,61 @@ defmodule Ceo.MessageHandler do
     tool_name = message["subject"]
     arguments = message["content"] || %{}
 
-    case Ceo.execute_tool(tool_name, arguments) do
-      {:ok, result} ->
-        Logger.info("Tool #{tool_name} executed successfully")
-        send_response(message["from"], message["id"], result)
+    case Ceo.validate_tool_arguments(tool_name, arguments) do
+      :ok ->
+        case tool_result(Ceo.execute_tool(tool_name, arguments)) do
+          {:ok, result} ->
+            Logger.info("Tool #{tool_name} executed successfully")
+            MessageBus.reply(message, tool_response(result))
+            mark_processed(message)
+
+          {:error, reason} ->
+            Logger.error("Tool #{tool_name} failed: #{inspect(reason)}")
+            retry_or_fail(message, reason)
+        end
 
       {:error, reason} ->
-        Logger.error("Tool #{tool_name} failed: #{inspect(reason)}")
-        send_error(message["from"], message["id"], reason)
+        # Invalid arguments won't get better on retry
+        Logger.error("Invalid arguments for #{tool_name}: #{inspect(reason)}")
+        MessageBus.reply(message, {:error, reason})
+        mark_processed(message)
     end
   end
 
-  defp send_response(recipient, request_id, result) do
-    response = %{
-      "id" => "#{request_id}_response",
-      "from" => "ceo",
-      "to" => recipient,
-      "type" => "response",
-      "in_reply_to" => request_id,
-      "result" => result,
-      "timestamp" => DateTime.utc_now() |> DateTime.to_iso8601()
-    }
+  # Leave the request to MessageBus retries (e.g. Ollama briefly down);
+  # only answer the requester once it has been dead-lettered
+  defp retry_or_fail(%{"db_id" => db_id} = message, reason) when not is_nil(db_id) do
+    case MessageBus.mark_message_failed(db_id, reason) do
+      {:ok, %EchoShared.Schemas.Message{next_attempt_at: next_attempt_at}} ->
+        Logger.info("Request #{db_id} will be retried at #{next_attempt_at}")
 
-    channel = "messages:#{recipient}"
+      {:ok, %EchoShared.Schemas.DeadLetter{}} ->
+        MessageBus.reply(message, {:error, reason})
 
-    case Redix.command(:redix, ["PUBLISH", channel, Jason.encode!(response)]) do
-      {:ok, _} ->
-        Logger.info("Sent response to #{recipient} on channel #{channel}")
-      {:error, reason} ->
-        Logger.error("Failed to send response: #{inspect(reason)}")
+      {:error, error} ->
+        Logger.warning("Failed to record failure of message #{db_id}: #{inspect(error)}")
+        MessageBus.reply(message, {:error, reason})
     end
   end
 
-  defp send_error(recipient, request_id, reason) do
-    error = %{
-      "id" => "#{request_id}_error",
-      "from" => "ceo",
-      "to" => recipient,
-      "type" => "error",
-      "in_reply_to" => request_id,
-      "error" => %{
-        "message" => inspect(reason)
-      },
-      "timestamp" => DateTime.utc_now() |> DateTime.to_iso8601()
-    }
+  defp retry_or_fail(message, reason), do: MessageBus.reply(message, {:error, reason})
 
-    channel = "messages:#{recipient}"
-    {:ok, _} = Redix.command(:redix, ["PUBLISH", channel, Jason.encode!(error)])
-    Logger.error("Sent error to #{recipient}")
+  defp mark_processed(%{"db_id" => db_id}) when not is_nil(db_id) do
+    case MessageBus.mark_message_processed(db_id, :ceo) do
+      {:ok, _} -> :ok
+      {:error, reason} -> Logger.warning("Failed to mark message #{db_id} as processed: #{inspect(reason)}")
+    end
   end
 
+  defp mark_processed(_message), do: :ok
+
+  # Agents consume the structured result; the text rendering is for humans
+  defp tool_result({:ok, _text, structured}), do: {:ok, structured}
+  defp tool_result(result), do: result
+
+  defp tool_response(content) when is_list(content), do: %{"content" => content}
+  defp tool_response(result), do: result
+
   defp handle_escalation(message) do
     Logger.warning("Escalation received: #{message["subject"]}")
     # TODO: Handle escalations requiring CEO attention
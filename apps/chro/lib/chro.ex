@@ -35,6 +35,7 @@ defmodule Chro do
 
   alias EchoShared.MessageBus
   alias EchoShared.Schemas.Decision
+  alias EchoShared.Decisions.Escalation
   alias EchoShared.Repo
   alias EchoShared.LLM.DecisionHelper
 
@@ -83,6 +84,20 @@ defmodule Chro do
             }
           },
           required: ["requisition_id", "position", "department", "approved", "rationale"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{type: "string"},
+            requisition_id: %{type: "string"},
+            position: %{type: "string"},
+            department: %{type: "string"},
+            approved: %{type: "boolean"},
+            status: %{type: "string"},
+            rationale: %{type: "string"},
+            salary_range: %{type: "string"}
+          },
+          required: ["decision_id", "requisition_id", "position", "department", "approved", "status", "rationale"]
         }
       },
       %{
@@ -120,6 +135,32 @@ defmodule Chro do
             }
           },
           required: ["employee_role", "review_period", "rating"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            employee_role: %{type: "string"},
+            review_period: %{type: "string"},
+            rating: %{
+              type: "string",
+              enum: ["exceeds_expectations", "meets_expectations", "needs_improvement", "unsatisfactory"]
+            },
+            strengths: %{type: "array", items: %{type: "string"}},
+            areas_for_improvement: %{type: "array", items: %{type: "string"}},
+            promotion_recommended: %{type: "boolean"},
+            reviewed_by: %{type: "string"},
+            reviewed_at: %{type: "string"}
+          },
+          required: [
+            "employee_role",
+            "review_period",
+            "rating",
+            "strengths",
+            "areas_for_improvement",
+            "promotion_recommended",
+            "reviewed_by",
+            "reviewed_at"
+          ]
         }
       },
       %{
@@ -147,6 +188,18 @@ defmodule Chro do
             }
           },
           required: ["purpose", "amount", "category"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            purpose: %{type: "string"},
+            amount: %{type: "number"},
+            category: %{type: "string"},
+            duration: %{type: "string"},
+            allocated_by: %{type: "string"},
+            allocated_at: %{type: "string"}
+          },
+          required: ["purpose", "amount", "category", "allocated_by", "allocated_at"]
         }
       },
       %{
@@ -184,6 +237,27 @@ defmodule Chro do
             }
           },
           required: ["issue_id", "issue_type", "resolution"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{type: "string"},
+            issue_id: %{type: "string"},
+            issue_type: %{type: "string"},
+            parties_involved: %{type: "array", items: %{type: "string"}},
+            resolution: %{type: "string"},
+            action_items: %{type: "array", items: %{type: "string"}},
+            escalated: %{type: "boolean"}
+          },
+          required: [
+            "decision_id",
+            "issue_id",
+            "issue_type",
+            "parties_involved",
+            "resolution",
+            "action_items",
+            "escalated"
+          ]
         }
       },
       %{
@@ -201,6 +275,17 @@ defmodule Chro do
               description: "Include detailed breakdowns by department (default: true)"
             }
           }
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            time_range: %{type: "string"},
+            hr_decisions: %{type: "integer"},
+            hiring_approvals: %{type: "integer"},
+            performance_reviews: %{type: "integer"},
+            overall_status: %{type: "string"}
+          },
+          required: ["time_range", "hr_decisions", "hiring_approvals", "performance_reviews", "overall_status"]
         }
       },
       %{
@@ -232,6 +317,20 @@ defmodule Chro do
             }
           },
           required: ["issue_id", "reason", "urgency"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            issue_id: %{type: "string"},
+            decision_id: %{type: "string"},
+            status: %{type: "string"},
+            escalated_to: %{type: "string"},
+            reason: %{type: "string"},
+            urgency: %{type: "string"},
+            recommendation: %{type: "string"},
+            confidential: %{type: "boolean"}
+          },
+          required: ["issue_id", "decision_id", "status", "escalated_to", "reason", "urgency", "confidential"]
         }
       },
       %{
@@ -255,6 +354,15 @@ defmodule Chro do
             }
           },
           required: ["query_type", "question"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            query_type: %{type: "string"},
+            llm_enabled: %{type: "boolean"},
+            response: %{type: "string"}
+          },
+          required: ["query_type", "llm_enabled"]
         }
       },
       %{
@@ -290,19 +398,36 @@ defmodule Chro do
             }
           },
           required: ["question"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            response: %{type: "string"},
+            session_id: %{type: "string"},
+            turn_count: %{type: "integer"},
+            estimated_tokens: %{type: "integer"},
+            model: %{type: "string"},
+            agent: %{type: "string"},
+            warnings: %{type: "array", items: %{type: "string"}}
+          },
+          required: ["response", "session_id", "turn_count", "model", "agent"]
         }
       }
     ]
   end
 
   @impl true
-  def execute_tool("approve_hiring_request", args) do
-    with {:ok, requisition_id} <- validate_required_string(args, "requisition_id"),
-         {:ok, position} <- validate_required_string(args, "position"),
-         {:ok, department} <- validate_required_string(args, "department"),
-         {:ok, approved} <- validate_required_boolean(args, "approved"),
-         {:ok, rationale} <- validate_required_string(args, "rationale"),
-         {:ok, decision} <- record_hiring_decision(requisition_id, position, department, approved, rationale, args) do
+  def execute_tool(
+        "approve_hiring_request",
+        %{
+          "requisition_id" => requisition_id,
+          "position" => position,
+          "department" => department,
+          "approved" => approved,
+          "rationale" => rationale
+        } = args
+      ) do
+    with {:ok, decision} <- record_hiring_decision(requisition_id, position, department, approved, rationale, args) do
 
       # Notify relevant departments
       notify_hiring_decision(decision, department, approved)
@@ -320,17 +445,31 @@ defmodule Chro do
       The #{department} department has been notified of the decision.
       """
 
-      {:ok, result}
+      {:ok, result,
+       %{
+         decision_id: decision.id,
+         requisition_id: requisition_id,
+         position: position,
+         department: department,
+         approved: approved,
+         status: decision.status,
+         rationale: rationale,
+         salary_range: args["salary_range"]
+       }}
     else
       {:error, reason} -> {:error, "Failed to process hiring request: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("conduct_performance_review", args) do
-    with {:ok, employee_role} <- validate_required_string(args, "employee_role"),
-         {:ok, review_period} <- validate_required_string(args, "review_period"),
-         {:ok, rating} <- validate_required_string(args, "rating"),
-         {:ok, review} <- record_performance_review(employee_role, review_period, rating, args) do
+  def execute_tool(
+        "conduct_performance_review",
+        %{
+          "employee_role" => employee_role,
+          "review_period" => review_period,
+          "rating" => rating
+        } = args
+      ) do
+    with {:ok, review} <- record_performance_review(employee_role, review_period, rating, args) do
 
       # Notify employee's manager
       notify_performance_review(review, employee_role)
@@ -352,17 +491,17 @@ defmodule Chro do
       The performance review has been recorded and relevant parties have been notified.
       """
 
-      {:ok, result}
+      {:ok, result, review}
     else
       {:error, reason} -> {:error, "Failed to conduct performance review: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("allocate_hr_budget", args) do
-    with {:ok, purpose} <- validate_required_string(args, "purpose"),
-         {:ok, amount} <- validate_required_number(args, "amount"),
-         {:ok, category} <- validate_required_string(args, "category"),
-         :ok <- validate_budget_authority(amount),
+  def execute_tool(
+        "allocate_hr_budget",
+        %{"purpose" => purpose, "amount" => amount, "category" => category} = args
+      ) do
+    with :ok <- validate_budget_authority(amount),
          {:ok, allocation} <- create_budget_allocation(purpose, amount, category, args) do
 
       # Notify CEO of budget allocation
@@ -385,7 +524,7 @@ defmodule Chro do
       The budget has been allocated and CEO has been notified.
       """
 
-      {:ok, result}
+      {:ok, result, allocation}
     else
       {:error, :budget_limit_exceeded} ->
         {:error, "Budget amount exceeds CHRO autonomous authority ($300,000). Requires CEO approval."}
@@ -395,11 +534,11 @@ defmodule Chro do
     end
   end
 
-  def execute_tool("resolve_hr_issue", args) do
-    with {:ok, issue_id} <- validate_required_string(args, "issue_id"),
-         {:ok, issue_type} <- validate_required_string(args, "issue_type"),
-         {:ok, resolution} <- validate_required_string(args, "resolution"),
-         {:ok, issue_record} <- record_hr_issue_resolution(issue_id, issue_type, resolution, args) do
+  def execute_tool(
+        "resolve_hr_issue",
+        %{"issue_id" => issue_id, "issue_type" => issue_type, "resolution" => resolution} = args
+      ) do
+    with {:ok, issue_record} <- record_hr_issue_resolution(issue_id, issue_type, resolution, args) do
 
       # Check if escalation is needed
       if args["requires_escalation"] do
@@ -422,7 +561,16 @@ defmodule Chro do
       The issue resolution has been recorded.
       """
 
-      {:ok, result}
+      {:ok, result,
+       %{
+         decision_id: issue_record.id,
+         issue_id: issue_id,
+         issue_type: issue_type,
+         parties_involved: args["parties_involved"] || [],
+         resolution: resolution,
+         action_items: args["action_items"] || [],
+         escalated: args["requires_escalation"] == true
+       }}
     else
       {:error, reason} -> {:error, "Failed to resolve HR issue: #{inspect(reason)}"}
     end
@@ -434,81 +582,85 @@ defmodule Chro do
 
     with {:ok, metrics} <- get_team_health_metrics(time_range, include_details) do
       result = format_team_health_report(metrics, time_range, include_details)
-      {:ok, result}
+      {:ok, result, Map.put(metrics, :overall_status, "Healthy")}
     else
       {:error, reason} -> {:error, "Failed to retrieve team health metrics: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("escalate_to_ceo", args) do
-    with {:ok, issue_id} <- validate_required_string(args, "issue_id"),
-         {:ok, reason} <- validate_required_string(args, "reason"),
-         {:ok, urgency} <- validate_required_string(args, "urgency"),
-         {:ok, escalation} <- create_hr_escalation(issue_id, reason, urgency, args) do
-
-      # Send escalation to CEO
-      MessageBus.publish_message(
-        :chro,
-        :ceo,
-        :escalation,
-        "HR Issue Escalation: #{issue_id}",
-        escalation
-      )
+  def execute_tool(
+        "escalate_to_ceo",
+        %{"issue_id" => issue_id, "reason" => reason, "urgency" => urgency} = args
+      ) do
+    confidential = args["confidential"] != false
 
+    # The issue is escalated as an HR decision, routed up to the CEO, who is notified
+    with {:ok, decision} <- open_hr_issue(issue_id, confidential, args),
+         {:ok, escalated} <- Escalation.escalate(decision, reason, from: :chro, to: :ceo, urgency: urgency) do
       result = """
       HR Issue Escalated to CEO
 
       Issue ID: #{issue_id}
+      Decision ID: #{escalated.id}
       Urgency: #{urgency}
-      Confidential: #{if args["confidential"] != false, do: "Yes", else: "No"}
+      Confidential: #{if confidential, do: "Yes", else: "No"}
       Reason: #{reason}
       CHRO Recommendation: #{args["recommendation"] || "None provided"}
 
       The issue has been escalated to the CEO for review and decision.
       """
 
-      {:ok, result}
+      {:ok, result,
+       %{
+         issue_id: issue_id,
+         decision_id: escalated.id,
+         status: escalated.status,
+         escalated_to: escalated.escalated_to,
+         reason: reason,
+         urgency: urgency,
+         recommendation: args["recommendation"],
+         confidential: confidential
+       }}
     else
       {:error, reason} -> {:error, "Failed to escalate HR issue: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("ai_consult", args) do
-    with {:ok, query_type} <- validate_required_string(args, "query_type"),
-         {:ok, question} <- validate_required_string(args, "question") do
-      context = args["context"] || %{}
-
-      result = case query_type do
-        "decision_analysis" ->
-          decision_context = Map.merge(context, %{
-            decision_type: context["decision_type"] || "general",
-            context: question
-          })
-          DecisionHelper.analyze_decision(:chro, decision_context)
-
-        "option_evaluation" ->
-          evaluation_context = %{
-            question: question,
-            options: context["options"] || [],
-            criteria: context["criteria"]
-          }
-          DecisionHelper.evaluate_options(:chro, evaluation_context)
+  def execute_tool("ai_consult", %{"query_type" => query_type, "question" => question} = args) do
+    context = args["context"] || %{}
+
+    result = case query_type do
+      "decision_analysis" ->
+        decision_context = Map.merge(context, %{
+          decision_type: context["decision_type"] || "general",
+          context: question
+        })
+        DecisionHelper.analyze_decision(:chro, decision_context)
+
+      "option_evaluation" ->
+        evaluation_context = %{
+          question: question,
+          options: context["options"] || [],
+          criteria: context["criteria"]
+        }
+        DecisionHelper.evaluate_options(:chro, evaluation_context)
 
-        "question" ->
-          DecisionHelper.consult(:chro, question, context["additional_context"])
+      "question" ->
+        DecisionHelper.consult(:chro, question, context["additional_context"])
 
-        _ ->
-          {:error, "Unknown query type: #{query_type}"}
-      end
+      _ ->
+        {:error, "Unknown query type: #{query_type}"}
+    end
 
-      case result do
-        {:ok, response} ->
-          {:ok, "AI Consultation Result:\n\n" <> response}
-        {:error, :llm_disabled} ->
-          {:ok, "AI consultation disabled. Enable with CHRO_LLM_ENABLED=true"}
-        {:error, reason} ->
-          {:error, "AI consultation failed: #{inspect(reason)}"}
-      end
+    case result do
+      {:ok, response} ->
+        {:ok, "AI Consultation Result:\n\n" <> response,
+         %{query_type: query_type, llm_enabled: true, response: response}}
+      {:error, :llm_disabled} ->
+        {:ok, "AI consultation disabled. Enable with CHRO_LLM_ENABLED=true",
+         %{query_type: query_type, llm_enabled: false}}
+      {:error, reason} ->
+        {:error, "AI consultation failed: #{inspect(reason)}"}
     end
   end
 
@@ -621,18 +773,35 @@ defmodule Chro do
     |> Repo.insert()
   end
 
-  defp create_hr_escalation(issue_id, reason, urgency, args) do
-    escalation = %{
-      issue_id: issue_id,
-      reason: reason,
-      urgency: urgency,
-      recommendation: args["recommendation"],
-      confidential: args["confidential"] != false,
-      escalated_by: "chro",
-      escalated_at: DateTime.utc_now()
-    }
+  # The open decision for an HR issue, or a new one
+  defp open_hr_issue(issue_id, confidential, args) do
+    open =
+      Repo.one(
+        from d in Decision,
+          where: d.decision_type == "hr_issue_resolution" and d.status in [:pending, :escalated] and
+                fragment("?->>'issue_id' = ?", d.context, ^issue_id),
+          limit: 1
+      )
 
-    {:ok, escalation}
+    case open do
+      %Decision{} = decision ->
+        {:ok, decision}
+
+      nil ->
+        %Decision{}
+        |> Decision.changeset(%{
+          decision_type: "hr_issue_resolution",
+          initiator_role: "chro",
+          mode: :hierarchical,
+          status: :pending,
+          context: %{
+            issue_id: issue_id,
+            confidential: confidential,
+            recommendation: args["recommendation"]
+          }
+        })
+        |> Repo.insert()
+    end
   end
 
   defp get_team_health_metrics(time_range, _include_details) do
@@ -748,30 +917,6 @@ defmodule Chro do
     |> String.replace(~r/\B(?=(\d{3})+(?!\d))/, ",")
   end
 
-  defp validate_required_number(args, key) do
-    case Map.get(args, key) do
-      nil -> {:error, "Missing required field: #{key}"}
-      value when is_number(value) -> {:ok, value}
-      _ -> {:error, "Field #{key} must be a number"}
-    end
-  end
-
-  defp validate_required_boolean(args, key) do
-    case Map.get(args, key) do
-      nil -> {:error, "Missing required field: #{key}"}
-      value when is_boolean(value) -> {:ok, value}
-      _ -> {:error, "Field #{key} must be a boolean"}
-    end
-  end
-
-  defp validate_required_string(args, key) do
-    case Map.get(args, key) do
-      nil -> {:error, "Missing required field: #{key}"}
-      value when is_binary(value) -> {:ok, value}
-      _ -> {:error, "Field #{key} must be a string"}
-    end
-  end
-
   defp format_session_response(result) do
     model = EchoShared.LLM.Config.get_model(:chro)
 
@@ -35,6 +35,7 @@ defmodule Cto do
 
   alias EchoShared.MessageBus
   alias EchoShared.Schemas.Decision
+  alias EchoShared.Decisions.Escalation
   alias EchoShared.Repo
   alias EchoShared.LLM.DecisionHelper
 
@@ -76,6 +77,18 @@ defmodule Cto do
             }
           },
           required: ["proposal_id", "rationale", "approved"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{type: "string"},
+            proposal_id: %{type: "string"},
+            approved: %{type: "boolean"},
+            status: %{type: "string"},
+            rationale: %{type: "string"},
+            conditions: %{type: "array", items: %{type: "string"}}
+          },
+          required: ["decision_id", "proposal_id", "approved", "status", "rationale", "conditions"]
         }
       },
       %{
@@ -102,6 +115,18 @@ defmodule Cto do
             }
           },
           required: ["recipient", "amount", "purpose"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            recipient: %{type: "string"},
+            amount: %{type: "number"},
+            purpose: %{type: "string"},
+            duration: %{type: "string"},
+            allocated_by: %{type: "string"},
+            allocated_at: %{type: "string"}
+          },
+          required: ["recipient", "amount", "purpose", "allocated_by", "allocated_at"]
         }
       },
       %{
@@ -131,6 +156,18 @@ defmodule Cto do
             }
           },
           required: ["architecture_id", "components"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            architecture_id: %{type: "string"},
+            components: %{type: "array", items: %{type: "string"}},
+            concerns: %{type: "array", items: %{type: "string"}},
+            recommendations: %{type: "array", items: %{type: "string"}},
+            reviewed_by: %{type: "string"},
+            reviewed_at: %{type: "string"}
+          },
+          required: ["architecture_id", "components", "concerns", "recommendations", "reviewed_by", "reviewed_at"]
         }
       },
       %{
@@ -162,6 +199,19 @@ defmodule Cto do
             }
           },
           required: ["change_id", "change_type", "approved", "rationale"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{type: "string"},
+            change_id: %{type: "string"},
+            change_type: %{type: "string"},
+            approved: %{type: "boolean"},
+            status: %{type: "string"},
+            rationale: %{type: "string"},
+            risk_assessment: %{type: "string"}
+          },
+          required: ["decision_id", "change_id", "change_type", "approved", "status", "rationale"]
         }
       },
       %{
@@ -179,6 +229,17 @@ defmodule Cto do
               description: "Include detailed breakdowns (default: true)"
             }
           }
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            time_range: %{type: "string"},
+            technical_decisions: %{type: "integer"},
+            approved_decisions: %{type: "integer"},
+            approval_rate: %{type: "number", minimum: 0, maximum: 100},
+            overall_status: %{type: "string", enum: ["Healthy", "Needs Attention"]}
+          },
+          required: ["time_range", "technical_decisions", "approved_decisions", "approval_rate", "overall_status"]
         }
       },
       %{
@@ -206,6 +267,18 @@ defmodule Cto do
             }
           },
           required: ["decision_id", "reason", "urgency"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{type: "string"},
+            status: %{type: "string"},
+            escalated_to: %{type: "string"},
+            urgency: %{type: "string"},
+            reason: %{type: "string"},
+            recommendation: %{type: "string"}
+          },
+          required: ["decision_id", "status", "escalated_to", "urgency", "reason"]
         }
       },
       %{
@@ -229,6 +302,15 @@ defmodule Cto do
             }
           },
           required: ["query_type", "question"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            query_type: %{type: "string"},
+            llm_enabled: %{type: "boolean"},
+            response: %{type: "string"}
+          },
+          required: ["query_type", "llm_enabled"]
         }
       },
       %{
@@ -264,17 +346,30 @@ defmodule Cto do
             }
           },
           required: ["question"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            response: %{type: "string"},
+            session_id: %{type: "string"},
+            turn_count: %{type: "integer"},
+            estimated_tokens: %{type: "integer"},
+            model: %{type: "string"},
+            agent: %{type: "string"},
+            warnings: %{type: "array", items: %{type: "string"}}
+          },
+          required: ["response", "session_id", "turn_count", "model", "agent"]
         }
       }
     ]
   end
 
   @impl true
-  def execute_tool("approve_technical_proposal", args) do
-    with {:ok, proposal_id} <- validate_required_string(args, "proposal_id"),
-         {:ok, rationale} <- validate_required_string(args, "rationale"),
-         {:ok, approved} <- validate_required_boolean(args, "approved"),
-         {:ok, decision} <- record_technical_decision(proposal_id, approved, rationale, args) do
+  def execute_tool(
+        "approve_technical_proposal",
+        %{"proposal_id" => proposal_id, "rationale" => rationale, "approved" => approved} = args
+      ) do
+    with {:ok, decision} <- record_technical_decision(proposal_id, approved, rationale, args) do
 
       # Notify relevant parties
       notify_technical_decision(decision, approved)
@@ -290,17 +385,25 @@ defmodule Cto do
       The decision has been recorded and relevant teams have been notified.
       """
 
-      {:ok, result}
+      {:ok, result,
+       %{
+         decision_id: decision.id,
+         proposal_id: proposal_id,
+         approved: approved,
+         status: decision.status,
+         rationale: rationale,
+         conditions: args["conditions"] || []
+       }}
     else
       {:error, reason} -> {:error, "Failed to process proposal: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("allocate_engineering_budget", args) do
-    with {:ok, recipient} <- validate_required_string(args, "recipient"),
-         {:ok, amount} <- validate_required_number(args, "amount"),
-         {:ok, purpose} <- validate_required_string(args, "purpose"),
-         :ok <- validate_budget_authority(amount),
+  def execute_tool(
+        "allocate_engineering_budget",
+        %{"recipient" => recipient, "amount" => amount, "purpose" => purpose} = args
+      ) do
+    with :ok <- validate_budget_authority(amount),
          {:ok, allocation} <- create_budget_allocation(recipient, amount, purpose, args) do
 
       # Send budget notification
@@ -323,7 +426,7 @@ defmodule Cto do
       The budget has been allocated and stakeholders have been notified.
       """
 
-      {:ok, result}
+      {:ok, result, allocation}
     else
       {:error, :budget_limit_exceeded} ->
         {:error, "Budget amount exceeds CTO autonomous authority ($500,000). Requires CEO approval."}
@@ -333,10 +436,11 @@ defmodule Cto do
     end
   end
 
-  def execute_tool("review_architecture", args) do
-    with {:ok, architecture_id} <- validate_required_string(args, "architecture_id"),
-         {:ok, components} <- validate_required(args, "components"),
-         {:ok, _review} <- create_architecture_review(architecture_id, components, args) do
+  def execute_tool(
+        "review_architecture",
+        %{"architecture_id" => architecture_id, "components" => components} = args
+      ) do
+    with {:ok, review} <- create_architecture_review(architecture_id, components, args) do
 
       result = """
       Architecture Review Complete
@@ -353,18 +457,22 @@ defmodule Cto do
       The architecture review has been recorded and shared with the engineering team.
       """
 
-      {:ok, result}
+      {:ok, result, review}
     else
       {:error, reason} -> {:error, "Failed to review architecture: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("approve_infrastructure_change", args) do
-    with {:ok, change_id} <- validate_required_string(args, "change_id"),
-         {:ok, change_type} <- validate_required_string(args, "change_type"),
-         {:ok, approved} <- validate_required_boolean(args, "approved"),
-         {:ok, rationale} <- validate_required_string(args, "rationale"),
-         {:ok, change_record} <- record_infrastructure_change(change_id, change_type, approved, rationale, args) do
+  def execute_tool(
+        "approve_infrastructure_change",
+        %{
+          "change_id" => change_id,
+          "change_type" => change_type,
+          "approved" => approved,
+          "rationale" => rationale
+        } = args
+      ) do
+    with {:ok, change_record} <- record_infrastructure_change(change_id, change_type, approved, rationale, args) do
 
       # Notify operations and engineering teams
       notify_infrastructure_change(change_record, approved)
@@ -381,7 +489,16 @@ defmodule Cto do
       The infrastructure change decision has been communicated to relevant teams.
       """
 
-      {:ok, result}
+      {:ok, result,
+       %{
+         decision_id: change_record.id,
+         change_id: change_id,
+         change_type: change_type,
+         approved: approved,
+         status: change_record.status,
+         rationale: rationale,
+         risk_assessment: args["risk_assessment"]
+       }}
     else
       {:error, reason} -> {:error, "Failed to process infrastructure change: #{inspect(reason)}"}
     end
@@ -393,28 +510,21 @@ defmodule Cto do
 
     with {:ok, metrics} <- get_engineering_metrics(time_range, include_details) do
       result = format_engineering_metrics(metrics, time_range, include_details)
-      {:ok, result}
+      {:ok, result, Map.put(metrics, :overall_status, engineering_status(metrics))}
     else
       {:error, reason} -> {:error, "Failed to retrieve metrics: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("escalate_to_ceo", args) do
-    with {:ok, decision_id} <- validate_required_string(args, "decision_id"),
-         {:ok, reason} <- validate_required_string(args, "reason"),
-         {:ok, urgency} <- validate_required_string(args, "urgency"),
-         {:ok, decision} <- load_decision(decision_id),
-         {:ok, escalated} <- escalate_decision_to_ceo(decision, reason, urgency, args) do
-
-      # Send escalation to CEO
-      MessageBus.publish_message(
-        :cto,
-        :ceo,
-        :escalation,
-        "Technical Decision Escalation: #{decision_id}",
-        escalated
-      )
-
+  def execute_tool(
+        "escalate_to_ceo",
+        %{"decision_id" => decision_id, "reason" => reason, "urgency" => urgency} = args
+      ) do
+    # Routed through the reporting chain up to the CEO, who is notified
+    with {:ok, decision} <- load_decision(decision_id),
+         context = Map.put(decision.context || %{}, "recommendation", args["recommendation"]),
+         {:ok, escalated} <-
+           Escalation.escalate(decision, reason, from: :cto, to: :ceo, urgency: urgency, context: context) do
       result = """
       Decision Escalated to CEO
 
@@ -426,72 +536,75 @@ defmodule Cto do
       The decision has been escalated to the CEO for final approval.
       """
 
-      {:ok, result}
+      {:ok, result,
+       %{
+         decision_id: decision_id,
+         status: escalated.status,
+         escalated_to: escalated.escalated_to,
+         urgency: urgency,
+         reason: reason,
+         recommendation: args["recommendation"]
+       }}
     else
       {:error, reason} -> {:error, "Failed to escalate decision: #{inspect(reason)}"}
     end
   end
 
-  def execute_tool("ai_consult", args) do
-    with {:ok, query_type} <- validate_required_string(args, "query_type"),
-         {:ok, question} <- validate_required_string(args, "question") do
-
-      context = args["context"] || %{}
-
-      result = case query_type do
-        "decision_analysis" ->
-          decision_context = Map.merge(context, %{
-            decision_type: context["decision_type"] || "technical",
-            context: question
-          })
-          consult_llm_for_decision(decision_context)
-
-        "option_evaluation" ->
-          evaluation_context = %{
-            question: question,
-            options: context["options"] || [],
-            criteria: context["criteria"]
-          }
-          consult_llm_for_evaluation(evaluation_context)
+  def execute_tool("ai_consult", %{"query_type" => query_type, "question" => question} = args) do
+    context = args["context"] || %{}
 
-        "architecture_review" ->
-          consult_llm_simple(question, context["additional_context"])
+    result = case query_type do
+      "decision_analysis" ->
+        decision_context = Map.merge(context, %{
+          decision_type: context["decision_type"] || "technical",
+          context: question
+        })
+        consult_llm_for_decision(decision_context)
 
-        "code_generation" ->
-          task = %{
-            description: question,
-            language: context["language"],
-            requirements: context["requirements"],
-            constraints: context["constraints"]
-          }
-          consult_llm_for_code(task)
+      "option_evaluation" ->
+        evaluation_context = %{
+          question: question,
+          options: context["options"] || [],
+          criteria: context["criteria"]
+        }
+        consult_llm_for_evaluation(evaluation_context)
+
+      "architecture_review" ->
+        consult_llm_simple(question, context["additional_context"])
+
+      "code_generation" ->
+        task = %{
+          description: question,
+          language: context["language"],
+          requirements: context["requirements"],
+          constraints: context["constraints"]
+        }
+        consult_llm_for_code(task)
 
-        "technical_question" ->
-          consult_llm_simple(question, context["additional_context"])
+      "technical_question" ->
+        consult_llm_simple(question, context["additional_context"])
 
-        _ ->
-          {:error, "Unknown query type: #{query_type}"}
-      end
+      _ ->
+        {:error, "Unknown query type: #{query_type}"}
+    end
 
-      case result do
-        {:ok, response} ->
-          {:ok, """
-          AI Consultation Result (Query Type: #{query_type})
+    case result do
+      {:ok, response} ->
+        {:ok, """
+        AI Consultation Result (Query Type: #{query_type})
 
-          #{response}
+        #{response}
 
-          ---
-          Note: This is AI-generated technical advice. Validate architecture decisions with team and organizational standards.
-          """}
+        ---
+        Note: This is AI-generated technical advice. Validate architecture decisions with team and organizational standards.
+        """, %{query_type: query_type, llm_enabled: true, response: response}}
 
-        {:error, :llm_disabled} ->
-          {:ok, "AI consultation is currently disabled for CTO role. Enable with CTO_LLM_ENABLED=true"}
+      {:error, :llm_disabled} ->
+        {:ok, "AI consultation is currently disabled for CTO role. Enable with CTO_LLM_ENABLED=true",
+         %{query_type: query_type, llm_enabled: false}}
 
-        {:error, reason} ->
-          {:error, "AI consultation failed: #{inspect(reason)}"}
-      end
-    else
-      {:error, reason} -> {:error, "Invalid ai_consult request: #{inspect(reason)}"}
+      {:error, reason} ->
+        {:error, "AI consultation failed: #{inspect(reason)}"}
     end
   end
 
@@ -606,24 +719,6 @@ defmodule Cto do
     |> Repo.insert()
   end
 
-  defp escalate_decision_to_ceo(decision, reason, urgency, args) do
-    attrs = %{
-      status: :escalated,
-      metadata: Map.merge(decision.metadata || %{}, %{
-        escalated_by: "cto",
-        escalated_to: "ceo",
-        reason: reason,
-        urgency: urgency,
-        recommendation: args["recommendation"],
-        escalated_at: DateTime.utc_now()
-      })
-    }
-
-    decision
-    |> Decision.changeset(attrs)
-    |> Repo.update()
-  end
-
   defp get_engineering_metrics(time_range, _include_details) do
     hours = parse_time_range(time_range)
     cutoff = DateTime.utc_now() |> DateTime.add(-hours * 3600, :second)
@@ -663,10 +758,14 @@ defmodule Cto do
       - Decisions approved: #{metrics.approved_decisions}
       - Approval rate: #{metrics.approval_rate}%
 
-    Overall Status: #{if metrics.approval_rate >= 70, do: "Healthy", else: "Needs Attention"}
+    Overall Status: #{engineering_status(metrics)}
     """
   end
 
+  defp engineering_status(metrics) do
+    if metrics.approval_rate >= 70, do: "Healthy", else: "Needs Attention"
+  end
+
   defp validate_budget_authority(amount) do
     limit = Application.get_env(:cto, :autonomous_budget_limit, 500_000)
 
@@ -686,6 +785,7 @@ defmodule Cto do
     )
 
     MessageBus.publish_decision_event(
+      :cto,
       :completed,
       %{decision_id: decision.id, type: "technical_proposal"}
     )
@@ -732,37 +832,6 @@ defmodule Cto do
     |> String.replace(~r/\B(?=(\d{3})+(?!\d))/, ",")
   end
 
-  defp validate_required_number(args, key) do
-    case Map.get(args, key) do
-      nil -> {:error, "Missing required field: #{key}"}
-      value when is_number(value) -> {:ok, value}
-      _ -> {:error, "Field #{key} must be a number"}
-    end
-  end
-
-  defp validate_required_boolean(args, key) do
-    case Map.get(args, key) do
-      nil -> {:error, "Missing required field: #{key}"}
-      value when is_boolean(value) -> {:ok, value}
-      _ -> {:error, "Field #{key} must be a boolean"}
-    end
-  end
-
-  defp validate_required(args, key) do
-    case Map.get(args, key) do
-      nil -> {:error, "Missing required field: #{key}"}
-      value -> {:ok, value}
-    end
-  end
-
-  defp validate_required_string(args, key) do
-    case Map.get(args, key) do
-      nil -> {:error, "Missing required field: #{key}"}
-      value when is_binary(value) -> {:ok, value}
-      _ -> {:error, "Field #{key} must be a string"}
-    end
-  end
-
   ## LLM Consultation Helpers
 
   defp consult_llm_for_decision(decision_context) do
@@ -11,11 +11,6 @@ defmodule Cto.Application do
     Logger.info("Starting CTO Agent...")
 
     children = [
-      %{
-        id: Redix.PubSub,
-        start: {Redix.PubSub, :start_link, [[name: :redix_pubsub, host: redis_host(), port: redis_port()]]}
-      },
-
       # Heartbeat worker
       {EchoShared.HeartbeatWorker, role: :cto, metadata: %{version: "1.0.0"}},
 
@@ -26,7 +21,4 @@ defmodule Cto.Application do
     opts = [strategy: :one_for_one, name: Cto.Supervisor]
     Supervisor.start_link(children, opts)
   end
-
-  defp redis_host, do: System.get_env("REDIS_HOST", "localhost")
-  defp redis_port, do: String.to_integer(System.get_env("REDIS_PORT", "6383"))
 end
@@ -36,76 +36,24 @@ defmodule Cto.MessageHandler do
     {:ok, _} = MessageBus.subscribe_to_role(:cto)
 
     # Subscribe to decision events
-    Redix.PubSub.subscribe(:redix_pubsub, ["decisions:new", "decisions:escalated"], self())
-
-    # Initialize state immediately to ensure GenServer starts successfully
-    initial_state = %{recent_broadcasts: MapSet.new()}
-
-    # Fix #5: Catch up on missed broadcasts during downtime (with resilient error handling)
-    # Use Task to defer database query - don't block initialization
-    # If database is unavailable during startup, agent will still start and catch up later
-    task = Task.async(fn ->
-      try do
-        missed_broadcasts = MessageBus.fetch_unread_broadcasts(:cto)
-        Logger.info("CTO catching up on #{length(missed_broadcasts)} missed broadcasts")
-        missed_broadcasts
-      rescue
-        error ->
-          Logger.warning("CTO couldn't fetch missed broadcasts during init (database may be busy): #{inspect(error)}")
-          Logger.info("CTO will process new messages from now on. Missed messages can be caught up later via health check.")
-          []
-      end
-    end)
-
-    # Wait briefly for database query, but don't fail if it times out
-    missed_broadcasts = case Task.yield(task, 2000) || Task.shutdown(task) do
-      {:ok, broadcasts} -> broadcasts
-      nil ->
-        Logger.warning("CTO database catchup timed out during init - starting with clean state")
-        []
-    end
-
-    # Process missed broadcasts with state tracking (only if we got any)
-    final_state = Enum.reduce(missed_broadcasts, initial_state, fn msg, acc_state ->
-      # Convert DB message to map format expected by handle_message
-      message_map = %{
-        "id" => to_string(msg.id),
-        "from" => msg.from_role,
-        "to" => msg.to_role,
-        "type" => msg.type,
-        "subject" => msg.subject,
-        "content" => msg.content
-      }
-
-      # P0 Bug Fix #1: Pass state parameter (3-arg version)
-      new_state = handle_message("messages:all", message_map, acc_state)
-
-      # P0 Bug Fix #2: Mark message as read after processing (wrap in try/rescue)
-      try do
-        MessageBus.mark_message_processed(msg.id)
-      rescue
-        error -> Logger.debug("Couldn't mark message #{msg.id} as processed: #{inspect(error)}")
-      end
-
-      new_state
-    end)
+    MessageBus.subscribe(["decisions:new", "decisions:escalated"])
 
-    # Return final state after catchup (or empty state if catchup failed)
-    {:ok, final_state}
+    # Missed broadcasts are replayed by MessageBus.subscribe_to_role/1
+    {:ok, %{recent_broadcasts: MapSet.new()}}
   end
 
   @impl true
   def handle_info({:redix_pubsub, _pid, _ref, :message, %{channel: channel, payload: payload}}, state) do
     Logger.info("CTO received Redis message on channel: #{channel}")
 
-    new_state = case Jason.decode(payload) do
+    new_state = case MessageBus.decode_message(channel, payload) do
       {:ok, message} ->
         updated_state = handle_message(channel, message, state)
 
         # P1 Fix: Mark Redis messages as processed in DB to prevent re-processing on restart
-        # This complements the catchup flow which already marks messages as processed
+        # Broadcasts get a CTO receipt, so other agents still catch up on them
         if db_id = message["db_id"] do
-          case MessageBus.mark_message_processed(db_id) do
+          case MessageBus.mark_message_processed(db_id, :cto) do
             {:ok, _} -> Logger.debug("Marked message #{db_id} as processed")
             {:error, reason} -> Logger.warning("Failed to mark message #{db_id} as processed: #{inspect(reason)}")
           end
@@ -241,13 +189,13 @@ defmodule Cto.MessageHandler do
     case consult_llm_for_response(message, memories) do
       {:ok, response_text} ->
         Logger.info("LLM generated response: #{response_text}")
-        send_response(message["from"], message["id"], message["subject"], response_text)
+        MessageBus.reply(message, response_text)
 
       {:error, reason} ->
         Logger.error("LLM consultation failed: #{inspect(reason)}")
         # Send fallback response
         fallback_response = "I received your message about '#{message["subject"]}'. As CTO, I'm reviewing this request and will provide a detailed response shortly."
-        send_response(message["from"], message["id"], message["subject"], fallback_response)
+        MessageBus.reply(message, fallback_response)
     end
   end
 
@@ -321,31 +269,6 @@ defmodule Cto.MessageHandler do
     end
   end
 
-  defp send_response(recipient, request_id, subject, response_text) do
-    response = %{
-      "id" => "msg_#{:erlang.unique_integer([:positive])}",
-      "from" => "cto",
-      "to" => recipient,
-      "type" => "response",
-      "in_reply_to" => request_id,
-      "subject" => "Re: #{subject}",
-      "content" => response_text,
-      "timestamp" => DateTime.utc_now() |> DateTime.to_iso8601()
-    }
-
-    channel = "messages:#{recipient}"
-
-    case Redix.command(:redix, ["PUBLISH", channel, Jason.encode!(response)]) do
-      {:ok, subscribers} ->
-        Logger.info("✓ Sent response to #{recipient} on #{channel} (#{subscribers} subscribers)")
-        # Also save to database
-        MessageBus.store_message_in_db("cto", recipient, "response", "Re: #{subject}", %{content: response_text})
-
-      {:error, reason} ->
-        Logger.error("Failed to send response: #{inspect(reason)}")
-    end
-  end
-
   defp handle_escalation(message) do
     Logger.warning("Escalation received: #{message["subject"]}")
     # TODO: Handle escalations requiring CTO attention
@@ -189,7 +189,10 @@ defmodule Delegator do
   end
 
   @impl true
-  def execute_tool("start_session", %{"task_category" => category_str, "description" => description}) do
+  def execute_tool(
+        "start_session",
+        %{"task_category" => category_str, "description" => description}
+      ) do
     category = String.to_existing_atom(category_str)
 
     # Check if session already active
@@ -80,6 +80,35 @@ defmodule Delegator.MessageRouter do
     GenServer.call(__MODULE__, {:send_to_agent, role, message, context})
   end
 
+  @doc """
+  Send a request to a specific agent and wait for its reply.
+
+  Runs in the caller's process (not the router) so slow agents don't
+  block other routing. See `EchoShared.MessageBus.request/5`.
+
+  ## Options
+
+    * `:timeout` - Milliseconds to wait for the reply (default: 30s)
+
+  ## Examples
+
+      {:ok, reply} = MessageRouter.ask_agent(:cto, "Estimate migration effort", %{service: "auth"})
+  """
+  @spec ask_agent(atom(), String.t(), map(), keyword()) :: {:ok, map()} | {:error, term()}
+  def ask_agent(role, message, context \\ %{}, opts \\ []) do
+    if AgentRegistry.registered?(role) do
+      MessageBus.request(
+        :delegator,
+        role,
+        "Direct task from delegator",
+        %{message: message, context: context},
+        opts
+      )
+    else
+      {:error, :agent_not_running}
+    end
+  end
+
   @doc """
   Broadcast a message to all active agents.
 
@@ -202,7 +231,7 @@ defmodule Delegator.MessageRouter do
       payload: inspect(payload)
     )
 
-    case Jason.decode(payload) do
+    case MessageBus.decode_message(channel, payload) do
       {:ok, decoded} ->
         handle_agent_message(channel, decoded, state)
 
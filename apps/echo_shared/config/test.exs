@@ -12,3 +12,23 @@ config :echo_shared, EchoShared.Repo,
 
 # Print only warnings and errors during test
 config :logger, level: :warning
+
+# In-process message bus, so the suite runs without Redis
+config :echo_shared, :message_bus_adapter, :local
+
+# Keep the periodic workers (retries, expiry, outbox relay, deadlines) out of
+# the supervision tree; tests drive those code paths directly
+config :echo_shared, :start_background_workers, false
+
+# Sign messages in tests so verification is exercised; the suite holds every role's key
+signing_keys =
+  for role <- ~w(ceo cto chro operations_head product_manager senior_architect uiux_engineer
+                 senior_developer test_lead delegator workflow_engine flow_engine decision_scheduler human),
+      into: %{} do
+    {public_key, private_key} = :crypto.generate_key(:eddsa, :ed25519)
+    {role, {Base.encode64(public_key), Base.encode64(private_key)}}
+  end
+
+config :echo_shared,
+  message_signing_public_keys: Map.new(signing_keys, fn {role, {public_key, _}} -> {role, public_key} end),
+  message_signing_private_keys: Map.new(signing_keys, fn {role, {_, private_key}} -> {role, private_key} end)
@@ -4,8 +4,19 @@ defmodule EchoShared.Application do
 
   Starts and supervises:
   - Ecto repository (PostgreSQL connection pool)
-  - Redis connection pool
+  - MessageBus adapter processes (Redis connections by default)
+  - Message nonce cache (rejects replayed envelopes)
   - LLM session manager
+  - MCP tool registry (runtime tool visibility)
+  - Flow task supervisor (flows resumed after human approval)
+  - Message retry scheduler (redelivers failed messages)
+  - Message outbox relay (publishes committed messages to the adapter)
+  - Decision deadline scheduler (reminders and timeouts)
+
+  The periodic workers (retry scheduler, expiry sweeper, outbox relay and
+  deadline scheduler) only start when `:start_background_workers` is true
+  (the default); the test config turns them off so they don't race the
+  tests' own database sandbox.
   """
 
   use Application
@@ -15,24 +26,51 @@ defmodule EchoShared.Application do
   def start(_type, _args) do
     Logger.info("Starting ECHO Shared library...")
 
+    # MessageBus backend (Redis command + pub/sub connections by default)
+    message_bus_children = EchoShared.MessageBus.adapter().children()
+
     # Base children that all applications need
     base_children = [
       # Ecto repository
-      EchoShared.Repo,
-
-      # Redis connection pool (for commands)
-      {Redix, name: :redix, host: redis_host(), port: redis_port()},
-
-      # Note: Redix.PubSub is started by each agent's Application module
-      # (see agents/*/lib/*/application.ex) not here to avoid conflicts
+      EchoShared.Repo
+    ] ++ message_bus_children ++ [
+      # Nonces of verified message envelopes (replay protection)
+      EchoShared.MessageBus.NonceCache,
 
       # Agent health monitor
       EchoShared.AgentHealthMonitor,
 
       # LLM session manager (for conversation memory)
-      EchoShared.LLM.Session
+      EchoShared.LLM.Session,
+
+      # Runtime MCP tool visibility (tools/list_changed)
+      EchoShared.MCP.ToolRegistry,
+
+      # Flows resumed after human approval
+      {Task.Supervisor, name: EchoShared.Workflow.FlowEngine.TaskSupervisor}
+    ]
+
+    # Periodic database workers
+    worker_children = [
+      # Redelivery of failed messages after backoff
+      EchoShared.MessageBus.RetryScheduler,
+
+      # Archives expired, unprocessed messages
+      EchoShared.MessageBus.ExpirySweeper,
+
+      # Publishes pending outbox rows through the MessageBus adapter
+      EchoShared.MessageBus.OutboxRelay,
+
+      # Reminds voters and settles decisions past their deadline
+      EchoShared.Decisions.DeadlineScheduler
     ]
 
+    base_children = if background_workers_enabled?() do
+      base_children ++ worker_children
+    else
+      base_children
+    end
+
     # Add Workflow Engine only if enabled (for workflow orchestrator, not agents)
     children = if workflow_engine_enabled?() do
       base_children ++ [EchoShared.Workflow.Engine]
@@ -44,15 +82,11 @@ defmodule EchoShared.Application do
     Supervisor.start_link(children, opts)
   end
 
-  defp workflow_engine_enabled? do
-    System.get_env("WORKFLOW_ENGINE_ENABLED", "false") == "true"
-  end
-
-  defp redis_host do
-    System.get_env("REDIS_HOST", "localhost")
+  defp background_workers_enabled? do
+    Application.get_env(:echo_shared, :start_background_workers, true)
   end
 
-  defp redis_port do
-    System.get_env("REDIS_PORT", "6383") |> String.to_integer()  # Changed default to match Docker
+  defp workflow_engine_enabled? do
+    System.get_env("WORKFLOW_ENGINE_ENABLED", "false") == "true"
   end
 end
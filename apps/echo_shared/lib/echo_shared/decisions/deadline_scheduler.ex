@@ -0,0 +1,63 @@
+defmodule EchoShared.Decisions.DeadlineScheduler do
+  @moduledoc """
+  Acts on decision deadlines.
+
+  Every 30 seconds this process reminds participants who haven't voted on
+  decisions nearing their deadline (`Deadlines.send_reminders/0`), then
+  settles pending decisions whose deadline has passed according to their
+  timeout policy (`Deadlines.expire_overdue/0`), so decisions don't sit in
+  `:pending` forever.
+
+  Every agent node runs a scheduler; reminders are claimed atomically and
+  timeouts lock the decision row, so each happens once.
+  """
+
+  use GenServer
+  require Logger
+
+  alias EchoShared.Decisions.Deadlines
+
+  @check_interval 30_000 # 30 seconds
+
+  ## Client API
+
+  def start_link(opts \\ []) do
+    GenServer.start_link(__MODULE__, opts, name: __MODULE__)
+  end
+
+  ## Server Callbacks
+
+  @impl true
+  def init(_opts) do
+    schedule_check()
+    {:ok, %{}}
+  end
+
+  @impl true
+  def handle_info(:check_deadlines, state) do
+    try do
+      {:ok, _reminded} = Deadlines.send_reminders()
+
+      case Deadlines.expire_overdue() do
+        {:ok, 0} -> :ok
+        {:ok, count} -> Logger.info("Settled #{count} decision(s) past their deadline")
+      end
+    rescue
+      error ->
+        Logger.debug("Deadline scheduler couldn't query database (may be busy during startup): #{inspect(error)}")
+    end
+
+    schedule_check()
+    {:noreply, state}
+  end
+
+  def handle_info(_msg, state) do
+    {:noreply, state}
+  end
+
+  ## Private Functions
+
+  defp schedule_check do
+    Process.send_after(self(), :check_deadlines, @check_interval)
+  end
+end
@@ -0,0 +1,221 @@
+defmodule EchoShared.Decisions.Deadlines do
+  @moduledoc """
+  Reminders and timeouts for decisions with a `deadline`.
+
+  Called periodically by `EchoShared.Decisions.DeadlineScheduler`:
+
+  1. `send_reminders/0` - Once per decision, shortly before its deadline,
+     reminds participants who haven't voted
+  2. `expire_overdue/0` - Settles pending decisions whose deadline has
+     passed according to their `timeout_policy` (see
+     `EchoShared.Schemas.Decision`); escalated ones go up their
+     initiator's reporting chain (`EchoShared.Decisions.Escalation`).
+     A decision whose escalation fails gets an `escalation_error` and
+     is left out of later sweeps
+
+  Reminders are sent `reminder_lead_seconds/0` before the deadline
+  (`DECISION_REMINDER_LEAD_SECONDS` env var, or
+  `config :echo_shared, :decision_reminder_lead_seconds`, default 1 hour).
+  """
+
+  require Logger
+  import Ecto.Query
+
+  alias EchoShared.Decisions.{Escalation, Voting}
+  alias EchoShared.MessageBus
+  alias EchoShared.Repo
+  alias EchoShared.Schemas.{Decision, DecisionVote}
+
+  @default_reminder_lead_seconds 3_600
+
+  @doc """
+  Remind participants who haven't voted on decisions nearing their deadline.
+
+  ## Returns
+
+  - `{:ok, count}` - Number of decisions whose participants were reminded
+  """
+  @spec send_reminders() :: {:ok, non_neg_integer()}
+  def send_reminders do
+    now = DateTime.utc_now() |> DateTime.truncate(:second)
+    remind_from = DateTime.add(now, reminder_lead_seconds(), :second)
+
+    # Setting reminder_sent_at claims the decisions, so schedulers on other
+    # nodes don't remind twice
+    {count, decisions} =
+      Repo.update_all(
+        from(d in Decision,
+          where: d.status == :pending and is_nil(d.reminder_sent_at),
+          where: d.deadline > ^now and d.deadline <= ^remind_from,
+          select: d
+        ),
+        set: [reminder_sent_at: now]
+      )
+
+    Enum.each(decisions, &remind/1)
+    {:ok, count}
+  end
+
+  @doc """
+  Settle pending decisions whose deadline has passed.
+
+  Decisions with an `escalation_error` are skipped.
+
+  ## Returns
+
+  - `{:ok, count}` - Number of decisions settled
+  """
+  @spec expire_overdue() :: {:ok, non_neg_integer()}
+  def expire_overdue do
+    now = DateTime.utc_now()
+
+    overdue =
+      Repo.all(
+        from d in Decision,
+          where: d.status == :pending and d.deadline <= ^now and is_nil(d.escalation_error),
+          select: d.id
+      )
+
+    {:ok, Enum.count(overdue, &match?({:ok, _}, timeout_decision(&1)))}
+  end
+
+  @doc """
+  Get the number of seconds before a deadline that reminders are sent.
+  """
+  @spec reminder_lead_seconds() :: pos_integer()
+  def reminder_lead_seconds do
+    case System.get_env("DECISION_REMINDER_LEAD_SECONDS") do
+      nil -> Application.get_env(:echo_shared, :decision_reminder_lead_seconds, @default_reminder_lead_seconds)
+      value -> String.to_integer(value)
+    end
+  end
+
+  ## Private Functions
+
+  defp remind(decision) do
+    voters =
+      Repo.all(from v in DecisionVote, where: v.decision_id == ^decision.id, select: v.voter_role)
+
+    for participant <- decision.participants || [], participant not in voters do
+      MessageBus.publish_message(
+        :decision_scheduler,
+        participant,
+        :notification,
+        "Vote Reminder: #{decision.decision_type}",
+        %{
+          decision_id: decision.id,
+          decision_type: decision.decision_type,
+          deadline: decision.deadline,
+          timeout_policy: decision.timeout_policy
+        },
+        %{priority: :high, expires_at: decision.deadline}
+      )
+    end
+  end
+
+  # Collaborative votes are closed under their rule; there's nothing to
+  # count for other modes, so :close escalates them
+  defp timeout_decision(decision_id) do
+    result =
+      case Repo.get(Decision, decision_id) do
+        %Decision{mode: :collaborative, timeout_policy: :close} ->
+          Voting.finalize(decision_id)
+
+        %Decision{timeout_policy: :approve} ->
+          settle(decision_id, :approved)
+
+        %Decision{timeout_policy: :reject} ->
+          settle(decision_id, :rejected)
+
+        %Decision{} = decision ->
+          escalate(decision)
+
+        nil ->
+          {:error, :not_found}
+      end
+
+    case result do
+      {:ok, decision} ->
+        Logger.info("Decision #{decision.id} #{decision.status} at its deadline")
+
+      # Settled elsewhere in the meantime
+      {:error, reason} when reason in [:closed, :not_found] ->
+        :ok
+
+      # Only escalation can fail otherwise (no reporting line, policy, ...);
+      # recorded so the next sweep doesn't retry it forever
+      {:error, reason} ->
+        Logger.error("Decision #{decision_id} couldn't be escalated at its deadline: #{inspect(reason)}")
+
+        Repo.update_all(
+          from(d in Decision, where: d.id == ^decision_id and d.status == :pending),
+          set: [escalation_error: inspect(reason), updated_at: DateTime.utc_now() |> DateTime.truncate(:second)]
+        )
+    end
+
+    result
+  end
+
+  # Up the initiator's reporting chain; a decision with nobody above its
+  # initiator (e.g. a system role) is just marked escalated
+  defp escalate(decision) do
+    if Escalation.chain(decision.initiator_role) == [] do
+      settle(decision.id, :escalated)
+    else
+      Repo.transaction(fn ->
+        # Another node may have settled it since it was selected
+        with %Decision{} = decision <- get_pending(decision.id),
+             {:ok, escalated} <- Escalation.escalate(decision, "Deadline passed", from: decision.initiator_role) do
+          escalated
+        else
+          nil -> Repo.rollback(:closed)
+          {:error, reason} -> Repo.rollback(reason)
+        end
+      end)
+      |> case do
+        {:ok, escalated} ->
+          Escalation.announce(escalated, escalated.initiator_role)
+          {:ok, escalated}
+
+        {:error, reason} ->
+          {:error, reason}
+      end
+    end
+  end
+
+  defp settle(decision_id, status) do
+    Repo.transaction(fn ->
+      # Another node may have settled it since it was selected
+      case get_pending(decision_id) do
+        nil ->
+          Repo.rollback(:closed)
+
+        decision ->
+          decision
+          |> Decision.changeset(%{
+            status: status,
+            outcome: Map.merge(decision.outcome || %{}, %{"decided_by" => "timeout", "deadline" => decision.deadline}),
+            completed_at: DateTime.utc_now() |> DateTime.truncate(:second)
+          })
+          |> Repo.update!()
+      end
+    end)
+    |> case do
+      {:ok, decision} ->
+        event = if status == :escalated, do: :escalated, else: :completed
+        MessageBus.publish_decision_event(:decision_scheduler, event, %{
+          decision_id: decision.id,
+          status: status,
+          reason: "deadline"
+        })
+        {:ok, decision}
+
+      {:error, reason} ->
+        {:error, reason}
+    end
+  end
+
+  defp get_pending(decision_id) do
+    Repo.one(from d in Decision, where: d.id == ^decision_id and d.status == :pending, lock: "FOR UPDATE")
+  end
+end
@@ -0,0 +1,301 @@
+defmodule EchoShared.Decisions.Escalation do
+  @moduledoc """
+  Escalation of decisions up the reporting chain.
+
+  `escalate/3` walks up from the escalating role, starting with its
+  manager, until it reaches a role with authority over the decision's
+  `decision_type` (or the role given as `:to`):
+
+      senior_developer -> senior_architect -> cto -> ceo -> human
+
+  Each step is recorded as a hop in the decision's `escalation_hops`. The
+  decision is marked `:escalated`, with `escalated_to` set to the role
+  that now holds it. Once that commits, `decisions:escalated` is published
+  with the full hop history (`announce/2`).
+
+  The holder is sent an `:escalation` message. `"human"` is the exception:
+  it has authority over everything, ends every chain, and gets an item in
+  the human approval inbox instead (`EchoShared.Decisions.HumanApprovals`).
+
+  ## Org Chart
+
+  Reporting lines and authority default to the ECHO organization and can
+  be overridden per role:
+
+      config :echo_shared, :reports_to, %{"uiux_engineer" => "senior_architect"}
+      config :echo_shared, :decision_authority, %{"cto" => ["architecture", "engineering_budget"]}
+
+  A role's authority is a list of decision types, or `:any`.
+  """
+
+  require Logger
+  import Ecto.Query
+
+  alias EchoShared.Decisions.HumanApprovals
+  alias EchoShared.MessageBus
+  alias EchoShared.Repo
+  alias EchoShared.Schemas.Decision
+
+  @human "human"
+
+  @default_reports_to %{
+    "senior_developer" => "senior_architect",
+    "test_lead" => "senior_architect",
+    "uiux_engineer" => "product_manager",
+    "senior_architect" => "cto",
+    "product_manager" => "ceo",
+    "cto" => "ceo",
+    "chro" => "ceo",
+    "operations_head" => "ceo",
+    "ceo" => @human
+  }
+
+  @default_authority %{
+    "senior_developer" => ["implementation", "bug_fix", "refactoring"],
+    "test_lead" => ["test_strategy", "release_readiness"],
+    "uiux_engineer" => ["ui_design", "ux_research"],
+    "senior_architect" => ["architecture", "technical_proposal", "system_design"],
+    "product_manager" => ["feature_prioritization", "product_roadmap", "requirements"],
+    "cto" => [
+      "technology_strategy",
+      "infrastructure_architecture",
+      "infrastructure_change",
+      "engineering_budget",
+      "team_structure",
+      "technical_standards"
+    ],
+    "chro" => ["hiring", "hiring_request", "performance_management", "hr_policy", "hr_issue_resolution"],
+    "operations_head" => ["operations", "vendor_management", "incident_response"],
+    "ceo" => [
+      "strategic_planning",
+      "strategic_initiative",
+      "budget",
+      "budget_allocation",
+      "c_suite_hiring",
+      "company_direction",
+      "crisis_management"
+    ],
+    @human => :any
+  }
+
+  @doc """
+  Escalate a decision up the reporting chain.
+
+  ## Parameters
+
+  - `decision` - Decision (or its id) to escalate; must be pending or
+    already escalated
+  - `reason` - Why it is escalated
+  - `opts` - Options:
+    - `:from` - Escalating role (default: the role currently holding the
+      decision, else its initiator)
+    - `:to` - Role above `:from` to escalate to; the walk stops there
+      whether or not it has authority (default: the first role with
+      authority)
+    - `:urgency` - `"low"`, `"medium"`, `"high"` or `"critical"`
+    - `:context` - Extra context for the holder (default: the decision's)
+
+  ## Returns
+
+  - `{:ok, decision}` - Escalated to `decision.escalated_to`
+  - `{:error, :not_found}` - No such decision
+  - `{:error, :closed}` - The decision was already approved or rejected
+  - `{:error, {:no_reporting_line, role}}` - Nobody above `role`
+  - `{:error, {:not_above, role}}` - `:to` isn't above the escalating role
+  - `{:error, :forbidden}` - The policy doesn't allow the escalation message
+
+  Inside a caller's transaction the escalation only happens if that
+  transaction commits, so it isn't announced here: the caller calls
+  `announce/2` after its commit.
+  """
+  @spec escalate(Decision.t() | String.t(), String.t(), keyword()) :: {:ok, Decision.t()} | {:error, term()}
+  def escalate(decision, reason, opts \\ [])
+
+  def escalate(%Decision{id: decision_id}, reason, opts), do: escalate(decision_id, reason, opts)
+
+  def escalate(decision_id, reason, opts) do
+    nested? = Repo.in_transaction?()
+
+    result =
+      Repo.transaction(fn ->
+        # Row lock so concurrent escalations append their hops in turn
+        with %Decision{} = decision <- get_decision(decision_id),
+             :ok <- check_open(decision),
+             from = to_string(opts[:from] || decision.escalated_to || decision.initiator_role),
+             {:ok, hops} <- route(from, opts[:to], decision.decision_type, reason, opts[:urgency]),
+             {:ok, escalated} <- record(decision, hops),
+             :ok <- notify_holder(escalated, from, reason, opts) do
+          {escalated, from}
+        else
+          nil -> Repo.rollback(:not_found)
+          {:error, error} -> Repo.rollback(error)
+        end
+      end)
+
+    case result do
+      {:ok, {escalated, from}} ->
+        unless nested?, do: announce(escalated, from)
+        {:ok, escalated}
+
+      {:error, error} ->
+        {:error, error}
+    end
+  end
+
+  @doc """
+  Publish `decisions:escalated` for an escalated decision, signed as
+  `from` (the escalating role).
+
+  The reason and urgency are those of the decision's last hop.
+  """
+  @spec announce(Decision.t(), atom() | String.t()) :: {:ok, integer()} | {:error, term()}
+  def announce(%Decision{} = decision, from) do
+    from = to_string(from)
+    hop = List.last(decision.escalation_hops || []) || %{}
+
+    Logger.info("Decision #{decision.id} escalated by #{from} to #{decision.escalated_to}: #{hop["reason"]}")
+
+    MessageBus.publish_decision_event(from, :escalated, %{
+      decision_id: decision.id,
+      decision_type: decision.decision_type,
+      status: decision.status,
+      reason: hop["reason"],
+      urgency: hop["urgency"],
+      escalated_by: from,
+      escalated_to: decision.escalated_to,
+      hops: decision.escalation_hops
+    })
+  end
+
+  @doc """
+  The roles above `role`, nearest first.
+  """
+  @spec chain(atom() | String.t()) :: [String.t()]
+  def chain(role), do: walk(to_string(role), [to_string(role)])
+
+  @doc """
+  The role `role` reports to, or nil at the top of the chain.
+  """
+  @spec reports_to(atom() | String.t()) :: String.t() | nil
+  def reports_to(role) do
+    configured = Application.get_env(:echo_shared, :reports_to, %{})
+
+    @default_reports_to
+    |> Map.merge(Map.new(configured, fn {role, manager} -> {to_string(role), to_string(manager)} end))
+    |> Map.get(to_string(role))
+  end
+
+  @doc """
+  Whether `role` has authority over decisions of `decision_type`.
+  """
+  @spec authority?(atom() | String.t(), String.t()) :: boolean()
+  def authority?(role, decision_type) do
+    configured = Application.get_env(:echo_shared, :decision_authority, %{})
+
+    authority =
+      @default_authority
+      |> Map.merge(Map.new(configured, fn {role, types} -> {to_string(role), types} end))
+      |> Map.get(to_string(role), [])
+
+    case authority do
+      :any -> true
+      types -> to_string(decision_type) in Enum.map(types, &to_string/1)
+    end
+  end
+
+  ## Private Functions
+
+  defp walk(role, seen) do
+    case reports_to(role) do
+      nil -> []
+      manager -> if manager in seen, do: [], else: [manager | walk(manager, [manager | seen])]
+    end
+  end
+
+  # Up to and including `to`, else the first role with authority (or the top)
+  defp route(from, to, decision_type, reason, urgency) do
+    with {:ok, targets} <- targets(from, to && to_string(to), decision_type) do
+      at = DateTime.utc_now() |> DateTime.truncate(:second) |> DateTime.to_iso8601()
+
+      hops =
+        Enum.zip_with([from | targets], targets, fn hop_from, hop_to ->
+          %{
+            "from" => hop_from,
+            "to" => hop_to,
+            "reason" => reason,
+            "urgency" => urgency,
+            "authority" => authority?(hop_to, decision_type),
+            "at" => at
+          }
+        end)
+
+      {:ok, hops}
+    end
+  end
+
+  defp targets(from, to, decision_type) do
+    roles = chain(from)
+
+    cond do
+      roles == [] ->
+        {:error, {:no_reporting_line, from}}
+
+      to == nil ->
+        {passed, rest} = Enum.split_while(roles, &(not authority?(&1, decision_type)))
+        {:ok, passed ++ Enum.take(rest, 1)}
+
+      to in roles ->
+        {:ok, Enum.take_while(roles, &(&1 != to)) ++ [to]}
+
+      true ->
+        {:error, {:not_above, to}}
+    end
+  end
+
+  defp record(decision, hops) do
+    decision
+    |> Decision.changeset(%{
+      status: :escalated,
+      escalated_to: List.last(hops)["to"],
+      escalation_hops: (decision.escalation_hops || []) ++ hops
+    })
+    |> Repo.update()
+  end
+
+  # Written in the transaction, so it's only sent if the escalation commits
+  defp notify_holder(%Decision{escalated_to: @human} = decision, from, reason, opts) do
+    approval = HumanApprovals.request(decision, requested_by: from, reason: reason, urgency: opts[:urgency])
+
+    with {:ok, _approval} <- approval, do: :ok
+  end
+
+  defp notify_holder(decision, from, reason, opts) do
+    result =
+      MessageBus.publish_message(
+        from,
+        decision.escalated_to,
+        :escalation,
+        "Decision Escalation: #{decision.decision_type}",
+        %{
+          decision_id: decision.id,
+          decision_type: decision.decision_type,
+          reason: reason,
+          urgency: opts[:urgency],
+          context: opts[:context] || decision.context,
+          hops: decision.escalation_hops
+        }
+      )
+
+    with {:ok, _message_id} <- result, do: :ok
+  end
+
+  defp check_open(%Decision{status: status}) when status in [:pending, :escalated], do: :ok
+  defp check_open(%Decision{}), do: {:error, :closed}
+
+  defp get_decision(decision_id) do
+    Repo.one(from d in Decision, where: d.id == ^decision_id, lock: "FOR UPDATE")
+  rescue
+    # Not a UUID
+    Ecto.Query.CastError -> nil
+  end
+end
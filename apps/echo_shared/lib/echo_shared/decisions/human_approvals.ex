@@ -0,0 +1,460 @@
+defmodule EchoShared.Decisions.HumanApprovals do
+  @moduledoc """
+  Inbox of decisions waiting on a human.
+
+  Items (`EchoShared.Schemas.HumanApproval`) are opened when:
+
+  - A decision is escalated to `"human"` (`EchoShared.Decisions.Escalation`)
+  - A workflow reaches a `{:pause, reason}` step (`EchoShared.Workflow.Engine`)
+  - A flow listener asks for approval (`EchoShared.Workflow.FlowEngine`)
+
+  A human lists and inspects them, then approves, rejects or comments
+  (the CEO's `*_human_approval` MCP tools). The outcome is applied to the
+  `Decision` (status, `outcome` with `"decided_by" => "human"`) and
+  `decisions:completed` is published.
+
+  ## Resuming
+
+  A workflow or flow paused on the item is resumed with the resolution
+  under `:human_approval` in its context (workflows) or
+  `"human_approval"` in its state (flows).
+
+  Flows are resumed straight away. A workflow's steps only live in the
+  engine that started it, so it is resumed there: immediately if that
+  engine runs on this node, otherwise by the engine's periodic
+  `resume_approved_workflows/0`. `resumed_at` records that it happened.
+  """
+
+  require Logger
+  import Ecto.Query
+
+  alias EchoShared.MessageBus
+  alias EchoShared.Repo
+  alias EchoShared.Schemas.{Decision, HumanApproval}
+  alias EchoShared.Workflow.{Engine, FlowEngine}
+
+  @default_limit 50
+
+  @doc """
+  Open an approval for a decision.
+
+  If the decision already has a pending approval, that one is returned
+  (linked to the given workflow or flow if it wasn't yet).
+
+  ## Parameters
+
+  - `decision` - Decision (or its id) needing a human
+  - `opts` - Options:
+    - `:requested_by` - Requesting role (default: the decision's initiator)
+    - `:reason` - Why a human is needed
+    - `:urgency` - `"low"`, `"medium"`, `"high"` or `"critical"`
+    - `:workflow_execution_id` / `:flow_execution_id` - Execution paused
+      until the approval is resolved
+
+  ## Returns
+
+  - `{:ok, approval}` - Pending approval
+  - `{:error, :not_found}` - No such decision
+  - `{:error, changeset}` - Invalid attributes
+  """
+  @spec request(Decision.t() | String.t(), keyword()) :: {:ok, HumanApproval.t()} | {:error, term()}
+  def request(%Decision{} = decision, opts) do
+    links = Map.new(Keyword.take(opts, [:workflow_execution_id, :flow_execution_id]))
+
+    case Repo.get_by(HumanApproval, decision_id: decision.id, status: :pending) do
+      nil ->
+        %HumanApproval{}
+        |> HumanApproval.changeset(
+          Map.merge(links, %{
+            decision_id: decision.id,
+            requested_by: to_string(opts[:requested_by] || decision.initiator_role),
+            reason: opts[:reason],
+            urgency: opts[:urgency]
+          })
+        )
+        |> Repo.insert()
+        |> tap(fn
+          {:ok, approval} -> Logger.info("Human approval ##{approval.id} opened for decision #{decision.id}")
+          {:error, _changeset} -> :ok
+        end)
+
+      existing ->
+        existing
+        |> HumanApproval.changeset(Map.reject(links, fn {field, _id} -> Map.get(existing, field) end))
+        |> Repo.update()
+    end
+  end
+
+  def request(decision_id, opts) do
+    case Repo.get(Decision, decision_id) do
+      nil -> {:error, :not_found}
+      decision -> request(decision, opts)
+    end
+  end
+
+  @doc """
+  Open an approval for a paused workflow or flow execution.
+
+  Uses `request[:decision_id]` when given; otherwise a `:human` mode
+  decision is created for the pause.
+
+  ## Parameters
+
+  - `kind` - `:workflow` or `:flow`
+  - `execution_id` - The paused execution
+  - `request` - Map with `:reason`, and optionally `:decision_id`,
+    `:decision_type` (default: `"<kind>_approval"`), `:context` and
+    `:urgency`
+  """
+  @spec request_for_execution(:workflow | :flow, String.t(), map()) ::
+          {:ok, HumanApproval.t()} | {:error, term()}
+  def request_for_execution(kind, execution_id, request) when kind in [:workflow, :flow] do
+    requested_by = "#{kind}_engine"
+
+    decision =
+      case request[:decision_id] do
+        nil ->
+          %Decision{}
+          |> Decision.changeset(%{
+            decision_type: request[:decision_type] || "#{kind}_approval",
+            initiator_role: requested_by,
+            mode: :human,
+            context: Map.merge(request[:context] || %{}, %{execution_id: execution_id, reason: request[:reason]})
+          })
+          |> Repo.insert()
+
+        decision_id ->
+          {:ok, decision_id}
+      end
+
+    with {:ok, decision} <- decision do
+      request(decision, [
+        {:"#{kind}_execution_id", execution_id},
+        requested_by: requested_by,
+        reason: request[:reason],
+        urgency: request[:urgency]
+      ])
+    end
+  end
+
+  @doc """
+  List approvals, oldest first, with their decisions.
+
+  ## Options
+
+  - `:status` - `:pending` (default), `:approved`, `:rejected` or `:all`
+  - `:limit` - Maximum number of approvals (default: 50)
+  """
+  @spec list(keyword()) :: [HumanApproval.t()]
+  def list(opts \\ []) do
+    query =
+      from a in HumanApproval,
+        order_by: [asc: a.inserted_at, asc: a.id],
+        limit: ^Keyword.get(opts, :limit, @default_limit),
+        preload: :decision
+
+    case Keyword.get(opts, :status, :pending) do
+      :all -> Repo.all(query)
+      status -> Repo.all(from a in query, where: a.status == ^status)
+    end
+  end
+
+  @doc """
+  Get an approval with its decision.
+  """
+  @spec get(integer() | String.t()) :: {:ok, HumanApproval.t()} | {:error, :not_found}
+  def get(approval_id) do
+    case Repo.get(HumanApproval, approval_id) do
+      nil -> {:error, :not_found}
+      approval -> {:ok, Repo.preload(approval, :decision)}
+    end
+  rescue
+    Ecto.Query.CastError -> {:error, :not_found}
+  end
+
+  @doc """
+  Approve the decision of a pending approval.
+
+  See `resolve/3`.
+  """
+  @spec approve(integer() | String.t(), keyword()) :: {:ok, HumanApproval.t()} | {:error, term()}
+  def approve(approval_id, opts \\ []), do: resolve(approval_id, :approved, opts)
+
+  @doc """
+  Reject the decision of a pending approval.
+
+  See `resolve/3`.
+  """
+  @spec reject(integer() | String.t(), keyword()) :: {:ok, HumanApproval.t()} | {:error, term()}
+  def reject(approval_id, opts \\ []), do: resolve(approval_id, :rejected, opts)
+
+  @doc """
+  Resolve a pending approval and apply the outcome to its decision.
+
+  Publishes `decisions:completed`, then resumes the workflow or flow
+  paused on the approval; otherwise the requesting role is notified.
+
+  ## Parameters
+
+  - `approval_id` - Pending approval
+  - `status` - `:approved` or `:rejected`
+  - `opts` - Options:
+    - `:by` - Who resolved it (default: `"human"`)
+    - `:note` - Rationale recorded on the approval and decision
+
+  ## Returns
+
+  - `{:ok, approval}` - Resolved (and `resumed_at` set if resumed)
+  - `{:error, :not_found}` - No such approval
+  - `{:error, :already_resolved}` - The approval isn't pending
+  - `{:error, :closed}` - The decision was settled some other way
+  """
+  @spec resolve(integer() | String.t(), :approved | :rejected, keyword()) ::
+          {:ok, HumanApproval.t()} | {:error, term()}
+  def resolve(approval_id, status, opts \\ []) when status in [:approved, :rejected] do
+    by = to_string(opts[:by] || "human")
+    now = DateTime.utc_now() |> DateTime.truncate(:second)
+
+    result =
+      Repo.transaction(fn ->
+        with %HumanApproval{} = approval <- get_locked(HumanApproval, approval_id),
+             :ok <- check_pending(approval),
+             %Decision{} = decision <- get_locked(Decision, approval.decision_id),
+             :ok <- check_open(decision),
+             {:ok, decision} <- apply_outcome(decision, approval, status, by, opts[:note], now),
+             {:ok, approval} <-
+               approval
+               |> HumanApproval.changeset(%{status: status, resolved_by: by, resolution_note: opts[:note], resolved_at: now})
+               |> Repo.update() do
+          {approval, decision}
+        else
+          nil -> Repo.rollback(:not_found)
+          {:error, error} -> Repo.rollback(error)
+        end
+      end)
+
+    case result do
+      {:ok, {approval, decision}} ->
+        Logger.info("Human approval ##{approval.id} #{status} by #{by} (decision #{decision.id})")
+
+        MessageBus.publish_decision_event(:human, :completed, %{
+          decision_id: decision.id,
+          status: status,
+          decided_by: "human",
+          resolved_by: by,
+          approval_id: approval.id
+        })
+
+        {:ok, follow_up(approval, decision)}
+
+      {:error, error} ->
+        {:error, error}
+    end
+  end
+
+  @doc """
+  Add a comment to an approval.
+
+  The requesting role is sent the comment, unless a workflow or flow is
+  paused on the approval. Only pending approvals take comments; a note on
+  the resolution goes in `resolve/3`'s `:note`.
+
+  ## Options
+
+  - `:by` - Who is commenting (default: `"human"`)
+
+  ## Returns
+
+  - `{:ok, approval}` - The approval with the comment appended
+  - `{:error, :not_found}` - No such approval
+  - `{:error, :already_resolved}` - The approval isn't pending
+  """
+  @spec comment(integer() | String.t(), String.t(), keyword()) :: {:ok, HumanApproval.t()} | {:error, term()}
+  def comment(approval_id, text, opts \\ []) do
+    comment = %{
+      "by" => to_string(opts[:by] || "human"),
+      "text" => text,
+      "at" => DateTime.utc_now() |> DateTime.truncate(:second) |> DateTime.to_iso8601()
+    }
+
+    # Pushed in one UPDATE, so concurrent comments aren't lost
+    case Repo.update_all(
+           from(a in HumanApproval, where: a.id == ^approval_id and a.status == :pending, select: a),
+           push: [comments: comment],
+           set: [updated_at: DateTime.utc_now() |> DateTime.truncate(:second)]
+         ) do
+      {1, [approval]} ->
+        notify_requester(approval, "Comment on Decision #{approval.decision_id}", %{comment: comment})
+        {:ok, approval}
+
+      {0, []} ->
+        if Repo.get(HumanApproval, approval_id), do: {:error, :already_resolved}, else: {:error, :not_found}
+    end
+  rescue
+    Ecto.Query.CastError -> {:error, :not_found}
+  end
+
+  @doc """
+  Resume the workflow or flow paused on a resolved approval.
+
+  ## Returns
+
+  - `{:ok, approval}` - Resumed (or nothing left to resume); `resumed_at` set
+  - `{:error, :pending}` - The approval isn't resolved yet
+  - `{:error, :engine_not_running}` - The workflow engine runs on another node
+  - `{:error, :definition_unavailable}` - The workflow's engine restarted
+    while it was paused, so it was marked failed; recorded as the
+    approval's `resume_error`, so it isn't retried
+  - `{:error, reason}` - The engine couldn't resume the execution
+  """
+  @spec resume(HumanApproval.t()) :: {:ok, HumanApproval.t()} | {:error, term()}
+  def resume(%HumanApproval{status: :pending}), do: {:error, :pending}
+
+  def resume(%HumanApproval{} = approval) do
+    resolution = resolution(approval)
+
+    result =
+      cond do
+        approval.flow_execution_id ->
+          FlowEngine.resume_after_approval(approval.flow_execution_id, resolution)
+
+        approval.workflow_execution_id && GenServer.whereis(Engine) ->
+          Engine.resume_workflow(approval.workflow_execution_id, %{human_approval: resolution})
+
+        approval.workflow_execution_id ->
+          {:error, :engine_not_running}
+
+        true ->
+          :ok
+      end
+
+    case result do
+      :ok ->
+        mark_resumed(approval)
+
+      {:ok, _execution} ->
+        mark_resumed(approval)
+
+      # Already running or finished, so there is nothing left to resume
+      {:error, :not_paused} ->
+        mark_resumed(approval)
+
+      {:error, :definition_unavailable} ->
+        record_resume_error(approval, :definition_unavailable)
+        {:error, :definition_unavailable}
+
+      {:error, reason} ->
+        {:error, reason}
+    end
+  end
+
+  @doc """
+  Resume workflows whose approval was resolved but not yet resumed.
+
+  Called periodically by `EchoShared.Workflow.Engine` for approvals
+  resolved on other nodes.
+
+  ## Returns
+
+  - `{:ok, count}` - Number of workflows resumed
+  """
+  @spec resume_approved_workflows() :: {:ok, non_neg_integer()}
+  def resume_approved_workflows do
+    approvals =
+      Repo.all(
+        from a in HumanApproval,
+          where: a.status != :pending and not is_nil(a.workflow_execution_id),
+          where: is_nil(a.resumed_at) and is_nil(a.resume_error)
+      )
+
+    {:ok, Enum.count(approvals, &match?({:ok, _}, resume(&1)))}
+  end
+
+  ## Private Functions
+
+  defp apply_outcome(decision, approval, status, by, note, now) do
+    decision
+    |> Decision.changeset(%{
+      status: status,
+      outcome:
+        Map.merge(decision.outcome || %{}, %{
+          "decided_by" => "human",
+          "resolved_by" => by,
+          "note" => note,
+          "approval_id" => approval.id
+        }),
+      completed_at: now
+    })
+    |> Repo.update()
+  end
+
+  defp follow_up(approval, decision) do
+    if approval.workflow_execution_id || approval.flow_execution_id do
+      case resume(approval) do
+        {:ok, resumed} ->
+          resumed
+
+        {:error, reason} ->
+          Logger.info("Approval ##{approval.id} resolved; execution not resumed yet: #{inspect(reason)}")
+          approval
+      end
+    else
+      notify_requester(approval, "Decision #{String.capitalize(to_string(approval.status))}: #{decision.decision_type}", %{
+        decision_id: decision.id,
+        status: approval.status,
+        resolved_by: approval.resolved_by,
+        note: approval.resolution_note
+      })
+
+      approval
+    end
+  end
+
+  # Executions are resumed instead; there's no agent to tell
+  defp notify_requester(%HumanApproval{workflow_execution_id: nil, flow_execution_id: nil} = approval, subject, content) do
+    MessageBus.publish_message(
+      :human,
+      approval.requested_by,
+      :notification,
+      subject,
+      Map.put(content, :approval_id, approval.id)
+    )
+  end
+
+  defp notify_requester(_approval, _subject, _content), do: :ok
+
+  defp mark_resumed(approval) do
+    approval
+    |> HumanApproval.changeset(%{resumed_at: DateTime.utc_now() |> DateTime.truncate(:second)})
+    |> Repo.update()
+  end
+
+  defp record_resume_error(approval, reason) do
+    approval
+    |> HumanApproval.changeset(%{resume_error: to_string(reason)})
+    |> Repo.update()
+  end
+
+  defp resolution(approval) do
+    %{
+      approval_id: approval.id,
+      decision_id: approval.decision_id,
+      status: approval.status,
+      resolved_by: approval.resolved_by,
+      note: approval.resolution_note,
+      comments: approval.comments
+    }
+  end
+
+  defp check_pending(%HumanApproval{status: :pending}), do: :ok
+  defp check_pending(%HumanApproval{}), do: {:error, :already_resolved}
+
+  defp check_open(%Decision{status: status}) when status in [:pending, :escalated], do: :ok
+  defp check_open(%Decision{}), do: {:error, :closed}
+
+  defp get_locked(schema, id) do
+    Repo.one(from r in schema, where: r.id == ^id, lock: "FOR UPDATE")
+  rescue
+    Ecto.Query.CastError -> nil
+  end
+end
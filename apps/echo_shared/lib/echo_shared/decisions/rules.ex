@@ -0,0 +1,198 @@
+defmodule EchoShared.Decisions.Rules do
+  @moduledoc """
+  Quorum and consensus rules for collaborative decisions.
+
+  Each `decision_type` has a rule, looked up in order from the
+  `decision_rules` table (`EchoShared.Schemas.DecisionRule`), the
+  `:decision_rules` app config, and the defaults:
+
+      config :echo_shared, :decision_rules, %{
+        "architecture" => %{method: :majority, vetoes: ["cto"]},
+        "budget" => %{method: :supermajority, quorum: 0.75, no_shows: :count_as_reject},
+        "hiring" => %{method: :weighted, role_weights: %{"chro" => 2.0}}
+      }
+
+  A `"default"` entry applies to types without their own rule.
+
+  ## Rule Fields
+
+  - `:method` - How the consensus score decides:
+    - `:majority` - Approved when the score is above `threshold` (0.5)
+    - `:supermajority` - Approved when the score is at least `threshold` (2/3)
+    - `:unanimous` - Approved when nobody rejects
+    - `:weighted` - Majority with votes weighted by `role_weights`
+  - `:threshold` - Overrides the method's threshold
+  - `:quorum` - Share of participants that must vote, abstentions
+    included (default: 0.5)
+  - `:role_weights` - Weight per role (default: 1.0 each); applies to every
+    method, `:weighted` just makes the intent explicit
+  - `:vetoes` - Roles whose reject vote rejects the decision outright
+  - `:abstentions` - `:exclude` (default; count toward quorum only) or
+    `:count_as_reject`
+  - `:no_shows` - Participants without a vote when the vote closes:
+    `:exclude` (default), `:count_as_abstain` or `:count_as_reject`
+  - `:on_no_quorum` - `:reject` (default) or `:escalate` the decision
+  - `:weigh_confidence` - Multiply each vote's weight by its confidence
+    (default: true)
+
+  The consensus score is the weighted share of approve votes among the
+  votes that count.
+  """
+
+  alias EchoShared.Repo
+  alias EchoShared.Schemas.DecisionRule
+
+  @defaults %{
+    method: :majority,
+    threshold: nil,
+    quorum: 0.5,
+    role_weights: %{},
+    vetoes: [],
+    abstentions: :exclude,
+    no_shows: :exclude,
+    on_no_quorum: :reject,
+    weigh_confidence: true
+  }
+
+  @thresholds %{majority: 0.5, weighted: 0.5, supermajority: 2 / 3, unanimous: 1.0}
+
+  @type rule :: %{
+          method: :majority | :supermajority | :unanimous | :weighted,
+          threshold: float() | nil,
+          quorum: float(),
+          role_weights: %{String.t() => number()},
+          vetoes: [String.t()],
+          abstentions: :exclude | :count_as_reject,
+          no_shows: :exclude | :count_as_abstain | :count_as_reject,
+          on_no_quorum: :reject | :escalate,
+          weigh_confidence: boolean()
+        }
+
+  @type evaluation ::
+          {:decided, :approved | :rejected | :escalated, map()} | {:pending, map()}
+
+  @doc """
+  The rule for a decision type.
+  """
+  @spec for_type(String.t()) :: rule()
+  def for_type(decision_type) do
+    case Repo.get_by(DecisionRule, decision_type: decision_type) do
+      %DecisionRule{} = rule ->
+        rule |> Map.from_struct() |> Map.take(Map.keys(@defaults)) |> normalize()
+
+      nil ->
+        configured = Application.get_env(:echo_shared, :decision_rules, %{})
+
+        (config_entry(configured, decision_type) || config_entry(configured, "default") || %{})
+        |> normalize()
+    end
+  end
+
+  @doc """
+  Evaluate the votes on a decision under a rule.
+
+  While participants are still to vote (and `closing?` is false) the
+  decision stays pending, unless a veto has already rejected it. Once
+  everyone has voted, or the vote is `closing?` (e.g. its deadline
+  passed), the quorum and the method decide.
+
+  `votes` are `EchoShared.Schemas.DecisionVote`s (or maps with
+  `:voter_role`, `:vote` and `:confidence`).
+
+  ## Returns
+
+  - `{:decided, status, tally}` - `:approved`, `:rejected` or `:escalated`
+  - `{:pending, tally}` - Waiting for more votes
+  """
+  @spec evaluate(rule(), [String.t()], [map()], boolean()) :: evaluation()
+  def evaluate(rule, participants, votes, closing? \\ false) do
+    voters = Enum.map(votes, & &1.voter_role)
+    no_shows = Enum.reject(participants, &(&1 in voters))
+    tally = tally(rule, votes, if(closing?, do: no_shows, else: []))
+    veto = Enum.find(votes, &(&1.vote == :reject and &1.voter_role in rule.vetoes))
+
+    cond do
+      veto ->
+        {:decided, :rejected, Map.merge(tally, %{reason: :veto, vetoed_by: veto.voter_role})}
+
+      no_shows != [] and not closing? ->
+        {:pending, tally}
+
+      not quorum_met?(rule, participants, votes) ->
+        status = if rule.on_no_quorum == :escalate, do: :escalated, else: :rejected
+        {:decided, status, Map.merge(tally, %{reason: :no_quorum, no_shows: no_shows})}
+
+      passes?(rule, tally) ->
+        {:decided, :approved, Map.merge(tally, %{reason: :vote, no_shows: no_shows})}
+
+      true ->
+        {:decided, :rejected, Map.merge(tally, %{reason: :vote, no_shows: no_shows})}
+    end
+  end
+
+  ## Private Functions
+
+  defp tally(rule, votes, no_shows) do
+    weight = fn role, confidence ->
+      role_weight = Map.get(rule.role_weights, role, 1.0)
+      if rule.weigh_confidence, do: role_weight * confidence, else: role_weight * 1.0
+    end
+
+    sum = fn choice ->
+      votes
+      |> Enum.filter(&(&1.vote == choice))
+      |> Enum.map(&weight.(&1.voter_role, &1.confidence))
+      |> Enum.sum()
+    end
+
+    no_show_weight = no_shows |> Enum.map(&weight.(&1, 1.0)) |> Enum.sum()
+
+    abstain = sum.(:abstain) + if(rule.no_shows == :count_as_abstain, do: no_show_weight, else: 0.0)
+    approve = sum.(:approve) * 1.0
+
+    reject =
+      sum.(:reject) +
+        if(rule.abstentions == :count_as_reject, do: abstain, else: 0.0) +
+        if(rule.no_shows == :count_as_reject, do: no_show_weight, else: 0.0)
+
+    %{
+      method: rule.method,
+      approve_weight: approve,
+      reject_weight: reject * 1.0,
+      votes: length(votes),
+      abstentions: Enum.count(votes, &(&1.vote == :abstain)),
+      consensus_score: if(approve + reject > 0, do: approve / (approve + reject), else: 0.0)
+    }
+  end
+
+  defp quorum_met?(_rule, [], _votes), do: true
+  defp quorum_met?(rule, participants, votes), do: length(votes) / length(participants) >= rule.quorum
+
+  defp passes?(%{method: :unanimous}, tally), do: tally.approve_weight > 0 and tally.reject_weight == 0
+  defp passes?(%{method: :supermajority} = rule, tally), do: tally.consensus_score >= threshold(rule)
+  defp passes?(rule, tally), do: tally.consensus_score > threshold(rule)
+
+  defp threshold(rule), do: rule.threshold || Map.fetch!(@thresholds, rule.method)
+
+  defp config_entry(configured, decision_type) do
+    Map.get(configured, decision_type) ||
+      Enum.find_value(configured, fn {type, rule} -> to_string(type) == decision_type && rule end)
+  end
+
+  defp normalize(rule) do
+    rule = Map.merge(@defaults, Map.new(rule))
+
+    %{
+      rule
+      | method: to_atom(rule.method),
+        abstentions: to_atom(rule.abstentions),
+        no_shows: to_atom(rule.no_shows),
+        on_no_quorum: to_atom(rule.on_no_quorum),
+        role_weights: Map.new(rule.role_weights, fn {role, weight} -> {to_string(role), weight} end),
+        vetoes: Enum.map(rule.vetoes, &to_string/1)
+    }
+  end
+
+  defp to_atom(value) when is_atom(value), do: value
+  defp to_atom(value) when is_binary(value), do: String.to_existing_atom(value)
+end
@@ -0,0 +1,278 @@
+defmodule EchoShared.Decisions.Voting do
+  @moduledoc """
+  Voting on collaborative decisions.
+
+  1. `open_vote/1` publishes `decisions:vote_required` once per participant
+  2. Participants vote with `cast_vote/4` (the shared `cast_vote` MCP tool)
+  3. As votes arrive, and when the vote closes with `finalize/1` (e.g. at
+     its deadline), the decision type's rule decides whether the decision
+     is approved, rejected or escalated; escalated decisions go up their
+     initiator's reporting chain (`EchoShared.Decisions.Escalation`)
+
+  Rules (majority, supermajority, unanimity, role weights, vetoes, quorum
+  and how abstentions and no-shows count) are per `decision_type`; see
+  `EchoShared.Decisions.Rules`. The decision's `consensus_score` is the
+  weighted share of votes in favour and its `outcome` the full tally.
+  """
+
+  require Logger
+  import Ecto.Query
+
+  alias EchoShared.Decisions.{Escalation, Rules}
+  alias EchoShared.MessageBus
+  alias EchoShared.Repo
+  alias EchoShared.Schemas.{Decision, DecisionVote}
+
+  @doc """
+  Ask every participant of a collaborative decision to vote.
+
+  ## Returns
+
+  - `{:ok, decision}` - `decisions:vote_required` published per participant
+  - `{:error, :not_found}` - No such decision
+  - `{:error, :not_collaborative}` - Only collaborative decisions are voted on
+  - `{:error, :closed}` - The decision is no longer pending
+  - `{:error, :no_participants}` - Nobody to vote
+  """
+  @spec open_vote(Decision.t() | String.t()) :: {:ok, Decision.t()} | {:error, term()}
+  def open_vote(%Decision{} = decision) do
+    with :ok <- check_open(decision) do
+      case decision.participants || [] do
+        [] ->
+          {:error, :no_participants}
+
+        participants ->
+          Enum.each(participants, fn participant ->
+            MessageBus.publish_decision_event(decision.initiator_role, :vote_required, %{
+              decision_id: decision.id,
+              voter: participant,
+              decision_type: decision.decision_type,
+              initiator: decision.initiator_role,
+              context: decision.context
+            })
+          end)
+
+          Logger.info("Vote opened on decision #{decision.id} for #{Enum.join(participants, ", ")}")
+          {:ok, decision}
+      end
+    end
+  end
+
+  def open_vote(decision_id) do
+    case get_decision(decision_id) do
+      nil -> {:error, :not_found}
+      decision -> open_vote(decision)
+    end
+  end
+
+  @doc """
+  Record a participant's vote.
+
+  A participant may change their vote until the decision is finalized.
+  The decision is finalized as soon as its rule decides it (everyone has
+  voted, or a veto).
+
+  ## Parameters
+
+  - `decision_id` - Decision being voted on
+  - `role` - Voting role (must be a participant)
+  - `vote` - `:approve`, `:reject` or `:abstain` (atom or string)
+  - `opts` - Options:
+    - `:confidence` - Weight of the vote, 0.0-1.0 (default: 1.0)
+    - `:rationale` - Why the participant voted this way
+
+  ## Returns
+
+  - `{:ok, vote}` - Vote recorded
+  - `{:error, :not_found}` - No such decision
+  - `{:error, :not_participant}` - `role` isn't a participant
+  - `{:error, :not_collaborative}` / `{:error, :closed}` - Not open for votes
+  - `{:error, changeset}` - Invalid vote or confidence
+  """
+  @spec cast_vote(String.t(), atom() | String.t(), atom() | String.t(), keyword()) ::
+          {:ok, DecisionVote.t()} | {:error, term()}
+  def cast_vote(decision_id, role, vote, opts \\ []) do
+    role = to_string(role)
+
+    result =
+      Repo.transaction(fn ->
+        # Row lock so concurrent last votes finalize the decision once
+        with %Decision{} = decision <- get_decision(decision_id, lock: true),
+             :ok <- check_open(decision),
+             :ok <- check_participant(decision, role),
+             {:ok, recorded} <- upsert_vote(decision, role, vote, opts),
+             {:ok, finalized} <- maybe_finalize(decision) do
+          {recorded, finalized}
+        else
+          nil -> Repo.rollback(:not_found)
+          {:error, reason} -> Repo.rollback(reason)
+        end
+      end)
+
+    case result do
+      {:ok, {recorded, finalized}} ->
+        Logger.info("#{role} voted #{recorded.vote} on decision #{decision_id}")
+        if finalized, do: announce(finalized, role)
+        {:ok, recorded}
+
+      {:error, reason} ->
+        {:error, reason}
+    end
+  end
+
+  @doc """
+  Evaluate the votes cast on a decision so far under its type's rule.
+
+  With `closing?`, participants who haven't voted are treated as no-shows
+  and the rule must decide (see `EchoShared.Decisions.Rules.evaluate/4`).
+  """
+  @spec evaluate(Decision.t(), boolean()) :: Rules.evaluation()
+  def evaluate(%Decision{} = decision, closing? \\ false) do
+    decision.decision_type
+    |> Rules.for_type()
+    |> Rules.evaluate(decision.participants || [], list_votes(decision.id), closing?)
+  end
+
+  @doc """
+  List the votes cast on a decision, oldest first.
+  """
+  @spec list_votes(String.t()) :: [DecisionVote.t()]
+  def list_votes(decision_id) do
+    Repo.all(from v in DecisionVote, where: v.decision_id == ^decision_id, order_by: [asc: v.voted_at, asc: v.id])
+  end
+
+  @doc """
+  Close the vote and decide the decision under its type's rule.
+
+  Participants who haven't voted count as no-shows. Sets `status`
+  (approved, rejected, or escalated without quorum), `consensus_score`,
+  `outcome` (the tally) and `completed_at`, then publishes
+  `decisions:completed` (or `decisions:escalated`) as
+  `:decision_scheduler`, which closes votes at their deadline.
+
+  ## Returns
+
+  - `{:ok, decision}` - The finalized decision
+  - `{:error, :not_found}` - No such decision
+  - `{:error, :not_collaborative}` / `{:error, :closed}` - Not open for votes
+  """
+  @spec finalize(String.t()) :: {:ok, Decision.t()} | {:error, term()}
+  def finalize(decision_id) do
+    Repo.transaction(fn ->
+      with %Decision{} = decision <- get_decision(decision_id, lock: true),
+           :ok <- check_open(decision),
+           {:decided, status, tally} <- evaluate(decision, true),
+           {:ok, decision} <- finalize_locked(decision, status, tally) do
+        decision
+      else
+        nil -> Repo.rollback(:not_found)
+        {:error, reason} -> Repo.rollback(reason)
+      end
+    end)
+    |> case do
+      {:ok, decision} ->
+        announce(decision, :decision_scheduler)
+        {:ok, decision}
+
+      {:error, reason} ->
+        {:error, reason}
+    end
+  end
+
+  ## Private Functions
+
+  defp maybe_finalize(decision) do
+    case evaluate(decision) do
+      {:decided, status, tally} -> finalize_locked(decision, status, tally)
+      {:pending, _tally} -> {:ok, nil}
+    end
+  end
+
+  # Without quorum the decision goes up its initiator's reporting chain, if
+  # there's anybody above the initiator
+  defp finalize_locked(decision, :escalated, tally) do
+    if Escalation.chain(decision.initiator_role) == [] do
+      record_result(decision, :escalated, tally)
+    else
+      with {:ok, decision} <- record_tally(decision, tally) do
+        Escalation.escalate(decision, "No quorum", from: decision.initiator_role)
+      end
+    end
+  end
+
+  defp finalize_locked(decision, status, tally), do: record_result(decision, status, tally)
+
+  defp record_tally(decision, tally) do
+    decision
+    |> Decision.changeset(%{consensus_score: tally.consensus_score, outcome: Map.put(tally, :decided_by, "vote")})
+    |> Repo.update()
+  end
+
+  defp record_result(decision, status, tally) do
+    decision
+    |> Decision.changeset(%{
+      status: status,
+      consensus_score: tally.consensus_score,
+      outcome: Map.put(tally, :decided_by, "vote"),
+      completed_at: DateTime.utc_now() |> DateTime.truncate(:second)
+    })
+    |> Repo.update()
+  end
+
+  # After the commit, so listeners reading the decision see the result;
+  # signed by the voter whose vote decided it, or the scheduler closing it
+  defp announce(decision, from) do
+    Logger.info(
+      "Decision #{decision.id} #{decision.status} after voting (consensus #{Float.round(decision.consensus_score, 2)})"
+    )
+
+    if decision.escalated_to do
+      # Escalated up the initiator's reporting chain, inside our transaction
+      Escalation.announce(decision, decision.initiator_role)
+    else
+      event = if decision.status == :escalated, do: :escalated, else: :completed
+
+      MessageBus.publish_decision_event(from, event, %{
+        decision_id: decision.id,
+        status: decision.status,
+        consensus_score: decision.consensus_score
+      })
+    end
+  end
+
+  defp upsert_vote(decision, role, vote, opts) do
+    %DecisionVote{}
+    |> DecisionVote.changeset(%{
+      decision_id: decision.id,
+      voter_role: role,
+      vote: vote,
+      confidence: Keyword.get(opts, :confidence, 1.0),
+      rationale: Keyword.get(opts, :rationale),
+      voted_at: DateTime.utc_now() |> DateTime.truncate(:second)
+    })
+    |> Repo.insert(
+      on_conflict: {:replace, [:vote, :confidence, :rationale, :voted_at]},
+      conflict_target: [:decision_id, :voter_role],
+      returning: true
+    )
+  end
+
+  defp check_open(%Decision{mode: :collaborative, status: :pending}), do: :ok
+  defp check_open(%Decision{mode: :collaborative}), do: {:error, :closed}
+  defp check_open(%Decision{}), do: {:error, :not_collaborative}
+
+  defp check_participant(decision, role) do
+    if role in (decision.participants || []), do: :ok, else: {:error, :not_participant}
+  end
+
+  defp get_decision(decision_id, opts \\ []) do
+    query = from d in Decision, where: d.id == ^decision_id
+
+    query = if opts[:lock], do: lock(query, "FOR UPDATE"), else: query
+
+    Repo.one(query)
+  rescue
+    # Not a UUID
+    Ecto.Query.CastError -> nil
+  end
+end
@@ -0,0 +1,53 @@
+defmodule EchoShared.LLM.Backend do
+  @moduledoc """
+  Routes an agent's LLM chat to the backend configured for its role.
+
+  Backends (see `EchoShared.LLM.Config.get_backend/1`):
+
+  - `:ollama` - `EchoShared.LLM.Client.chat/3` against local Ollama
+  - `:sampling` - `EchoShared.MCP.Sampling.create_message/2` on the
+    connected MCP client's model, or Ollama when the client can't sample
+  - `:auto` - Ollama, retried on the client's model if Ollama fails
+
+  `DecisionHelper` and `Session` call this instead of `Client.chat/3`
+  so every consultation honours the role's backend.
+  """
+
+  require Logger
+
+  alias EchoShared.LLM.{Client, Config}
+  alias EchoShared.MCP.Sampling
+
+  @doc """
+  Send a chat completion for `role` through its configured backend.
+
+  Takes the same `model`, `messages` and `opts` as `Client.chat/3`. The
+  model name is passed to the MCP client as a hint.
+
+  ## Returns
+
+  - `{:ok, response_text}` on success
+  - `{:error, reason}` on failure
+  """
+  @spec chat(atom(), String.t(), [map()], map()) :: {:ok, String.t()} | {:error, term()}
+  def chat(role, model, messages, opts \\ %{}) do
+    case {Config.get_backend(role), Sampling.available?()} do
+      {:sampling, true} ->
+        Logger.info("#{role}: Sampling from MCP client model")
+        Sampling.create_message(messages, Map.put(opts, :model, model))
+
+      {:auto, true} ->
+        case Client.chat(model, messages, opts) do
+          {:ok, response} ->
+            {:ok, response}
+
+          {:error, reason} ->
+            Logger.warning("#{role}: Ollama failed (#{inspect(reason)}), sampling from MCP client model")
+            Sampling.create_message(messages, Map.put(opts, :model, model))
+        end
+
+      _ollama_or_no_client ->
+        Client.chat(model, messages, opts)
+    end
+  end
+end
@@ -31,10 +31,13 @@ defmodule EchoShared.LLM.Client do
 
   require Logger
 
+  alias EchoShared.MCP.Progress
+
   @default_endpoint "http://localhost:11434"
   @default_timeout 180_000  # 180 seconds (3 minutes) - increased for cold model loading
   @default_temperature 0.7
   @default_max_tokens 2000
+  @progress_every_tokens 25
 
   @doc """
   Send a chat completion request to Ollama.
@@ -50,6 +53,12 @@ defmodule EchoShared.LLM.Client do
   - `{:ok, response_text}` on success
   - `{:error, reason}` on failure
 
+  ## Progress
+
+  When called from an MCP tool that was invoked with a progress token
+  (see `EchoShared.MCP.Progress`), the request is streamed so the client
+  receives model-loading, generation and token-count progress updates.
+
   ## Example
 
       messages = [
@@ -71,19 +80,10 @@ defmodule EchoShared.LLM.Client do
 
     Logger.debug("LLM chat request to #{model}: #{length(messages)} messages")
 
-    case post(url, payload, opts) do
-      {:ok, %{status: 200, body: body}} ->
-        response_text = get_in(body, ["message", "content"]) || ""
-        Logger.debug("LLM chat response: #{String.length(response_text)} chars")
-        {:ok, response_text}
-
-      {:ok, %{status: status, body: body}} ->
-        Logger.error("LLM API error (#{status}): #{inspect(body)}")
-        {:error, {:api_error, status, body}}
-
-      {:error, reason} ->
-        Logger.error("LLM request failed: #{inspect(reason)}")
-        {:error, reason}
+    if Progress.active?() do
+      stream_chat(url, payload, opts)
+    else
+      request_chat(url, payload, opts)
     end
   rescue
     e ->
@@ -211,6 +211,93 @@ defmodule EchoShared.LLM.Client do
 
   ## Private Functions
 
+  defp request_chat(url, payload, opts) do
+    case post(url, payload, opts) do
+      {:ok, %{status: 200, body: body}} ->
+        response_text = get_in(body, ["message", "content"]) || ""
+        Logger.debug("LLM chat response: #{String.length(response_text)} chars")
+        {:ok, response_text}
+
+      {:ok, %{status: status, body: body}} ->
+        Logger.error("LLM API error (#{status}): #{inspect(body)}")
+        {:error, {:api_error, status, body}}
+
+      {:error, reason} ->
+        Logger.error("LLM request failed: #{inspect(reason)}")
+        {:error, reason}
+    end
+  end
+
+  # Streams the chat response (Ollama NDJSON) and reports progress per phase:
+  # model loading until the first chunk, then generation with token counts.
+  defp stream_chat(url, payload, opts) do
+    progress = Progress.current()
+    Progress.notify(progress, 0, "Loading model #{payload.model}...", nil)
+
+    into = fn {:data, data}, {req, resp} ->
+      resp =
+        Req.Response.update_private(resp, :ollama_stream, %{buffer: "", content: [], tokens: 0}, fn acc ->
+          consume_stream_chunk(acc, data, progress)
+        end)
+
+      {:cont, {req, resp}}
+    end
+
+    result =
+      Req.post(url,
+        json: %{payload | stream: true},
+        receive_timeout: opts[:timeout] || @default_timeout,
+        retry: false,
+        into: into
+      )
+
+    case result do
+      {:ok, %{status: 200} = resp} ->
+        stream = Req.Response.get_private(resp, :ollama_stream, %{content: [], tokens: 0})
+        response_text = IO.iodata_to_binary(stream.content)
+
+        Progress.notify(progress, stream.tokens + 1, "Generation complete (#{stream.tokens} tokens)", nil)
+        Logger.debug("LLM chat response (streamed): #{String.length(response_text)} chars")
+        {:ok, response_text}
+
+      {:ok, %{status: status} = resp} ->
+        body = Req.Response.get_private(resp, :ollama_stream, %{buffer: ""}).buffer
+        Logger.error("LLM API error (#{status}): #{inspect(body)}")
+        {:error, {:api_error, status, body}}
+
+      {:error, reason} ->
+        Logger.error("LLM request failed: #{inspect(reason)}")
+        {:error, reason}
+    end
+  end
+
+  defp consume_stream_chunk(acc, data, progress) do
+    {lines, [buffer]} = (acc.buffer <> data) |> String.split("\n") |> Enum.split(-1)
+
+    Enum.reduce(lines, %{acc | buffer: buffer}, fn line, acc ->
+      case Jason.decode(line) do
+        {:ok, %{"message" => %{"content" => piece}}} ->
+          tokens = acc.tokens + 1
+
+          cond do
+            tokens == 1 ->
+              Progress.notify(progress, tokens, "Generating response...", nil)
+
+            rem(tokens, @progress_every_tokens) == 0 ->
+              Progress.notify(progress, tokens, "Generated #{tokens} tokens", nil)
+
+            true ->
+              :ok
+          end
+
+          %{acc | content: [acc.content, piece], tokens: tokens}
+
+        _ ->
+          acc
+      end
+    end)
+  end
+
   defp get_endpoint do
     System.get_env("OLLAMA_ENDPOINT") ||
       Application.get_env(:echo_shared, :ollama_endpoint, @default_endpoint)
@@ -20,6 +20,9 @@ defmodule EchoShared.LLM.Config do
       CEO_MODEL=qwen2.5:14b
       CTO_MODEL=deepseek-coder:33b
 
+  The backend serving each role (local Ollama or the MCP client's model)
+  is chosen by `get_backend/1`.
+
   Or in application config:
 
       config :echo_shared, :agent_models, %{
@@ -219,6 +222,30 @@ defmodule EchoShared.LLM.Config do
     Map.merge(defaults, opts)
   end
 
+  @doc """
+  Get the LLM backend for a specific agent role.
+
+  - `:ollama` - Local Ollama only (default)
+  - `:sampling` - The connected MCP client's model via `sampling/createMessage`,
+    falling back to Ollama when the client can't sample
+  - `:auto` - Ollama first, falling back to MCP sampling when Ollama fails
+    (unavailable or timed out)
+
+  Configured via environment variable (e.g., `CEO_LLM_BACKEND=sampling`) or:
+
+      config :echo_shared, :llm_backends, %{ceo: :auto}
+  """
+  def get_backend(role) when is_atom(role) do
+    env_var = role |> Atom.to_string() |> String.upcase() |> then(&"#{&1}_LLM_BACKEND")
+
+    case System.get_env(env_var) || System.get_env("LLM_BACKEND") do
+      "sampling" -> :sampling
+      "auto" -> :auto
+      "ollama" -> :ollama
+      _ -> Application.get_env(:echo_shared, :llm_backends, %{}) |> Map.get(role, :ollama)
+    end
+  end
+
   @doc """
   Check if LLM integration is enabled for a specific role.
 
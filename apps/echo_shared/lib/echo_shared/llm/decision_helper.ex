@@ -22,7 +22,7 @@ defmodule EchoShared.LLM.DecisionHelper do
   """
 
   require Logger
-  alias EchoShared.LLM.{Client, Config}
+  alias EchoShared.LLM.{Backend, Config}
 
   @doc """
   Analyze a decision and provide AI-powered insights.
@@ -71,7 +71,7 @@ defmodule EchoShared.LLM.DecisionHelper do
 
     Logger.info("#{role}: Consulting LLM (#{model}) for decision analysis")
 
-    case Client.chat(model, messages, opts) do
+    case Backend.chat(role, model, messages, opts) do
       {:ok, analysis} ->
         {:ok, analysis}
 
@@ -126,7 +126,7 @@ defmodule EchoShared.LLM.DecisionHelper do
 
       Logger.info("#{role}: Generating decision rationale with #{model}")
 
-      Client.chat(model, messages, opts)
+      Backend.chat(role, model, messages, opts)
     end
   end
 
@@ -165,7 +165,7 @@ defmodule EchoShared.LLM.DecisionHelper do
 
       Logger.info("#{role}: Evaluating options with #{model}")
 
-      Client.chat(model, messages, opts)
+      Backend.chat(role, model, messages, opts)
     end
   end
 
@@ -206,7 +206,7 @@ defmodule EchoShared.LLM.DecisionHelper do
 
       Logger.info("#{role}: Consulting #{model}")
 
-      Client.chat(model, messages, opts)
+      Backend.chat(role, model, messages, opts)
     end
   end
 
@@ -314,7 +314,7 @@ defmodule EchoShared.LLM.DecisionHelper do
 
       Logger.info("#{role}: Generating implementation with #{model}")
 
-      Client.chat(model, messages, opts)
+      Backend.chat(role, model, messages, opts)
     end
   end
 
@@ -34,7 +34,7 @@ defmodule EchoShared.LLM.Session do
   use GenServer
   require Logger
 
-  alias EchoShared.LLM.{Client, Config, ContextBuilder}
+  alias EchoShared.LLM.{Backend, Config, ContextBuilder}
   alias EchoShared.Repo
   alias EchoShared.Schemas.LlmSession
   import Ecto.Query
@@ -181,7 +181,7 @@ defmodule EchoShared.LLM.Session do
     Logger.debug("#{session.agent_role} session #{session.session_id}: Query turn #{session.turn_count + 1}")
 
     # Query LLM
-    case Client.chat(model, messages, gen_opts) do
+    case Backend.chat(session.agent_role, model, messages, gen_opts) do
       {:ok, response} ->
         # Update session
         updated_session = update_session(session, question, response, total_tokens)
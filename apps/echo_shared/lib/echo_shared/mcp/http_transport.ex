@@ -0,0 +1,454 @@
+defmodule EchoShared.MCP.HTTPTransport do
+  @moduledoc """
+  MCP streamable-HTTP transport for ECHO agents.
+
+  Lets a single long-running agent process serve several MCP clients,
+  dashboards and scripts at once, instead of each client spawning its own
+  stdio copy of the agent.
+
+  ## Endpoint
+
+  All traffic goes through one endpoint, `/mcp`:
+
+  - `POST /mcp` - Send one JSON-RPC message. Requests are answered with
+    `application/json`, or with a `text/event-stream` when the client accepts
+    it and the call is a `tools/call` (progress notifications are streamed
+    before the final response). Notifications, and client responses to
+    server requests such as `sampling/createMessage` (which are only sent on
+    a tool call's event stream), are answered with `202 Accepted`.
+  - `GET /mcp` - Open an SSE stream for server-initiated notifications
+    (e.g. `notifications/tools/list_changed`, `notifications/message`).
+  - `DELETE /mcp` - Terminate the session.
+
+  The `initialize` response carries an `Mcp-Session-Id` header that clients
+  must send on every subsequent request. Sessions idle for longer than
+  `MCP_HTTP_SESSION_IDLE_SECONDS` (default: 30 minutes) expire; see
+  `EchoShared.MCP.HTTPTransport.Sessions`.
+
+  ## Configuration
+
+  Ports are configured per agent, by environment variable or app config:
+
+      CEO_MCP_HTTP_PORT=4101
+
+      config :echo_shared, :mcp_http_ports, %{ceo: 4101, cto: 4102}
+
+  The server binds to `127.0.0.1` unless `MCP_HTTP_BIND` is set, and rejects
+  browser requests whose `Origin` is not local.
+
+  ## Usage
+
+      # In an agent's supervision tree or CLI
+      EchoShared.MCP.HTTPTransport.start_link(server: Ceo)
+  """
+
+  @behaviour Plug
+
+  import Plug.Conn
+  require Logger
+
+  alias EchoShared.MCP.{Logging, Progress, Protocol, Sampling, ToolRegistry}
+  alias EchoShared.MCP.HTTPTransport.Sessions
+
+  @registry __MODULE__.Registry
+  @task_sup __MODULE__.TaskSupervisor
+
+  @session_header "mcp-session-id"
+  @version_header "mcp-protocol-version"
+  @keepalive_ms 15_000
+
+  @default_ports %{
+    ceo: 4101,
+    cto: 4102,
+    chro: 4103,
+    operations_head: 4104,
+    product_manager: 4105,
+    senior_architect: 4106,
+    uiux_engineer: 4107,
+    senior_developer: 4108,
+    test_lead: 4109,
+    delegator: 4100
+  }
+
+  ## Supervision
+
+  def child_spec(opts) do
+    %{
+      id: __MODULE__,
+      start: {__MODULE__, :start_link, [opts]},
+      type: :supervisor
+    }
+  end
+
+  @doc """
+  Start the HTTP transport for an MCP server module.
+
+  ## Options
+
+  - `:server` - (Required) Module using `EchoShared.MCP.Server`
+  - `:port` - Port to listen on (default: `port_for/1` of the agent's role)
+  """
+  def start_link(opts) do
+    server = Keyword.fetch!(opts, :server)
+    port = Keyword.get_lazy(opts, :port, fn -> port_for(server.agent_info().role) end)
+
+    Logger.info("Starting #{server.agent_info().name} MCP HTTP transport on port #{port}")
+
+    children = [
+      {Registry, keys: :duplicate, name: @registry},
+      Sessions,
+      {Task.Supervisor, name: @task_sup},
+      {Bandit, plug: {__MODULE__, server: server}, port: port, ip: bind_ip()}
+    ]
+
+    Supervisor.start_link(children, strategy: :one_for_one, name: __MODULE__)
+  end
+
+  @doc """
+  Get the configured HTTP port for an agent role.
+  """
+  @spec port_for(atom()) :: pos_integer()
+  def port_for(role) do
+    env_var = role |> Atom.to_string() |> String.upcase() |> then(&"#{&1}_MCP_HTTP_PORT")
+
+    case System.get_env(env_var) do
+      nil ->
+        Application.get_env(:echo_shared, :mcp_http_ports, %{})
+        |> Map.get(role, Map.get(@default_ports, role, 4100))
+
+      port ->
+        String.to_integer(port)
+    end
+  end
+
+  @doc """
+  Send a server-initiated notification to every open SSE stream.
+
+  No-op when the HTTP transport isn't running.
+  """
+  @spec broadcast(map()) :: :ok
+  def broadcast(notification) do
+    if Process.whereis(@registry) do
+      Registry.dispatch(@registry, :streams, fn entries ->
+        for {pid, _session_id} <- entries, do: send(pid, {:mcp_notification, notification})
+      end)
+    end
+
+    :ok
+  end
+
+  ## Plug
+
+  @impl Plug
+  def init(opts), do: Keyword.fetch!(opts, :server)
+
+  @impl Plug
+  def call(%Plug.Conn{path_info: ["mcp"]} = conn, server) do
+    if allowed_origin?(conn) do
+      handle(conn.method, conn, server)
+    else
+      send_resp(conn, 403, "Forbidden origin")
+    end
+  end
+
+  def call(conn, _server), do: send_resp(conn, 404, "Not found")
+
+  ## Private Functions
+
+  defp handle("POST", conn, server) do
+    {:ok, body, conn} = read_body(conn)
+
+    case Protocol.parse_request(body) do
+      {:ok, %{method: "initialize"} = request} ->
+        response = server.handle_rpc(request)
+
+        session_id =
+          Sessions.create(
+            get_in(response, [:result, :protocolVersion]),
+            request.params["capabilities"] || %{}
+          )
+
+        conn
+        |> put_resp_header(@session_header, session_id)
+        |> send_json(200, response)
+
+      {:ok, request} ->
+        with {:ok, session_id} <- fetch_session(conn) do
+          handle_post(conn, server, session_id, request)
+        else
+          {:error, status, message} -> send_resp(conn, status, message)
+        end
+
+      {:response, response} ->
+        with {:ok, session_id} <- fetch_session(conn) do
+          route_client_response(session_id, response)
+          send_resp(conn, 202, "")
+        else
+          {:error, status, message} -> send_resp(conn, status, message)
+        end
+
+      {:error, {:parse_error, reason}} ->
+        error_codes = Protocol.error_codes()
+        response = Protocol.error_response(nil, error_codes.parse_error, "Parse error", inspect(reason))
+        send_json(conn, 400, response)
+
+      {:error, {:invalid_request, message}} ->
+        error_codes = Protocol.error_codes()
+        send_json(conn, 400, Protocol.error_response(nil, error_codes.invalid_request, message))
+    end
+  end
+
+  defp handle("GET", conn, server) do
+    with true <- accepts_sse?(conn),
+         {:ok, session_id} <- fetch_session(conn) do
+      {:ok, _} = Registry.register(@registry, :streams, session_id)
+      :ok = ToolRegistry.subscribe(server)
+      :ok = Logging.attach(self(), session_id)
+
+      conn
+      |> start_sse()
+      |> stream_notifications(session_id)
+    else
+      false -> send_resp(conn, 406, "GET requires Accept: text/event-stream")
+      {:error, status, message} -> send_resp(conn, status, message)
+    end
+  end
+
+  defp handle("DELETE", conn, _server) do
+    with {:ok, session_id} <- fetch_session(conn) do
+      Sessions.delete(session_id)
+      send_resp(conn, 200, "")
+    else
+      {:error, status, message} -> send_resp(conn, status, message)
+    end
+  end
+
+  defp handle(_method, conn, _server) do
+    conn
+    |> put_resp_header("allow", "GET, POST, DELETE")
+    |> send_resp(405, "Method not allowed")
+  end
+
+  defp handle_post(conn, _server, session_id, %{method: "notifications/cancelled", params: params}) do
+    Registry.dispatch(@registry, {:request, session_id, params["requestId"]}, fn entries ->
+      for {_pid, task_pid} <- entries, do: Task.Supervisor.terminate_child(@task_sup, task_pid)
+    end)
+
+    send_resp(conn, 202, "")
+  end
+
+  defp handle_post(conn, server, _session_id, %{id: nil} = request) do
+    server.handle_rpc(request)
+    send_resp(conn, 202, "")
+  end
+
+  defp handle_post(conn, server, session_id, %{method: "tools/call", id: id} = request) do
+    stream? = accepts_sse?(conn)
+    conn_pid = self()
+    progress_token = get_in(request.params, ["_meta", "progressToken"])
+    sampling? = stream? and Map.has_key?(session_capabilities(session_id), "sampling")
+
+    task =
+      Task.Supervisor.async_nolink(@task_sup, fn ->
+        if stream? and progress_token, do: Progress.register(progress_token, conn_pid)
+        # Sampling requests need the SSE stream to reach the client
+        Sampling.register(conn_pid, sampling?)
+        server.handle_rpc(request)
+      end)
+
+    # Lets a `notifications/cancelled` on another connection find this task
+    {:ok, _} = Registry.register(@registry, {:request, session_id, id}, task.pid)
+
+    conn =
+      if stream? do
+        conn
+        |> start_sse()
+        |> await_tool_call(task, id, session_id)
+      else
+        case Task.yield(task, :infinity) do
+          {:ok, response} -> send_json(conn, 200, response)
+          {:exit, reason} -> send_json(conn, 200, tool_exit_response(id, reason))
+        end
+      end
+
+    unregister_requests(session_id, id)
+    conn
+  end
+
+  defp handle_post(conn, server, session_id, request) do
+    send_json(conn, 200, server.handle_rpc(request, session_id))
+  end
+
+  defp await_tool_call(conn, %Task{ref: ref} = task, id, session_id) do
+    receive do
+      {:mcp_notification, notification} ->
+        case send_event(conn, notification) do
+          {:ok, conn} ->
+            await_tool_call(conn, task, id, session_id)
+
+          {:error, _closed} ->
+            Task.shutdown(task, :brutal_kill)
+            conn
+        end
+
+      {:mcp_client_request, from, request_ref, method, params} ->
+        request_id = "echo-" <> (:crypto.strong_rand_bytes(8) |> Base.encode16(case: :lower))
+
+        # The client answers with a separate POST, routed by route_client_response/2
+        {:ok, _} = Registry.register(@registry, {:client_request, session_id, request_id}, {from, request_ref})
+
+        case send_event(conn, Protocol.request(request_id, method, params)) do
+          {:ok, conn} ->
+            await_tool_call(conn, task, id, session_id)
+
+          {:error, _closed} ->
+            Task.shutdown(task, :brutal_kill)
+            conn
+        end
+
+      {:mcp_client_responded, request_id} ->
+        Registry.unregister(@registry, {:client_request, session_id, request_id})
+        await_tool_call(conn, task, id, session_id)
+
+      {^ref, response} ->
+        Process.demonitor(ref, [:flush])
+        {_, conn} = send_event(conn, response)
+        conn
+
+      {:DOWN, ^ref, :process, _pid, reason} when reason in [:shutdown, :killed] ->
+        # Cancelled: close the stream without a response
+        conn
+
+      {:DOWN, ^ref, :process, _pid, reason} ->
+        {_, conn} = send_event(conn, tool_exit_response(id, reason))
+        conn
+    end
+  end
+
+  defp route_client_response(session_id, %{id: id} = response) do
+    case Registry.lookup(@registry, {:client_request, session_id, id}) do
+      [{stream_pid, {from, ref}} | _] ->
+        reply = if response.error, do: {:error, response.error}, else: {:ok, response.result}
+        send(from, {:mcp_client_response, ref, reply})
+        # Only the registering process can unregister the entry
+        send(stream_pid, {:mcp_client_responded, id})
+
+      [] ->
+        Logger.warning("Ignoring response to unknown request #{inspect(id)}")
+    end
+  end
+
+  # Bandit serves keep-alive requests from the same process, so entries
+  # don't go away with the request
+  defp unregister_requests(session_id, id) do
+    Registry.unregister(@registry, {:request, session_id, id})
+
+    for {:client_request, ^session_id, _} = key <- Registry.keys(@registry, self()) do
+      Registry.unregister(@registry, key)
+    end
+
+    :ok
+  end
+
+  defp tool_exit_response(id, reason) do
+    error_codes = Protocol.error_codes()
+    message = if reason in [:shutdown, :killed], do: "Request cancelled", else: "Tool execution failed"
+
+    Protocol.error_response(id, error_codes.internal_error, message, inspect(reason))
+  end
+
+  defp stream_notifications(conn, session_id) do
+    receive do
+      {:mcp_notification, notification} ->
+        case send_event(conn, notification) do
+          {:ok, conn} -> stream_notifications(conn, session_id)
+          {:error, _closed} -> conn
+        end
+    after
+      @keepalive_ms ->
+        # An open stream keeps its session alive; an expired one ends it
+        with {:ok, _session} <- Sessions.touch(session_id),
+             {:ok, conn} <- chunk(conn, ": keepalive\n\n") do
+          stream_notifications(conn, session_id)
+        else
+          _ -> conn
+        end
+    end
+  end
+
+  defp start_sse(conn) do
+    conn
+    |> put_resp_content_type("text/event-stream")
+    |> put_resp_header("cache-control", "no-cache")
+    |> send_chunked(200)
+  end
+
+  defp send_event(conn, message) do
+    chunk(conn, "event: message\ndata: #{Jason.encode!(message)}\n\n")
+  end
+
+  defp send_json(conn, status, response) do
+    conn
+    |> put_resp_content_type("application/json")
+    |> send_resp(status, Jason.encode!(response))
+  end
+
+  defp session_capabilities(session_id) do
+    case Sessions.touch(session_id) do
+      {:ok, session} -> session.client_capabilities
+      :error -> %{}
+    end
+  end
+
+  defp fetch_session(conn) do
+    with :ok <- check_protocol_version(conn) do
+      case get_req_header(conn, @session_header) do
+        [session_id | _] ->
+          case Sessions.touch(session_id) do
+            {:ok, _session} -> {:ok, session_id}
+            :error -> {:error, 404, "Unknown session"}
+          end
+
+        [] ->
+          {:error, 400, "Missing Mcp-Session-Id header"}
+      end
+    end
+  end
+
+  # Clients on 2025-06-18+ send the negotiated version on every request
+  defp check_protocol_version(conn) do
+    case get_req_header(conn, @version_header) do
+      [] ->
+        :ok
+
+      [version | _] ->
+        if version in Protocol.supported_versions() do
+          :ok
+        else
+          {:error, 400, "Unsupported MCP-Protocol-Version: #{version}"}
+        end
+    end
+  end
+
+  defp accepts_sse?(conn) do
+    conn
+    |> get_req_header("accept")
+    |> Enum.any?(&String.contains?(&1, "text/event-stream"))
+  end
+
+  defp allowed_origin?(conn) do
+    case get_req_header(conn, "origin") do
+      [] -> true
+      [origin | _] -> URI.parse(origin).host in ["localhost", "127.0.0.1", "::1"]
+    end
+  end
+
+  defp bind_ip do
+    {:ok, ip} =
+      System.get_env("MCP_HTTP_BIND", "127.0.0.1")
+      |> String.to_charlist()
+      |> :inet.parse_address()
+
+    ip
+  end
+end
@@ -0,0 +1,129 @@
+defmodule EchoShared.MCP.HTTPTransport.Sessions do
+  @moduledoc """
+  Session table for the MCP HTTP transport.
+
+  Sessions are created by `initialize` and touched by every request that
+  carries their `Mcp-Session-Id`, and by the keepalives of an open GET
+  stream. Every minute, sessions idle for longer than the idle timeout are
+  dropped; their clients get `404 Unknown session` and must initialize
+  again, as the MCP spec prescribes.
+
+  ## Configuration
+
+      MCP_HTTP_SESSION_IDLE_SECONDS=1800
+
+      config :echo_shared, :mcp_http_session_idle_seconds, 1800
+  """
+
+  use GenServer
+  require Logger
+
+  alias EchoShared.MCP.Logging
+
+  @sweep_interval 60_000 # 1 minute
+  @default_idle_seconds 1800
+
+  ## Client API
+
+  def start_link(opts \\ []) do
+    GenServer.start_link(__MODULE__, opts, name: __MODULE__)
+  end
+
+  @doc """
+  Create a session and return its id.
+  """
+  @spec create(String.t() | nil, map()) :: String.t()
+  def create(protocol_version, client_capabilities) do
+    GenServer.call(__MODULE__, {:create, protocol_version, client_capabilities})
+  end
+
+  @doc """
+  Fetch a session, marking it as active.
+  """
+  @spec touch(String.t()) :: {:ok, map()} | :error
+  def touch(session_id) do
+    GenServer.call(__MODULE__, {:touch, session_id})
+  end
+
+  @doc """
+  Terminate a session.
+  """
+  @spec delete(String.t()) :: :ok
+  def delete(session_id) do
+    GenServer.call(__MODULE__, {:delete, session_id})
+  end
+
+  @doc """
+  Get the configured idle timeout in seconds.
+  """
+  @spec idle_seconds() :: pos_integer()
+  def idle_seconds do
+    case System.get_env("MCP_HTTP_SESSION_IDLE_SECONDS") do
+      nil -> Application.get_env(:echo_shared, :mcp_http_session_idle_seconds, @default_idle_seconds)
+      seconds -> String.to_integer(seconds)
+    end
+  end
+
+  ## Server Callbacks
+
+  @impl true
+  def init(_opts) do
+    schedule_sweep()
+    {:ok, %{}}
+  end
+
+  @impl true
+  def handle_call({:create, protocol_version, client_capabilities}, _from, sessions) do
+    session_id = "mcp_" <> (:crypto.strong_rand_bytes(16) |> Base.encode16(case: :lower))
+    now = DateTime.utc_now()
+
+    session = %{
+      protocol_version: protocol_version,
+      client_capabilities: client_capabilities,
+      created_at: now,
+      last_seen_at: now
+    }
+
+    {:reply, session_id, Map.put(sessions, session_id, session)}
+  end
+
+  def handle_call({:touch, session_id}, _from, sessions) do
+    case Map.fetch(sessions, session_id) do
+      {:ok, session} ->
+        session = %{session | last_seen_at: DateTime.utc_now()}
+        {:reply, {:ok, session}, Map.put(sessions, session_id, session)}
+
+      :error ->
+        {:reply, :error, sessions}
+    end
+  end
+
+  def handle_call({:delete, session_id}, _from, sessions) do
+    Logging.forget(session_id)
+    {:reply, :ok, Map.delete(sessions, session_id)}
+  end
+
+  @impl true
+  def handle_info(:sweep, sessions) do
+    cutoff = DateTime.add(DateTime.utc_now(), -idle_seconds(), :second)
+    {expired, active} = Map.split_with(sessions, fn {_id, s} -> DateTime.compare(s.last_seen_at, cutoff) == :lt end)
+
+    if map_size(expired) > 0 do
+      Enum.each(Map.keys(expired), &Logging.forget/1)
+      Logger.info("Expired #{map_size(expired)} idle MCP HTTP session(s)")
+    end
+
+    schedule_sweep()
+    {:noreply, active}
+  end
+
+  def handle_info(_msg, sessions) do
+    {:noreply, sessions}
+  end
+
+  ## Private Functions
+
+  defp schedule_sweep do
+    Process.send_after(self(), :sweep, @sweep_interval)
+  end
+end
@@ -0,0 +1,150 @@
+defmodule EchoShared.MCP.Logging do
+  @moduledoc """
+  MCP logging capability (`logging/setLevel`) bridged to Elixir `Logger`.
+
+  Installs an Erlang `:logger` handler that forwards the agent's `Logger`
+  events to connected MCP clients as `notifications/message`, so traces
+  such as `"DecisionEngine: should_escalate 0.62"` show up in the client
+  instead of being lost on stderr.
+
+  Transports attach the process that writes to the client (the stdio loop,
+  or an HTTP SSE stream) with `attach/2`, under the client's session.
+  Events are delivered to it as `{:mcp_notification, notification}`.
+
+  ## Levels
+
+  MCP log levels match the Erlang `:logger` levels:
+  `debug`, `info`, `notice`, `warning`, `error`, `critical`, `alert`,
+  `emergency`. Each session only gets events at or above the level it
+  selected (default: `warning`), so one client asking for `debug` doesn't
+  flood the others. The agent's own `Logger` level is left alone: events
+  below it are never produced, whatever a client selects.
+  """
+
+  alias EchoShared.MCP.Protocol
+
+  @handler_id :echo_mcp_logging
+  @levels ~w(debug info notice warning error critical alert emergency)
+  @default_level :warning
+
+  @doc """
+  MCP log level names, least severe first.
+  """
+  @spec levels() :: [String.t()]
+  def levels, do: @levels
+
+  @doc """
+  Forward log events to `pid` as MCP notifications.
+
+  Installs the `:logger` handler on first use. Dead processes are pruned
+  on the next attach.
+
+  ## Parameters
+
+  - `pid` - Process that writes to the client
+  - `session` - Client session whose level applies (default: `pid`)
+  """
+  @spec attach(pid(), term()) :: :ok
+  def attach(pid, session \\ nil) do
+    update_config(fn %{targets: targets} = config ->
+      targets =
+        targets
+        |> Map.filter(fn {target, _session} -> Process.alive?(target) end)
+        |> Map.put(pid, session || pid)
+
+      %{config | targets: targets}
+    end)
+  end
+
+  @doc """
+  Set the minimum level forwarded to a session (`logging/setLevel`).
+
+  ## Returns
+
+  - `:ok` on success
+  - `{:error, :invalid_level}` - Not an MCP log level
+  """
+  @spec set_level(String.t(), term()) :: :ok | {:error, :invalid_level}
+  def set_level(level, session) when level in @levels do
+    level = String.to_existing_atom(level)
+    update_config(&%{&1 | levels: Map.put(&1.levels, session, level)})
+  end
+
+  def set_level(_level, _session), do: {:error, :invalid_level}
+
+  @doc """
+  Drop a session's level and attached processes once it ends.
+  """
+  @spec forget(term()) :: :ok
+  def forget(session) do
+    update_config(fn config ->
+      %{
+        config
+        | targets: Map.reject(config.targets, fn {_pid, target_session} -> target_session == session end),
+          levels: Map.delete(config.levels, session)
+      }
+    end)
+  end
+
+  ## :logger handler callback
+
+  @doc false
+  def log(%{level: level, msg: msg, meta: meta}, %{config: %{targets: targets, levels: levels}}) do
+    recipients =
+      for {pid, session} <- targets,
+          :logger.compare_levels(level, Map.get(levels, session, @default_level)) != :lt,
+          do: pid
+
+    if recipients != [] do
+      params = %{
+        level: Atom.to_string(level),
+        logger: logger_name(meta),
+        data: format_message(msg)
+      }
+
+      notification = Protocol.notification("notifications/message", params)
+
+      for pid <- recipients, do: send(pid, {:mcp_notification, notification})
+    end
+
+    :ok
+  end
+
+  ## Private Functions
+
+  defp update_config(fun) do
+    :ok = ensure_handler()
+    {:ok, %{config: config}} = :logger.get_handler_config(@handler_id)
+    :logger.update_handler_config(@handler_id, :config, fun.(config))
+  end
+
+  defp ensure_handler do
+    case :logger.get_handler_config(@handler_id) do
+      {:ok, _config} ->
+        :ok
+
+      {:error, {:not_found, @handler_id}} ->
+        # Levels are filtered per session in log/2
+        case :logger.add_handler(@handler_id, __MODULE__, %{
+               level: :all,
+               filters: [elixir_only: {&:logger_filters.domain/2, {:stop, :not_equal, [:elixir]}}],
+               config: %{targets: %{}, levels: %{}}
+             }) do
+          :ok -> :ok
+          {:error, {:already_exist, @handler_id}} -> :ok
+        end
+    end
+  end
+
+  defp format_message({:string, chardata}), do: IO.chardata_to_string(chardata)
+  defp format_message({:report, report}), do: inspect(report)
+
+  defp format_message({format, args}) do
+    format
+    |> :io_lib.format(args)
+    |> IO.chardata_to_string()
+  end
+
+  defp logger_name(%{mfa: {module, _function, _arity}}), do: inspect(module)
+  defp logger_name(_meta), do: "echo"
+end
@@ -0,0 +1,85 @@
+defmodule EchoShared.MCP.Progress do
+  @moduledoc """
+  MCP progress notifications (`notifications/progress`) for long-running tools.
+
+  When a `tools/call` request carries `_meta.progressToken`, the server
+  registers the token in the process running the tool. Any code executing
+  in that process (e.g. `EchoShared.LLM.Client.chat/3`) can then report
+  progress without knowing it is being called over MCP:
+
+      Progress.report(0, "Loading model llama3.1:8b...")
+      Progress.report(120, "Generated 120 tokens")
+
+  Outside a tool call with a progress token, reporting is a no-op.
+
+  Per the MCP spec, `progress` must increase with every notification;
+  reports that don't advance past the last value sent are dropped.
+  """
+
+  alias EchoShared.MCP.Protocol
+
+  @key :echo_mcp_progress
+
+  @type t :: %{token: String.t() | integer(), server: pid()}
+
+  @doc """
+  Register a progress token for the current process.
+
+  Notifications are delivered to `server` as `{:mcp_notification, map}`.
+  """
+  @spec register(String.t() | integer(), pid()) :: :ok
+  def register(token, server) do
+    Process.put(@key, %{token: token, server: server})
+    Process.delete({@key, :last})
+    :ok
+  end
+
+  @doc """
+  Get the progress context registered for the current process, if any.
+
+  Capture this before handing work to a callback that might run elsewhere
+  and pass it to `notify/4`.
+  """
+  @spec current() :: t() | nil
+  def current, do: Process.get(@key)
+
+  @doc """
+  Check whether the current process is reporting progress to a client.
+  """
+  @spec active?() :: boolean()
+  def active?, do: current() != nil
+
+  @doc """
+  Report progress from the current process.
+  """
+  @spec report(number(), String.t() | nil, number() | nil) :: :ok
+  def report(progress, message \\ nil, total \\ nil) do
+    notify(current(), progress, message, total)
+  end
+
+  @doc """
+  Report progress for an explicit progress context.
+  """
+  @spec notify(t() | nil, number(), String.t() | nil, number() | nil) :: :ok
+  def notify(nil, _progress, _message, _total), do: :ok
+
+  def notify(%{token: token, server: server}, progress, message, total) do
+    last = Process.get({@key, :last})
+
+    if is_nil(last) or progress > last do
+      Process.put({@key, :last}, progress)
+
+      params =
+        %{progressToken: token, progress: progress}
+        |> maybe_put(:total, total)
+        |> maybe_put(:message, message)
+
+      send(server, {:mcp_notification, Protocol.notification("notifications/progress", params)})
+    end
+
+    :ok
+  end
+
+  defp maybe_put(map, _key, nil), do: map
+  defp maybe_put(map, key, value), do: Map.put(map, key, value)
+end
@@ -0,0 +1,130 @@
+defmodule EchoShared.MCP.Prompts do
+  @moduledoc """
+  Standard parameterised MCP prompts shared by all ECHO agents.
+
+  Prompts are rendered from the agent's role so the same template reads
+  differently for the CEO and the Test Lead.
+
+  ## Prompts
+
+  - `strategic_review` - Structured review of a topic from the agent's perspective
+  - `decision_review` - Review of an existing decision, embedding its resource
+  """
+
+  alias EchoShared.LLM.Config
+  alias EchoShared.MCP.Resources
+
+  @doc """
+  List prompt definitions for `prompts/list`.
+  """
+  @spec list() :: [map()]
+  def list do
+    [
+      %{
+        name: "strategic_review",
+        description: "Structured strategic review of a topic from this agent's perspective",
+        arguments: [
+          %{name: "topic", description: "What to review (initiative, proposal, problem)", required: true},
+          %{name: "horizon", description: "Planning horizon (e.g., 'Q1 2026', '12 months')", required: false},
+          %{name: "constraints", description: "Known constraints (budget, timeline, staffing)", required: false}
+        ]
+      },
+      %{
+        name: "decision_review",
+        description: "Review an existing organizational decision and recommend next steps",
+        arguments: [
+          %{name: "decision_id", description: "The decision ID to review", required: true}
+        ]
+      }
+    ]
+  end
+
+  @doc """
+  Render a prompt by name for an agent role.
+
+  ## Returns
+
+  - `{:ok, %{description: text, messages: [message]}}` on success
+  - `{:error, :prompt_not_found}` - Unknown prompt name
+  - `{:error, {:invalid_params, message}}` - Missing required argument
+  """
+  @spec get(atom(), String.t(), map()) :: {:ok, map()} | {:error, term()}
+  def get(role, "strategic_review", args) do
+    with {:ok, topic} <- fetch_argument(args, "topic") do
+      text = """
+      #{String.trim(Config.get_system_prompt(role))}
+
+      Conduct a strategic review of the following topic:
+
+      Topic: #{topic}
+      Horizon: #{args["horizon"] || "Not specified"}
+      Constraints: #{args["constraints"] || "None stated"}
+
+      Please cover:
+      1. Current situation and why it matters now
+      2. Options with trade-offs (cost, risk, time to value)
+      3. Impact on other roles in the organization
+      4. Your recommendation, confidence (0-1), and whether it needs escalation
+      """
+
+      {:ok,
+       %{
+         description: "Strategic review of #{topic}",
+         messages: [user_text(text)]
+       }}
+    end
+  end
+
+  def get(role, "decision_review", args) do
+    with {:ok, decision_id} <- fetch_argument(args, "decision_id") do
+      uri = "echo://decisions/#{decision_id}"
+
+      case Resources.read(role, uri) do
+        {:ok, [resource]} ->
+          {:ok,
+           %{
+             description: "Review of decision #{decision_id}",
+             messages: [
+               %{role: "user", content: %{type: "resource", resource: resource}},
+               user_text("""
+               As #{role}, review the decision above. Assess whether the outcome is sound,
+               identify risks or missing participants, and recommend whether to approve,
+               reject, or escalate it.
+               """)
+             ]
+           }}
+
+        {:error, :resource_not_found} ->
+          {:error, {:invalid_params, "Decision not found: #{decision_id}"}}
+      end
+    end
+  end
+
+  def get(_role, _name, _args), do: {:error, :prompt_not_found}
+
+  @doc """
+  Complete a prompt argument value.
+
+  Only `decision_review`'s `decision_id` has completions; other arguments
+  are free text.
+  """
+  @spec complete(String.t(), String.t(), String.t()) :: {:ok, [String.t()]}
+  def complete("decision_review", "decision_id", prefix) do
+    {:ok, Resources.complete_decision_ids(prefix)}
+  end
+
+  def complete(_name, _argument, _prefix), do: {:ok, []}
+
+  ## Private Functions
+
+  defp fetch_argument(args, key) do
+    case Map.get(args, key) do
+      value when is_binary(value) and value != "" -> {:ok, value}
+      _ -> {:error, {:invalid_params, "Missing required argument: #{key}"}}
+    end
+  end
+
+  defp user_text(text) do
+    %{role: "user", content: %{type: "text", text: text}}
+  end
+end
@@ -2,8 +2,17 @@ defmodule EchoShared.MCP.Protocol do
   @moduledoc """
   MCP (Model Context Protocol) JSON-RPC 2.0 implementation.
 
-  Implements the MCP protocol specification version 2024-11-05 for
-  communication between ECHO agents and MCP clients (like Claude Desktop).
+  Implements the MCP protocol specification (versions 2025-06-18,
+  2025-03-26 and 2024-11-05) for communication between ECHO agents and
+  MCP clients (like Claude Desktop).
+
+  ## Version Negotiation
+
+  The client's requested `protocolVersion` is echoed back when supported;
+  otherwise the server replies with its latest supported version and the
+  client decides whether to proceed. Capabilities that don't exist in the
+  negotiated version (e.g. `completions` before 2025-03-26) are not
+  advertised.
 
   ## Protocol Flow
 
@@ -14,15 +23,34 @@ defmodule EchoShared.MCP.Protocol do
 
   ## Supported Methods
 
-  - `initialize` - MCP handshake
+  - `initialize` - MCP handshake and version negotiation
+  - `ping` - Liveness check (answered immediately, even during tool calls)
   - `tools/list` - List available tools
   - `tools/call` - Execute a tool
   - `prompts/list` - List available prompts
   - `prompts/get` - Get a prompt
   - `resources/list` - List available resources
+  - `resources/templates/list` - List parameterised resource URI templates
   - `resources/read` - Read a resource
+  - `completion/complete` - Argument completion for prompts and resource templates
+  - `logging/setLevel` - Minimum level for forwarded log messages
+  - `notifications/cancelled` - Cancel an in-flight `tools/call`
   - `notifications/*` - Handle notifications (no response)
 
+  ## Server Notifications
+
+  - `notifications/progress` - Progress for `tools/call` requests that carry
+    `_meta.progressToken` (see `EchoShared.MCP.Progress`)
+  - `notifications/tools/list_changed` - The agent's available tools changed
+    (see `EchoShared.MCP.ToolRegistry`)
+  - `notifications/message` - Agent `Logger` output (see `EchoShared.MCP.Logging`)
+
+  ## Server Requests
+
+  - `sampling/createMessage` - Ask the client's model for a completion
+    (see `EchoShared.MCP.Sampling`); the client's response is parsed as
+    `{:response, ...}` by `parse_request/1`
+
   ## Message Format
 
   ### Request
@@ -67,7 +95,13 @@ defmodule EchoShared.MCP.Protocol do
   require Logger
 
   @json_rpc_version "2.0"
-  @mcp_version "2024-11-05"
+  # Newest first
+  @supported_versions ["2025-06-18", "2025-03-26", "2024-11-05"]
+
+  # First protocol version in which each optional feature exists
+  @feature_versions %{
+    completions: "2025-03-26"
+  }
 
   @type request :: %{
           jsonrpc: String.t(),
@@ -92,15 +126,29 @@ defmodule EchoShared.MCP.Protocol do
           }
         }
 
+  @type client_response :: %{
+          id: integer() | String.t(),
+          result: any(),
+          error: map() | nil
+        }
+
   @doc """
   Parse a JSON-RPC 2.0 request from stdin line.
+
+  Responses to server-initiated requests (e.g. `sampling/createMessage`)
+  are returned as `{:response, client_response}`. Batches (JSON arrays)
+  aren't supported and, like any other non-object, are invalid requests.
   """
-  @spec parse_request(String.t()) :: {:ok, request()} | {:error, term()}
+  @spec parse_request(String.t()) ::
+          {:ok, request()} | {:response, client_response()} | {:error, term()}
   def parse_request(line) do
     case Jason.decode(line) do
       {:ok, request} when is_map(request) ->
         validate_request(request)
 
+      {:ok, _other} ->
+        {:error, {:invalid_request, "Request must be a JSON object"}}
+
       {:error, reason} ->
         {:error, {:parse_error, reason}}
     end
@@ -143,6 +191,31 @@ defmodule EchoShared.MCP.Protocol do
     }
   end
 
+  @doc """
+  Create a server-initiated JSON-RPC 2.0 request.
+  """
+  @spec request(integer() | String.t(), String.t(), map()) :: map()
+  def request(id, method, params \\ %{}) do
+    %{
+      jsonrpc: @json_rpc_version,
+      id: id,
+      method: method,
+      params: params
+    }
+  end
+
+  @doc """
+  Create a JSON-RPC 2.0 notification (no `id`, no response expected).
+  """
+  @spec notification(String.t(), map()) :: map()
+  def notification(method, params \\ %{}) do
+    %{
+      jsonrpc: @json_rpc_version,
+      method: method,
+      params: params
+    }
+  end
+
   @doc """
   Encode a response to JSON string for stdout.
   """
@@ -151,18 +224,64 @@ defmodule EchoShared.MCP.Protocol do
     Jason.encode(response)
   end
 
+  @doc """
+  Protocol versions this server supports, newest first.
+  """
+  @spec supported_versions() :: [String.t()]
+  def supported_versions, do: @supported_versions
+
+  @doc """
+  The newest protocol version this server supports.
+  """
+  @spec latest_version() :: String.t()
+  def latest_version, do: hd(@supported_versions)
+
+  @doc """
+  Negotiate the protocol version for a client's requested version.
+
+  Returns the requested version if supported, otherwise the latest.
+  """
+  @spec negotiate_version(String.t() | nil) :: String.t()
+  def negotiate_version(requested) when requested in @supported_versions, do: requested
+  def negotiate_version(_requested), do: latest_version()
+
+  @doc """
+  Check whether an optional feature exists in a protocol version.
+  """
+  @spec supports?(String.t(), atom()) :: boolean()
+  def supports?(version, feature) do
+    case Map.fetch(@feature_versions, feature) do
+      {:ok, since} -> version >= since
+      :error -> true
+    end
+  end
+
   @doc """
   Create MCP initialize response with server capabilities.
+
+  ## Options
+
+  - `:protocol_version` - Negotiated version (default: latest)
+  - `:tools`, `:prompts`, `:resources`, `:logging`, `:completions` -
+    Capability maps; a capability is only advertised when its option is
+    given and non-nil, and exists in the negotiated version
+  - `:name`, `:version` - Server info
   """
   @spec initialize_response(keyword()) :: map()
   def initialize_response(opts \\ []) do
+    protocol_version = Keyword.get(opts, :protocol_version, latest_version())
+
+    capabilities =
+      [:tools, :prompts, :resources, :logging, :completions]
+      |> Enum.map(&{&1, Keyword.get(opts, &1)})
+      |> Enum.filter(fn {key, capability} ->
+        capability != nil and supports?(protocol_version, key)
+      end)
+      |> Map.new()
+
     %{
-      protocolVersion: @mcp_version,
-      capabilities: %{
-        tools: Keyword.get(opts, :tools, %{}),
-        prompts: Keyword.get(opts, :prompts, %{}),
-        resources: Keyword.get(opts, :resources, %{})
-      },
+      protocolVersion: protocol_version,
+      capabilities: capabilities,
       serverInfo: %{
         name: Keyword.get(opts, :name, "echo-agent"),
         version: Keyword.get(opts, :version, "0.1.0")
@@ -170,6 +289,22 @@ defmodule EchoShared.MCP.Protocol do
     }
   end
 
+  @doc """
+  Create completion/complete response.
+
+  At most 100 values are returned, per the MCP spec.
+  """
+  @spec completion_response([String.t()]) :: map()
+  def completion_response(values) when is_list(values) do
+    %{
+      completion: %{
+        values: Enum.take(values, 100),
+        total: length(values),
+        hasMore: length(values) > 100
+      }
+    }
+  end
+
   @doc """
   Create tools/list response.
   """
@@ -198,8 +333,69 @@ defmodule EchoShared.MCP.Protocol do
     }
   end
 
+  @doc """
+  Create tools/call success response with structured content.
+
+  `text` is the human-readable rendering; `structured` is the typed JSON
+  result described by the tool's `outputSchema`.
+  """
+  @spec tools_call_response(String.t(), map()) :: map()
+  def tools_call_response(text, structured) when is_binary(text) and is_map(structured) do
+    %{
+      content: [
+        %{type: "text", text: text}
+      ],
+      structuredContent: structured
+    }
+  end
+
+  @doc """
+  Create prompts/get response.
+  """
+  @spec prompts_get_response(String.t() | nil, [map()]) :: map()
+  def prompts_get_response(description, messages) when is_list(messages) do
+    result = %{messages: messages}
+
+    if description do
+      Map.put(result, :description, description)
+    else
+      result
+    end
+  end
+
+  @doc """
+  Create resources/templates/list response.
+  """
+  @spec resource_templates_list_response([map()]) :: map()
+  def resource_templates_list_response(templates) do
+    %{
+      resourceTemplates: templates
+    }
+  end
+
+  @doc """
+  Create resources/read response.
+
+  Accepts a list of content maps (`%{uri:, mimeType:, text:}`) as returned
+  by an agent's `read_resource/1` callback.
+  """
+  @spec resources_read_response([map()]) :: map()
+  def resources_read_response(contents) when is_list(contents) do
+    %{
+      contents: contents
+    }
+  end
+
   ## Private Functions
 
+  defp validate_request(%{"id" => id} = response)
+       when not is_map_key(response, "method") and
+              (is_map_key(response, "result") or is_map_key(response, "error")) do
+    with :ok <- validate_jsonrpc(response) do
+      {:response, %{id: id, result: response["result"], error: response["error"]}}
+    end
+  end
+
   defp validate_request(request) do
     with :ok <- validate_jsonrpc(request),
          :ok <- validate_method(request) do
@@ -234,7 +430,9 @@ defmodule EchoShared.MCP.Protocol do
       invalid_request: -32600,
       method_not_found: -32601,
       invalid_params: -32602,
-      internal_error: -32603
+      internal_error: -32603,
+      # MCP-specific
+      resource_not_found: -32002
     }
   end
 end
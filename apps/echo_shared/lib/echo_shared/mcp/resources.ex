@@ -0,0 +1,243 @@
+defmodule EchoShared.MCP.Resources do
+  @moduledoc """
+  Standard `echo://` resources exposed by every ECHO agent.
+
+  Lets MCP clients (like Claude Desktop) browse organizational state
+  directly instead of calling tools.
+
+  ## Resources
+
+  - `echo://decisions/recent` - Last 20 decisions across the organization
+  - `echo://messages/inbox` - Unread messages for the agent's role
+  - `echo://memories` - Index of organizational memory keys
+
+  ## Resource Templates
+
+  - `echo://decisions/{id}` - A single decision by UUID
+  - `echo://memories/{key}` - A single memory by key
+
+  All resources are returned as `application/json`.
+  """
+
+  import Ecto.Query
+
+  alias EchoShared.MessageBus
+  alias EchoShared.Repo
+  alias EchoShared.Schemas.{Decision, Memory}
+
+  @scheme "echo://"
+  @mime_type "application/json"
+  @recent_limit 20
+
+  @doc """
+  List the static resources available to an agent role.
+  """
+  @spec list(atom()) :: [map()]
+  def list(role) do
+    [
+      %{
+        uri: "echo://decisions/recent",
+        name: "Recent decisions",
+        description: "The #{@recent_limit} most recent organizational decisions",
+        mimeType: @mime_type
+      },
+      %{
+        uri: "echo://messages/inbox",
+        name: "Inbox",
+        description: "Unread messages addressed to #{role}",
+        mimeType: @mime_type
+      },
+      %{
+        uri: "echo://memories",
+        name: "Organizational memory index",
+        description: "Keys and tags of all shared organizational memories",
+        mimeType: @mime_type
+      }
+    ]
+  end
+
+  @doc """
+  List the parameterised resource URI templates (RFC 6570).
+  """
+  @spec templates() :: [map()]
+  def templates do
+    [
+      %{
+        uriTemplate: "echo://decisions/{id}",
+        name: "Decision",
+        description: "A single organizational decision by ID",
+        mimeType: @mime_type
+      },
+      %{
+        uriTemplate: "echo://memories/{key}",
+        name: "Memory",
+        description: "A single organizational memory by key",
+        mimeType: @mime_type
+      }
+    ]
+  end
+
+  @doc """
+  Read a resource by URI on behalf of an agent role.
+
+  ## Returns
+
+  - `{:ok, [content]}` - List of MCP resource contents
+  - `{:error, :resource_not_found}` - Unknown URI or missing record
+  """
+  @spec read(atom(), String.t()) :: {:ok, [map()]} | {:error, :resource_not_found}
+  def read(role, uri) when is_binary(uri) do
+    with {:ok, data} <- fetch(role, parse_uri(uri)) do
+      {:ok, [%{uri: uri, mimeType: @mime_type, text: Jason.encode!(data)}]}
+    end
+  end
+
+  def read(_role, _uri), do: {:error, :resource_not_found}
+
+  @doc """
+  Complete an argument of a resource template from existing records.
+
+  ## Example
+
+      Resources.complete("echo://memories/{key}", "key", "proj")
+      # => {:ok, ["project/goals", "project/roadmap"]}
+  """
+  @spec complete(String.t(), String.t(), String.t()) :: {:ok, [String.t()]}
+  def complete("echo://decisions/{id}", "id", prefix), do: {:ok, complete_decision_ids(prefix)}
+
+  def complete("echo://memories/{key}", "key", prefix) do
+    keys =
+      Repo.all(
+        from m in Memory,
+          where: like(m.key, ^"#{escape_like(prefix)}%"),
+          order_by: [asc: m.key],
+          limit: 100,
+          select: m.key
+      )
+
+    {:ok, keys}
+  end
+
+  def complete(_template, _argument, _prefix), do: {:ok, []}
+
+  @doc """
+  Complete a decision ID prefix, most recent decisions first.
+  """
+  @spec complete_decision_ids(String.t()) :: [String.t()]
+  def complete_decision_ids(prefix) do
+    Repo.all(
+      from d in Decision,
+        where: like(fragment("?::text", d.id), ^"#{escape_like(prefix)}%"),
+        order_by: [desc: d.inserted_at],
+        limit: 100,
+        select: d.id
+    )
+  end
+
+  @doc """
+  Parse an `echo://` URI into a `{collection, key}` tuple.
+
+  ## Example
+
+      Resources.parse_uri("echo://memories/project%2Fgoals")
+      # => {"memories", "project/goals"}
+  """
+  @spec parse_uri(String.t()) :: {String.t(), String.t() | nil} | :error
+  def parse_uri(@scheme <> rest) do
+    case String.split(rest, "/", parts: 2) do
+      [collection] -> {collection, nil}
+      [collection, ""] -> {collection, nil}
+      [collection, key] -> {collection, URI.decode(key)}
+    end
+  end
+
+  def parse_uri(_uri), do: :error
+
+  ## Private Functions
+
+  defp fetch(_role, {"decisions", "recent"}) do
+    decisions =
+      Repo.all(
+        from d in Decision,
+          order_by: [desc: d.inserted_at],
+          limit: @recent_limit
+      )
+
+    {:ok, Enum.map(decisions, &decision_to_map/1)}
+  end
+
+  defp fetch(_role, {"decisions", id}) when is_binary(id) do
+    with {:ok, uuid} <- Ecto.UUID.cast(id),
+         %Decision{} = decision <- Repo.get(Decision, uuid) do
+      {:ok, decision_to_map(decision)}
+    else
+      _ -> {:error, :resource_not_found}
+    end
+  end
+
+  defp fetch(role, {"messages", "inbox"}) do
+    messages = MessageBus.fetch_unread_messages(role)
+    {:ok, Enum.map(messages, &message_to_map/1)}
+  end
+
+  defp fetch(_role, {"memories", nil}) do
+    index =
+      Repo.all(
+        from m in Memory,
+          order_by: [asc: m.key],
+          select: %{key: m.key, tags: m.tags, created_by_role: m.created_by_role}
+      )
+
+    {:ok, index}
+  end
+
+  defp fetch(_role, {"memories", key}) do
+    case Repo.get_by(Memory, key: key) do
+      nil -> {:error, :resource_not_found}
+      memory -> {:ok, memory_to_map(memory)}
+    end
+  end
+
+  defp fetch(_role, _parsed), do: {:error, :resource_not_found}
+
+  defp escape_like(value), do: String.replace(value, ~r/[\\%_]/, &("\\" <> &1))
+
+  defp decision_to_map(decision) do
+    %{
+      id: decision.id,
+      decision_type: decision.decision_type,
+      initiator_role: decision.initiator_role,
+      participants: decision.participants || [],
+      mode: decision.mode,
+      status: decision.status,
+      context: decision.context,
+      consensus_score: decision.consensus_score,
+      outcome: decision.outcome,
+      inserted_at: decision.inserted_at,
+      completed_at: decision.completed_at
+    }
+  end
+
+  defp message_to_map(message) do
+    %{
+      id: message.id,
+      from_role: message.from_role,
+      to_role: message.to_role,
+      type: message.type,
+      subject: message.subject,
+      content: message.content,
+      inserted_at: message.inserted_at
+    }
+  end
+
+  defp memory_to_map(memory) do
+    %{
+      key: memory.key,
+      content: memory.content,
+      tags: memory.tags || [],
+      metadata: memory.metadata,
+      created_by_role: memory.created_by_role,
+      updated_at: memory.updated_at
+    }
+  end
+end
@@ -0,0 +1,133 @@
+defmodule EchoShared.MCP.Sampling do
+  @moduledoc """
+  MCP sampling (`sampling/createMessage`): borrow the connected client's model.
+
+  When a client declares the `sampling` capability, an agent can send the
+  client a completion request instead of calling local Ollama. The client
+  (e.g. Claude Desktop) runs the prompt on its own model, usually after
+  user approval, and returns the result.
+
+  Like `EchoShared.MCP.Progress`, the transport registers a channel in the
+  process running a `tools/call`. Code executing in that process can then
+  sample without knowing which transport it is on:
+
+      if Sampling.available?() do
+        {:ok, text} = Sampling.create_message([%{role: "user", content: "Summarize..."}])
+      end
+
+  Outside a tool call, or when the client doesn't support sampling,
+  `available?/0` is false and `create_message/2` returns
+  `{:error, :sampling_unavailable}`.
+
+  ## Transport contract
+
+  The channel process receives `{:mcp_client_request, from, ref, method, params}`,
+  sends the request to the client, and replies to `from` with
+  `{:mcp_client_response, ref, {:ok, result} | {:error, error}}` once the
+  client responds.
+  """
+
+  @key :echo_mcp_sampling
+  @default_timeout 180_000
+  @default_max_tokens 2000
+
+  @doc """
+  Register the client channel for the current process.
+
+  `supported?` is whether the client declared the `sampling` capability.
+  """
+  @spec register(pid(), boolean()) :: :ok
+  def register(channel, supported?) do
+    if supported?, do: Process.put(@key, channel), else: Process.delete(@key)
+    :ok
+  end
+
+  @doc """
+  Check whether the current process can sample from the client's model.
+  """
+  @spec available?() :: boolean()
+  def available?, do: Process.get(@key) != nil
+
+  @doc """
+  Request a completion from the client's model.
+
+  ## Parameters
+
+  - `messages` - List of `%{role: "system" | "user" | "assistant", content: text}`
+    maps (the same shape `EchoShared.LLM.Client.chat/3` takes). System
+    messages are sent as `systemPrompt`.
+  - `opts` - Optional map with:
+    - `:max_tokens` - Maximum tokens to sample (default: 2000)
+    - `:temperature` - Sampling temperature
+    - `:model` - Model name, sent as a hint (clients may ignore it)
+    - `:intelligence_priority`, `:speed_priority`, `:cost_priority` - 0..1
+    - `:timeout` - How long to wait for the client (default: 180s)
+
+  ## Returns
+
+  - `{:ok, response_text}` on success
+  - `{:error, :sampling_unavailable}` - No client channel or no sampling capability
+  - `{:error, :timeout}` - Client didn't respond in time
+  - `{:error, {:client_error, error}}` - Client rejected the request
+  """
+  @spec create_message([map()], map()) :: {:ok, String.t()} | {:error, term()}
+  def create_message(messages, opts \\ %{}) do
+    case Process.get(@key) do
+      nil ->
+        {:error, :sampling_unavailable}
+
+      channel ->
+        ref = make_ref()
+        send(channel, {:mcp_client_request, self(), ref, "sampling/createMessage", build_params(messages, opts)})
+
+        receive do
+          {:mcp_client_response, ^ref, {:ok, result}} -> extract_text(result)
+          {:mcp_client_response, ^ref, {:error, error}} -> {:error, {:client_error, error}}
+        after
+          opts[:timeout] || @default_timeout -> {:error, :timeout}
+        end
+    end
+  end
+
+  ## Private Functions
+
+  defp build_params(messages, opts) do
+    {system, conversation} = Enum.split_with(messages, &(to_string(&1[:role] || &1["role"]) == "system"))
+
+    %{
+      messages: Enum.map(conversation, &to_sampling_message/1),
+      maxTokens: opts[:max_tokens] || @default_max_tokens
+    }
+    |> maybe_put(:systemPrompt, join_system(system))
+    |> maybe_put(:temperature, opts[:temperature])
+    |> maybe_put(:modelPreferences, model_preferences(opts))
+  end
+
+  defp to_sampling_message(message) do
+    %{
+      role: to_string(message[:role] || message["role"]),
+      content: %{type: "text", text: message[:content] || message["content"] || ""}
+    }
+  end
+
+  defp join_system([]), do: nil
+  defp join_system(system), do: Enum.map_join(system, "\n\n", &(&1[:content] || &1["content"]))
+
+  defp model_preferences(opts) do
+    %{}
+    |> maybe_put(:hints, if(opts[:model], do: [%{name: opts[:model]}]))
+    |> maybe_put(:intelligencePriority, opts[:intelligence_priority])
+    |> maybe_put(:speedPriority, opts[:speed_priority])
+    |> maybe_put(:costPriority, opts[:cost_priority])
+    |> case do
+      preferences when map_size(preferences) == 0 -> nil
+      preferences -> preferences
+    end
+  end
+
+  defp extract_text(%{"content" => %{"type" => "text", "text" => text}}), do: {:ok, text}
+  defp extract_text(result), do: {:error, {:unsupported_content, result}}
+
+  defp maybe_put(map, _key, nil), do: map
+  defp maybe_put(map, key, value), do: Map.put(map, key, value)
+end
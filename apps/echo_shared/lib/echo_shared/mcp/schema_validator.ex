@@ -0,0 +1,178 @@
+defmodule EchoShared.MCP.SchemaValidator do
+  @moduledoc """
+  JSON Schema validation for MCP tool arguments.
+
+  Validates `tools/call` arguments against the tool's declared
+  `inputSchema` before `execute_tool/2` runs, so agents can pattern-match
+  required arguments directly instead of hand-rolling checks.
+
+  Supports the subset of JSON Schema used by ECHO tools:
+
+  - `type` (`object`, `string`, `number`, `integer`, `boolean`, `array`, `null`,
+    or a list of types)
+  - `properties`, `required`, `additionalProperties: false`
+  - `enum`
+  - `items`
+  - `minLength`, `maxLength`, `minimum`, `maximum`
+
+  Schemas may use atom or string keys. Optional properties set to `null`
+  are treated as absent.
+
+  ## Example
+
+      SchemaValidator.validate(%{"amount" => "lots"}, %{
+        type: "object",
+        properties: %{amount: %{type: "number"}, purpose: %{type: "string"}},
+        required: ["amount", "purpose"]
+      })
+      # => {:error, [
+      #      %{field: "amount", message: "must be a number"},
+      #      %{field: "purpose", message: "is required"}
+      #    ]}
+  """
+
+  @type error :: %{field: String.t(), message: String.t()}
+
+  @doc """
+  Validate a value against a JSON Schema.
+  """
+  @spec validate(term(), map()) :: :ok | {:error, [error()]}
+  def validate(value, schema) do
+    case validate_value(value, schema, "") do
+      [] -> :ok
+      errors -> {:error, errors}
+    end
+  end
+
+  ## Private Functions
+
+  defp validate_value(value, schema, path) do
+    case check_type(value, get(schema, :type), path) do
+      [] ->
+        check_enum(value, get(schema, :enum), path) ++ check_constraints(value, schema, path)
+
+      type_errors ->
+        type_errors
+    end
+  end
+
+  defp check_type(_value, nil, _path), do: []
+
+  defp check_type(value, types, path) when is_list(types) do
+    if Enum.any?(types, &type_matches?(value, &1)) do
+      []
+    else
+      [error(path, "must be one of types: #{Enum.join(types, ", ")}")]
+    end
+  end
+
+  defp check_type(value, type, path) do
+    if type_matches?(value, type), do: [], else: [error(path, "must be #{article(type)} #{type}")]
+  end
+
+  defp type_matches?(value, "string"), do: is_binary(value)
+  defp type_matches?(value, "number"), do: is_number(value)
+  defp type_matches?(value, "integer"), do: is_integer(value)
+  defp type_matches?(value, "boolean"), do: is_boolean(value)
+  defp type_matches?(value, "object"), do: is_map(value)
+  defp type_matches?(value, "array"), do: is_list(value)
+  defp type_matches?(value, "null"), do: is_nil(value)
+  defp type_matches?(_value, _type), do: true
+
+  defp check_enum(_value, nil, _path), do: []
+
+  defp check_enum(value, allowed, path) do
+    if value in allowed do
+      []
+    else
+      [error(path, "must be one of: #{Enum.map_join(allowed, ", ", &to_string/1)}")]
+    end
+  end
+
+  defp check_constraints(value, schema, path) when is_map(value) do
+    properties = get(schema, :properties) || %{}
+    required = get(schema, :required) || []
+
+    missing =
+      for key <- required, is_nil(Map.get(value, to_string(key))) do
+        error(join(path, key), "is required")
+      end
+
+    property_errors =
+      Enum.flat_map(properties, fn {key, property_schema} ->
+        case Map.get(value, to_string(key)) do
+          nil -> []
+          property_value -> validate_value(property_value, property_schema, join(path, key))
+        end
+      end)
+
+    unknown =
+      if get(schema, :additionalProperties) == false do
+        known = MapSet.new(Map.keys(properties), &to_string/1)
+
+        for key <- Map.keys(value), not MapSet.member?(known, to_string(key)) do
+          error(join(path, key), "is not allowed")
+        end
+      else
+        []
+      end
+
+    missing ++ property_errors ++ unknown
+  end
+
+  defp check_constraints(value, schema, path) when is_list(value) do
+    case get(schema, :items) do
+      nil ->
+        []
+
+      item_schema ->
+        value
+        |> Enum.with_index()
+        |> Enum.flat_map(fn {item, index} ->
+          validate_value(item, item_schema, "#{path}[#{index}]")
+        end)
+    end
+  end
+
+  defp check_constraints(value, schema, path) when is_binary(value) do
+    length = String.length(value)
+
+    check_bound(length, get(schema, :minLength), &>=/2, path, "must be at least %s characters") ++
+      check_bound(length, get(schema, :maxLength), &<=/2, path, "must be at most %s characters")
+  end
+
+  defp check_constraints(value, schema, path) when is_number(value) do
+    check_bound(value, get(schema, :minimum), &>=/2, path, "must be >= %s") ++
+      check_bound(value, get(schema, :maximum), &<=/2, path, "must be <= %s")
+  end
+
+  defp check_constraints(_value, _schema, _path), do: []
+
+  defp check_bound(_value, nil, _compare, _path, _message), do: []
+
+  defp check_bound(value, bound, compare, path, message) do
+    if compare.(value, bound) do
+      []
+    else
+      [error(path, String.replace(message, "%s", to_string(bound)))]
+    end
+  end
+
+  defp get(schema, key) when is_map(schema) do
+    case Map.fetch(schema, key) do
+      {:ok, value} -> value
+      :error -> Map.get(schema, Atom.to_string(key))
+    end
+  end
+
+  defp get(_schema, _key), do: nil
+
+  defp join("", key), do: to_string(key)
+  defp join(path, key), do: "#{path}.#{key}"
+
+  defp error("", message), do: %{field: "arguments", message: message}
+  defp error(path, message), do: %{field: path, message: message}
+
+  defp article(type) when type in ["array", "object", "integer"], do: "an"
+  defp article(_type), do: "a"
+end
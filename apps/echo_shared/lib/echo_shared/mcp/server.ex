@@ -47,23 +47,87 @@ defmodule EchoShared.MCP.Server do
   end
   ```
 
+  ## Transports
+
+  - `start/0` - JSON-RPC over stdio (one client per process)
+  - `start_http/1` - MCP streamable HTTP with SSE (many clients per process)
+
+  On both transports, `Logger` output from the agent is forwarded to clients
+  as `notifications/message` at the level chosen with `logging/setLevel`
+  (see `EchoShared.MCP.Logging`).
+
   ## Callbacks
 
   - `agent_info/0` - Returns agent metadata (name, version, role)
   - `tools/0` - Returns list of MCP tools
-  - `execute_tool/2` - Executes a tool by name
+  - `tool_enabled?/1` (optional) - Whether a declared tool is currently
+    available (default: hides `ai_consult` when LLM is disabled for the role)
+  - `execute_tool/2` - Executes a tool by name (arguments are already
+    validated against the tool's `inputSchema`, see `EchoShared.MCP.SchemaValidator`)
   - `prompts/0` (optional) - Returns list of MCP prompts
+  - `get_prompt/2` (optional) - Renders a prompt with arguments
   - `resources/0` (optional) - Returns list of MCP resources
+  - `resource_templates/0` (optional) - Returns list of resource URI templates
+  - `read_resource/1` (optional) - Reads a resource by URI
+  - `complete/2` (optional) - Completes a prompt or resource template argument
+
+  Prompts and resources default to the shared `EchoShared.MCP.Prompts` and
+  `EchoShared.MCP.Resources` definitions (`echo://decisions/{id}`,
+  `echo://messages/inbox`, `echo://memories/{key}`, ...), scoped to the
+  agent's role.
+
+  ## Tool Results
+
+  `execute_tool/2` may return:
+
+  - `{:ok, text}` - Plain text content
+  - `{:ok, [content]}` - MCP content blocks
+  - `{:ok, text, data}` - A text rendering plus typed `structuredContent`
+  - `{:ok, data}` - A map, returned as `structuredContent` with a JSON rendering
+
+  When a tool declares an `outputSchema`, structured results are validated
+  against it before they are sent.
+
+  ## Shared Tools
+
+  Every agent also offers the tools in `EchoShared.MCP.SharedTools` (e.g.
+  `message_thread`), executed on the agent's role without reaching
+  `execute_tool/2`.
+
+  ## Dynamic Tools
+
+  `tools/list` and `tools/call` only see the tools returned by
+  `available_tools/0`. Agents can toggle tools at runtime with
+  `enable_tool/1`, `disable_tool/1` and `reset_tool/1` (backed by
+  `EchoShared.MCP.ToolRegistry`); connected clients receive
+  `notifications/tools/list_changed` and re-fetch the list.
   """
 
-  alias EchoShared.MCP.Protocol
+  alias EchoShared.MCP.{
+    HTTPTransport,
+    Logging,
+    Progress,
+    Prompts,
+    Protocol,
+    Resources,
+    Sampling,
+    SchemaValidator,
+    SharedTools,
+    ToolRegistry
+  }
 
   @callback agent_info() :: %{name: String.t(), version: String.t(), role: atom()}
   @callback tools() :: [map()]
+  @callback tool_enabled?(name :: String.t()) :: boolean()
   @callback execute_tool(name :: String.t(), args :: map()) ::
-              {:ok, String.t() | [map()]} | {:error, term()}
+              {:ok, String.t() | [map()] | map()} | {:ok, String.t(), map()} | {:error, term()}
   @callback prompts() :: [map()]
+  @callback get_prompt(name :: String.t(), args :: map()) ::
+              {:ok, %{optional(:description) => String.t(), messages: [map()]}} | {:error, term()}
   @callback resources() :: [map()]
+  @callback resource_templates() :: [map()]
+  @callback read_resource(uri :: String.t()) :: {:ok, [map()]} | {:error, term()}
+  @callback complete(ref :: map(), argument :: map()) :: {:ok, [String.t()]} | {:error, term()}
 
   defmacro __using__(_opts) do
     quote do
@@ -72,31 +136,242 @@ defmodule EchoShared.MCP.Server do
 
       # Default implementations
       @impl true
-      def prompts, do: []
+      def tool_enabled?("ai_consult"), do: EchoShared.LLM.Config.llm_enabled?(agent_info().role)
+      def tool_enabled?(_name), do: true
 
       @impl true
-      def resources, do: []
+      def prompts, do: Prompts.list()
+
+      @impl true
+      def get_prompt(name, args), do: Prompts.get(agent_info().role, name, args)
+
+      @impl true
+      def resources, do: Resources.list(agent_info().role)
+
+      @impl true
+      def resource_templates, do: Resources.templates()
+
+      @impl true
+      def read_resource(uri), do: Resources.read(agent_info().role, uri)
+
+      @impl true
+      def complete(%{"type" => "ref/prompt", "name" => name}, %{"name" => arg} = argument),
+        do: Prompts.complete(name, arg, argument["value"] || "")
+
+      def complete(%{"type" => "ref/resource", "uri" => template}, %{"name" => arg} = argument),
+        do: Resources.complete(template, arg, argument["value"] || "")
+
+      def complete(ref, _argument), do: {:error, {:unknown_ref, ref}}
+
+      @doc """
+      Validate tool arguments against the tool's declared `inputSchema`.
+
+      Called by the server before every `execute_tool/2`. Agents invoking
+      their own tools from other paths (e.g. message handlers) should call it too.
+      Unknown tools pass through so `execute_tool/2` can report them.
+      """
+      def validate_tool_arguments(tool_name, arguments) do
+        case Enum.find(all_tools(), &((&1[:name] || &1["name"]) == tool_name)) do
+          nil -> :ok
+          tool -> SchemaValidator.validate(arguments, tool[:inputSchema] || tool["inputSchema"] || %{})
+        end
+      end
+
+      @doc """
+      List the tools currently available to clients.
+
+      Runtime overrides from `enable_tool/1` / `disable_tool/1` win over
+      `tool_enabled?/1`.
+      """
+      def available_tools do
+        Enum.filter(all_tools(), fn tool ->
+          name = tool[:name] || tool["name"]
+
+          case ToolRegistry.override(__MODULE__, name) do
+            nil -> tool_enabled?(name)
+            enabled -> enabled
+          end
+        end)
+      end
+
+      @doc """
+      Make a tool available and notify connected clients.
+      """
+      def enable_tool(name), do: ToolRegistry.enable(__MODULE__, name)
+
+      @doc """
+      Hide a tool and notify connected clients.
+      """
+      def disable_tool(name), do: ToolRegistry.disable(__MODULE__, name)
+
+      @doc """
+      Drop a runtime override so `tool_enabled?/1` decides again.
+      """
+      def reset_tool(name), do: ToolRegistry.reset(__MODULE__, name)
+
+      @doc """
+      Tell connected clients to re-fetch `tools/list`.
 
-      defoverridable prompts: 0, resources: 0
+      Use when a condition checked by `tool_enabled?/1` changes.
+      """
+      def notify_tools_changed, do: ToolRegistry.notify_changed(__MODULE__)
+
+      defoverridable tool_enabled?: 1,
+                     prompts: 0,
+                     get_prompt: 2,
+                     resources: 0,
+                     resource_templates: 0,
+                     read_resource: 1,
+                     complete: 2
 
       @doc """
       Start the MCP server loop.
 
       Reads JSON-RPC requests from stdin and writes responses to stdout.
+
+      Each `tools/call` runs in its own supervised task so a slow tool
+      (e.g. a 3-minute `ai_consult`) never blocks `tools/list`, `ping` or
+      other calls. Responses are written as tasks complete, and
+      `notifications/cancelled` kills the matching in-flight task.
+      Progress reported by a task (see `EchoShared.MCP.Progress`) is
+      written as `notifications/progress` while it runs, and sampling
+      requests from a task (see `EchoShared.MCP.Sampling`) are sent to the
+      client with the response routed back to the task.
       """
       def start do
         Logger.info("Starting #{agent_info().name} MCP server...")
 
-        # Read stdin line by line
-        IO.stream(:stdio, :line)
-        |> Stream.filter(&(&1 != "\n"))
-        |> Enum.each(&handle_line/1)
+        {:ok, task_sup} = Task.Supervisor.start_link()
+
+        # Read stdin line by line in a separate process so the loop stays responsive
+        parent = self()
+        spawn_link(fn -> read_stdin(parent) end)
+
+        :ok = ToolRegistry.subscribe(__MODULE__)
+        :ok = Logging.attach(self())
+
+        loop(%{
+          task_sup: task_sup,
+          in_flight: %{},
+          stdin_open: true,
+          client_capabilities: %{},
+          client_requests: %{},
+          next_request_id: 1
+        })
+      end
+
+      @doc """
+      Start the MCP server over streamable HTTP instead of stdio.
+
+      A single agent process then serves every connected client.
+      See `EchoShared.MCP.HTTPTransport` for endpoint and port configuration.
+
+      ## Options
+
+      - `:port` - Override the configured port for this agent
+      """
+      def start_http(opts \\ []) do
+        HTTPTransport.start_link(Keyword.put(opts, :server, __MODULE__))
+      end
+
+      @doc """
+      Handle a single parsed JSON-RPC message and return the response map,
+      or `:noreply` for notifications.
+
+      Transport-agnostic entry point used by `EchoShared.MCP.HTTPTransport`.
+      Unlike the stdio loop, tool calls run in the calling process.
+      `session` identifies the client for `logging/setLevel`
+      (see `EchoShared.MCP.Logging.attach/2`).
+      """
+      def handle_rpc(request, session \\ nil)
+      def handle_rpc(%{method: "tools/call", id: id, params: params}, _session), do: call_tool(id, params)
+
+      def handle_rpc(%{method: "logging/setLevel", id: id, params: params}, session),
+        do: set_log_level(id, params, session)
+
+      def handle_rpc(request, _session), do: dispatch(request)
+
+      defp read_stdin(parent) do
+        case IO.gets(:stdio, "") do
+          line when is_binary(line) ->
+            if line != "\n", do: send(parent, {:stdin_line, line})
+            read_stdin(parent)
+
+          _eof_or_error ->
+            send(parent, :stdin_closed)
+        end
+      end
+
+      # Finish once stdin is closed and every in-flight tool call has responded
+      defp loop(%{stdin_open: false, in_flight: in_flight}) when map_size(in_flight) == 0,
+        do: :ok
+
+      defp loop(state) do
+        receive do
+          {:stdin_line, line} ->
+            line
+            |> handle_line(state)
+            |> loop()
+
+          {ref, response} when is_reference(ref) ->
+            Process.demonitor(ref, [:flush])
+
+            case Map.pop(state.in_flight, ref) do
+              {nil, _in_flight} ->
+                loop(state)
+
+              {_call, in_flight} ->
+                send_response(response)
+                loop(%{state | in_flight: in_flight})
+            end
+
+          {:DOWN, ref, :process, _pid, reason} ->
+            case Map.pop(state.in_flight, ref) do
+              {nil, _in_flight} ->
+                loop(state)
+
+              {%{id: id, tool: tool}, in_flight} ->
+                Logger.error("Tool #{tool} crashed: #{inspect(reason)}")
+                error_codes = Protocol.error_codes()
+
+                response =
+                  Protocol.error_response(
+                    id,
+                    error_codes.internal_error,
+                    "Tool execution failed",
+                    inspect(reason)
+                  )
+
+                send_response(response)
+                loop(%{state | in_flight: in_flight})
+            end
+
+          {:mcp_notification, notification} ->
+            send_response(notification)
+            loop(state)
+
+          {:mcp_client_request, from, ref, method, params} ->
+            id = "echo-#{state.next_request_id}"
+            send_response(Protocol.request(id, method, params))
+
+            loop(%{
+              state
+              | client_requests: Map.put(state.client_requests, id, {from, ref}),
+                next_request_id: state.next_request_id + 1
+            })
+
+          :stdin_closed ->
+            loop(%{state | stdin_open: false})
+        end
       end
 
-      defp handle_line(line) do
+      defp handle_line(line, state) do
         case Protocol.parse_request(line) do
           {:ok, request} ->
-            handle_request(request)
+            handle_request(request, state)
+
+          {:response, response} ->
+            handle_client_response(response, state)
 
           {:error, {:parse_error, reason}} ->
             error_codes = Protocol.error_codes()
@@ -110,97 +385,333 @@ defmodule EchoShared.MCP.Server do
               )
 
             send_response(response)
+            state
+
+          {:error, {:invalid_request, message}} ->
+            error_codes = Protocol.error_codes()
+            send_response(Protocol.error_response(nil, error_codes.invalid_request, message))
+            state
+        end
+      end
+
+      defp handle_client_response(%{id: id} = response, state) do
+        case Map.pop(state.client_requests, id) do
+          {nil, _client_requests} ->
+            Logger.warning("Ignoring response to unknown request #{inspect(id)}")
+            state
+
+          {{from, ref}, client_requests} ->
+            reply = if response.error, do: {:error, response.error}, else: {:ok, response.result}
+            send(from, {:mcp_client_response, ref, reply})
+            %{state | client_requests: client_requests}
+        end
+      end
+
+      defp handle_request(%{method: "initialize", params: params} = request, state) do
+        send_response(dispatch(request))
+        %{state | client_capabilities: params["capabilities"] || %{}}
+      end
+
+      defp handle_request(%{method: "tools/call", id: id, params: params}, state) do
+        server = self()
+        sampling? = Map.has_key?(state.client_capabilities, "sampling")
+
+        task =
+          Task.Supervisor.async_nolink(state.task_sup, fn ->
+            case get_in(params, ["_meta", "progressToken"]) do
+              nil -> :ok
+              token -> Progress.register(token, server)
+            end
+
+            Sampling.register(server, sampling?)
+
+            call_tool(id, params)
+          end)
+
+        call = %{id: id, pid: task.pid, tool: params["name"]}
+
+        %{state | in_flight: Map.put(state.in_flight, task.ref, call)}
+      end
+
+      defp handle_request(%{method: "notifications/cancelled", params: params}, state) do
+        request_id = params["requestId"]
+
+        case Enum.find(state.in_flight, fn {_ref, call} -> call.id == request_id end) do
+          {ref, call} ->
+            Logger.info("Cancelling #{call.tool} (request #{inspect(request_id)}): #{params["reason"]}")
+            Task.Supervisor.terminate_child(state.task_sup, call.pid)
+            Process.demonitor(ref, [:flush])
+
+            # Cancelled requests receive no response
+            %{state | in_flight: Map.delete(state.in_flight, ref)}
+
+          nil ->
+            state
+        end
+      end
+
+      defp handle_request(%{method: "logging/setLevel", id: id, params: params}, state) do
+        send_response(set_log_level(id, params, self()))
+        state
+      end
+
+      defp handle_request(request, state) do
+        case dispatch(request) do
+          :noreply -> :ok
+          response -> send_response(response)
         end
+
+        state
       end
 
-      defp handle_request(%{method: "initialize", id: id}) do
+      defp dispatch(%{method: "initialize", id: id, params: params}) do
         info = agent_info()
+        protocol_version = Protocol.negotiate_version(params["protocolVersion"])
+
+        if protocol_version != params["protocolVersion"] do
+          Logger.warning(
+            "Client requested unsupported MCP version #{inspect(params["protocolVersion"])}, " <>
+              "offering #{protocol_version}"
+          )
+        end
 
         result =
           Protocol.initialize_response(
             name: info.name,
             version: info.version,
-            tools: if(Enum.empty?(tools()), do: %{}, else: %{listChanged: false}),
-            prompts: if(Enum.empty?(prompts()), do: %{}, else: %{listChanged: false}),
-            resources: if(Enum.empty?(resources()), do: %{}, else: %{listChanged: false})
+            protocol_version: protocol_version,
+            tools: %{listChanged: true},
+            prompts: if(Enum.empty?(prompts()), do: nil, else: %{listChanged: false}),
+            resources:
+              if(Enum.empty?(resources()) and Enum.empty?(resource_templates()),
+                do: nil,
+                else: %{subscribe: false, listChanged: false}
+              ),
+            logging: %{},
+            completions: %{}
           )
 
-        response = Protocol.success_response(id, result)
-        send_response(response)
+        Protocol.success_response(id, result)
+      end
+
+      defp dispatch(%{method: "ping", id: id}) do
+        Protocol.success_response(id, %{})
+      end
+
+      defp dispatch(%{method: "tools/list", id: id}) do
+        result = Protocol.tools_list_response(available_tools())
+        Protocol.success_response(id, result)
+      end
+
+      defp dispatch(%{method: "prompts/list", id: id}) do
+        result = %{prompts: prompts()}
+        Protocol.success_response(id, result)
+      end
+
+      defp dispatch(%{method: "prompts/get", id: id, params: params}) do
+        error_codes = Protocol.error_codes()
+        prompt_name = params["name"]
+
+        case get_prompt(prompt_name, params["arguments"] || %{}) do
+          {:ok, %{messages: messages} = prompt} ->
+            result = Protocol.prompts_get_response(prompt[:description], messages)
+            Protocol.success_response(id, result)
+
+          {:error, :prompt_not_found} ->
+            Protocol.error_response(
+              id,
+              error_codes.invalid_params,
+              "Unknown prompt: #{prompt_name}"
+            )
+
+          {:error, {:invalid_params, message}} ->
+            Protocol.error_response(id, error_codes.invalid_params, message)
+
+          {:error, reason} ->
+            Protocol.error_response(
+              id,
+              error_codes.internal_error,
+              "Prompt rendering failed",
+              inspect(reason)
+            )
+        end
+      end
+
+      defp dispatch(%{method: "resources/list", id: id}) do
+        result = %{resources: resources()}
+        Protocol.success_response(id, result)
+      end
+
+      defp dispatch(%{method: "resources/templates/list", id: id}) do
+        result = Protocol.resource_templates_list_response(resource_templates())
+        Protocol.success_response(id, result)
+      end
+
+      defp dispatch(%{method: "resources/read", id: id, params: params}) do
+        error_codes = Protocol.error_codes()
+        uri = params["uri"]
+
+        case read_resource(uri) do
+          {:ok, contents} ->
+            Protocol.success_response(id, Protocol.resources_read_response(contents))
+
+          {:error, :resource_not_found} ->
+            Protocol.error_response(
+              id,
+              error_codes.resource_not_found,
+              "Resource not found",
+              %{uri: uri}
+            )
+
+          {:error, reason} ->
+            Protocol.error_response(
+              id,
+              error_codes.internal_error,
+              "Resource read failed",
+              inspect(reason)
+            )
+        end
+      end
+
+      defp dispatch(%{method: "completion/complete", id: id, params: params}) do
+        error_codes = Protocol.error_codes()
+
+        case complete(params["ref"] || %{}, params["argument"] || %{}) do
+          {:ok, values} ->
+            Protocol.success_response(id, Protocol.completion_response(values))
+
+          {:error, reason} ->
+            Protocol.error_response(
+              id,
+              error_codes.invalid_params,
+              "Completion failed",
+              inspect(reason)
+            )
+        end
+      end
+
+      defp dispatch(%{method: "notifications/" <> _, id: nil}) do
+        # Notifications don't require a response
+        :noreply
+      end
+
+      defp dispatch(%{method: method, id: id}) do
+        error_codes = Protocol.error_codes()
+
+        Protocol.error_response(
+          id,
+          error_codes.method_not_found,
+          "Method not found: #{method}"
+        )
       end
 
-      defp handle_request(%{method: "tools/list", id: id}) do
-        result = Protocol.tools_list_response(tools())
-        response = Protocol.success_response(id, result)
-        send_response(response)
+      # The level applies to the client's session only
+      defp set_log_level(id, params, session) do
+        case Logging.set_level(params["level"], session) do
+          :ok ->
+            Protocol.success_response(id, %{})
+
+          {:error, :invalid_level} ->
+            error_codes = Protocol.error_codes()
+
+            Protocol.error_response(
+              id,
+              error_codes.invalid_params,
+              "Invalid log level: #{inspect(params["level"])}",
+              %{levels: Logging.levels()}
+            )
+        end
       end
 
-      defp handle_request(%{method: "tools/call", id: id, params: params}) do
+      # Runs inside a supervised task; returns the JSON-RPC response for the loop to write
+      defp call_tool(id, params) do
         tool_name = params["name"]
         arguments = params["arguments"] || %{}
+        error_codes = Protocol.error_codes()
+
+        with true <- tool_available?(tool_name),
+             :ok <- validate_tool_arguments(tool_name, arguments) do
+          execute_tool_call(id, tool_name, arguments)
+        else
+          false ->
+            Protocol.error_response(id, error_codes.invalid_params, "Unknown tool: #{tool_name}")
+
+          {:error, errors} ->
+            Protocol.error_response(
+              id,
+              error_codes.invalid_params,
+              "Invalid arguments for #{tool_name}",
+              %{errors: errors}
+            )
+        end
+      end
+
+      # Tools not declared in tools/0 still reach execute_tool/2, which reports them
+      defp tool_available?(tool_name) do
+        declared? = Enum.any?(all_tools(), &((&1[:name] || &1["name"]) == tool_name))
+
+        not declared? or Enum.any?(available_tools(), &((&1[:name] || &1["name"]) == tool_name))
+      end
+
+      defp all_tools, do: tools() ++ SharedTools.list()
+
+      defp run_tool(tool_name, arguments) do
+        if SharedTools.shared?(tool_name) do
+          SharedTools.execute(agent_info().role, tool_name, arguments)
+        else
+          execute_tool(tool_name, arguments)
+        end
+      end
 
-        case execute_tool(tool_name, arguments) do
+      defp execute_tool_call(id, tool_name, arguments) do
+        case run_tool(tool_name, arguments) do
           {:ok, result_text} when is_binary(result_text) ->
             result = Protocol.tools_call_response(result_text)
-            response = Protocol.success_response(id, result)
-            send_response(response)
+            Protocol.success_response(id, result)
 
           {:ok, content} when is_list(content) ->
             result = Protocol.tools_call_response(content)
-            response = Protocol.success_response(id, result)
-            send_response(response)
+            Protocol.success_response(id, result)
+
+          {:ok, result_text, structured} when is_binary(result_text) and is_map(structured) ->
+            structured_response(id, tool_name, result_text, structured)
 
           {:ok, content} when is_map(content) ->
-            # Handle map results (e.g., from session_consult tool)
-            # Convert map to JSON string for MCP response
-            result_text = Jason.encode!(content)
-            result = Protocol.tools_call_response(result_text)
-            response = Protocol.success_response(id, result)
-            send_response(response)
+            # Map results (e.g., from session_consult) double as their own text rendering
+            structured_response(id, tool_name, Jason.encode!(content), content)
 
           {:error, reason} ->
             error_codes = Protocol.error_codes()
 
-            response =
-              Protocol.error_response(
-                id,
-                error_codes.internal_error,
-                "Tool execution failed",
-                inspect(reason)
-              )
-
-            send_response(response)
+            Protocol.error_response(
+              id,
+              error_codes.internal_error,
+              "Tool execution failed",
+              inspect(reason)
+            )
         end
       end
 
-      defp handle_request(%{method: "prompts/list", id: id}) do
-        result = %{prompts: prompts()}
-        response = Protocol.success_response(id, result)
-        send_response(response)
-      end
-
-      defp handle_request(%{method: "resources/list", id: id}) do
-        result = %{resources: resources()}
-        response = Protocol.success_response(id, result)
-        send_response(response)
-      end
+      defp structured_response(id, tool_name, result_text, structured) do
+        # Round-trip through JSON so DateTimes, atoms, etc. match what the client sees
+        structured = structured |> Jason.encode!() |> Jason.decode!()
+        tool = Enum.find(all_tools(), &((&1[:name] || &1["name"]) == tool_name)) || %{}
 
-      defp handle_request(%{method: "notifications/" <> _, id: nil}) do
-        # Notifications don't require a response
-        :ok
-      end
+        case SchemaValidator.validate(structured, tool[:outputSchema] || tool["outputSchema"] || %{}) do
+          :ok ->
+            Protocol.success_response(id, Protocol.tools_call_response(result_text, structured))
 
-      defp handle_request(%{method: method, id: id}) do
-        error_codes = Protocol.error_codes()
-
-        response =
-          Protocol.error_response(
-            id,
-            error_codes.method_not_found,
-            "Method not found: #{method}"
-          )
+          {:error, errors} ->
+            Logger.error("Tool #{tool_name} returned output not matching its outputSchema: #{inspect(errors)}")
+            error_codes = Protocol.error_codes()
 
-        send_response(response)
+            Protocol.error_response(
+              id,
+              error_codes.internal_error,
+              "Tool returned invalid structured content",
+              %{errors: errors}
+            )
+        end
       end
 
       defp send_response(response) do
@@ -0,0 +1,255 @@
+defmodule EchoShared.MCP.SharedTools do
+  @moduledoc """
+  MCP tools offered by every ECHO agent in addition to its own `tools/0`.
+
+  ## Tools
+
+  - `message_thread` - Read a conversation thread between agents, or reply
+    in it (see `EchoShared.MessageBus.fetch_thread/1`)
+  - `cast_vote` - Vote on a collaborative decision the agent participates
+    in (see `EchoShared.Decisions.Voting`)
+  - `escalate_decision` - Escalate a decision up the reporting chain to
+    the first role with authority (see `EchoShared.Decisions.Escalation`)
+  """
+
+  alias EchoShared.Decisions.{Escalation, Voting}
+  alias EchoShared.MessageBus
+  alias EchoShared.Repo
+  alias EchoShared.Schemas.Decision
+
+  @doc """
+  List shared tool definitions.
+  """
+  @spec list() :: [map()]
+  def list do
+    [
+      %{
+        name: "message_thread",
+        description: "Read a conversation thread between agents, or continue it with a reply",
+        inputSchema: %{
+          type: "object",
+          properties: %{
+            action: %{
+              type: "string",
+              enum: ["read", "reply"],
+              description: "Read the thread or add a reply to it"
+            },
+            message_id: %{
+              type: "integer",
+              description: "Any message in the thread (for reply: the message being answered)"
+            },
+            content: %{
+              type: "string",
+              description: "Reply text (required for reply)"
+            },
+            to_role: %{
+              type: "string",
+              description: "Reply recipient (default: the other party of message_id)"
+            }
+          },
+          required: ["action", "message_id"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            thread_id: %{type: "integer"},
+            message_id: %{type: "integer"},
+            messages: %{
+              type: "array",
+              items: %{
+                type: "object",
+                properties: %{
+                  id: %{type: "integer"},
+                  in_reply_to: %{type: ["integer", "null"]},
+                  from_role: %{type: "string"},
+                  to_role: %{type: "string"},
+                  type: %{type: "string"},
+                  subject: %{type: "string"},
+                  content: %{type: "object"},
+                  inserted_at: %{type: "string"}
+                },
+                required: ["id", "from_role", "to_role", "subject"]
+              }
+            }
+          },
+          required: ["thread_id"]
+        }
+      },
+      %{
+        name: "cast_vote",
+        description: "Vote on a collaborative decision this agent participates in",
+        inputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{
+              type: "string",
+              description: "The decision being voted on"
+            },
+            vote: %{
+              type: "string",
+              enum: ["approve", "reject", "abstain"],
+              description: "This agent's vote"
+            },
+            confidence: %{
+              type: "number",
+              minimum: 0,
+              maximum: 1,
+              description: "Confidence in the vote, used as its weight (default: 1.0)"
+            },
+            rationale: %{
+              type: "string",
+              description: "Why this agent votes this way"
+            }
+          },
+          required: ["decision_id", "vote"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{type: "string"},
+            vote: %{type: "string"},
+            confidence: %{type: "number"},
+            decision_status: %{type: "string"},
+            consensus_score: %{type: ["number", "null"]}
+          },
+          required: ["decision_id", "vote", "decision_status"]
+        }
+      },
+      %{
+        name: "escalate_decision",
+        description: "Escalate a decision beyond this agent's authority up the reporting chain",
+        inputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{
+              type: "string",
+              description: "The decision to escalate"
+            },
+            reason: %{
+              type: "string",
+              description: "Why the decision needs escalating"
+            },
+            urgency: %{
+              type: "string",
+              enum: ["low", "medium", "high", "critical"],
+              description: "Urgency level for the response"
+            },
+            context: %{
+              type: "object",
+              description: "Additional context for whoever receives the decision"
+            }
+          },
+          required: ["decision_id", "reason"]
+        },
+        outputSchema: %{
+          type: "object",
+          properties: %{
+            decision_id: %{type: "string"},
+            status: %{type: "string"},
+            escalated_to: %{type: "string"},
+            hops: %{
+              type: "array",
+              items: %{
+                type: "object",
+                properties: %{
+                  from: %{type: "string"},
+                  to: %{type: "string"},
+                  reason: %{type: "string"},
+                  authority: %{type: "boolean"},
+                  at: %{type: "string"}
+                },
+                required: ["from", "to", "authority"]
+              }
+            }
+          },
+          required: ["decision_id", "status", "escalated_to", "hops"]
+        }
+      }
+    ]
+  end
+
+  @doc """
+  Whether `name` is a shared tool.
+  """
+  @spec shared?(String.t()) :: boolean()
+  def shared?(name), do: Enum.any?(list(), &(&1.name == name))
+
+  @doc """
+  Execute a shared tool on behalf of an agent role.
+
+  Arguments are validated against the tool's `inputSchema` by the server.
+  """
+  @spec execute(atom(), String.t(), map()) :: {:ok, String.t(), map()} | {:error, term()}
+  def execute(_role, "message_thread", %{"action" => "read", "message_id" => message_id}) do
+    with {:ok, messages} <- MessageBus.fetch_thread(message_id) do
+      thread_id = hd(messages).id
+
+      {:ok, "Thread #{thread_id}: #{length(messages)} messages",
+       %{thread_id: thread_id, messages: Enum.map(messages, &message_data/1)}}
+    end
+  end
+
+  def execute(role, "message_thread", %{"action" => "reply", "message_id" => message_id} = args) do
+    case args["content"] do
+      content when is_binary(content) and content != "" ->
+        opts = if args["to_role"], do: [to: args["to_role"]], else: []
+
+        with {:ok, db_id} <- MessageBus.continue_thread(message_id, role, content, opts),
+             {:ok, messages} <- MessageBus.fetch_thread(db_id) do
+          {:ok, "Replied in thread #{hd(messages).id} (message #{db_id})",
+           %{thread_id: hd(messages).id, message_id: db_id}}
+        end
+
+      _missing ->
+        {:error, {:invalid_params, "Missing required argument: content"}}
+    end
+  end
+
+  def execute(role, "cast_vote", %{"decision_id" => decision_id, "vote" => vote} = args) do
+    opts = [confidence: args["confidence"] || 1.0, rationale: args["rationale"]]
+
+    with {:ok, recorded} <- Voting.cast_vote(decision_id, role, vote, opts) do
+      decision = Repo.get!(Decision, decision_id)
+
+      {:ok, "Voted #{recorded.vote} on decision #{decision_id} (decision #{decision.status})",
+       %{
+         decision_id: decision_id,
+         vote: recorded.vote,
+         confidence: recorded.confidence,
+         decision_status: decision.status,
+         consensus_score: decision.consensus_score
+       }}
+    end
+  end
+
+  def execute(role, "escalate_decision", %{"decision_id" => decision_id, "reason" => reason} = args) do
+    opts = [from: role, urgency: args["urgency"], context: args["context"]]
+
+    with {:ok, decision} <- Escalation.escalate(decision_id, reason, opts) do
+      {:ok, "Escalated decision #{decision_id} to #{decision.escalated_to}",
+       %{
+         decision_id: decision.id,
+         status: decision.status,
+         escalated_to: decision.escalated_to,
+         hops: decision.escalation_hops
+       }}
+    end
+  end
+
+  def execute(_role, name, _args), do: {:error, {:unknown_tool, name}}
+
+  ## Private Functions
+
+  defp message_data(message) do
+    %{
+      id: message.id,
+      in_reply_to: message.in_reply_to,
+      from_role: message.from_role,
+      to_role: message.to_role,
+      type: to_string(message.type),
+      subject: message.subject,
+      content: message.content,
+      inserted_at: message.inserted_at
+    }
+  end
+end
@@ -0,0 +1,154 @@
+defmodule EchoShared.MCP.ToolRegistry do
+  @moduledoc """
+  Runtime tool visibility for ECHO MCP servers.
+
+  `tools/0` declares every tool an agent knows how to run. Which of those
+  are listed and callable is decided at runtime:
+
+  1. An explicit override set here with `enable/2` or `disable/2`
+  2. Otherwise the agent's `tool_enabled?/1` callback (by default
+     `ai_consult` is hidden when `Config.llm_enabled?/1` is false)
+
+  Every change sends `notifications/tools/list_changed` to subscribed
+  transports (the stdio loop and open HTTP SSE streams) so clients
+  re-fetch `tools/list`.
+
+  ## Example
+
+      # Expose workflow-only tools while a workflow runs
+      ToolRegistry.enable(Ceo, "approve_workflow_step")
+      ...
+      ToolRegistry.reset(Ceo, "approve_workflow_step")
+  """
+
+  use GenServer
+  require Logger
+
+  alias EchoShared.MCP.Protocol
+
+  ## Client API
+
+  def start_link(opts \\ []) do
+    GenServer.start_link(__MODULE__, opts, name: __MODULE__)
+  end
+
+  @doc """
+  Force a tool to be listed, regardless of `tool_enabled?/1`.
+  """
+  @spec enable(module(), String.t()) :: :ok
+  def enable(server, tool_name), do: GenServer.call(__MODULE__, {:put, server, tool_name, true})
+
+  @doc """
+  Force a tool to be hidden and rejected by `tools/call`.
+  """
+  @spec disable(module(), String.t()) :: :ok
+  def disable(server, tool_name), do: GenServer.call(__MODULE__, {:put, server, tool_name, false})
+
+  @doc """
+  Remove an override so `tool_enabled?/1` decides again.
+  """
+  @spec reset(module(), String.t()) :: :ok
+  def reset(server, tool_name), do: GenServer.call(__MODULE__, {:reset, server, tool_name})
+
+  @doc """
+  Get the override for a tool.
+
+  ## Returns
+
+  - `true` / `false` - Explicitly enabled or disabled
+  - `nil` - No override (or registry not running)
+  """
+  @spec override(module(), String.t()) :: boolean() | nil
+  def override(server, tool_name) do
+    if Process.whereis(__MODULE__) do
+      GenServer.call(__MODULE__, {:override, server, tool_name})
+    end
+  end
+
+  @doc """
+  Receive `{:mcp_notification, notification}` whenever the server's tool
+  list changes. The subscription ends when the calling process exits.
+  """
+  @spec subscribe(module()) :: :ok
+  def subscribe(server) do
+    if Process.whereis(__MODULE__) do
+      GenServer.call(__MODULE__, {:subscribe, server, self()})
+    else
+      :ok
+    end
+  end
+
+  @doc """
+  Notify subscribers that the server's tool list changed.
+
+  Called automatically by `enable/2`, `disable/2` and `reset/2`. Call it
+  directly when a condition checked by `tool_enabled?/1` changes.
+  """
+  @spec notify_changed(module()) :: :ok
+  def notify_changed(server), do: GenServer.cast(__MODULE__, {:notify_changed, server})
+
+  ## Server Callbacks
+
+  @impl true
+  def init(_opts) do
+    {:ok, %{overrides: %{}, subscribers: %{}}}
+  end
+
+  @impl true
+  def handle_call({:put, server, tool_name, enabled}, _from, state) do
+    key = {server, tool_name}
+
+    if Map.get(state.overrides, key) == enabled do
+      {:reply, :ok, state}
+    else
+      Logger.info("#{inspect(server)}: tool #{tool_name} #{if enabled, do: "enabled", else: "disabled"}")
+      state = put_in(state.overrides[key], enabled)
+      broadcast(state, server)
+      {:reply, :ok, state}
+    end
+  end
+
+  def handle_call({:reset, server, tool_name}, _from, state) do
+    case Map.pop(state.overrides, {server, tool_name}) do
+      {nil, _overrides} ->
+        {:reply, :ok, state}
+
+      {_enabled, overrides} ->
+        state = %{state | overrides: overrides}
+        broadcast(state, server)
+        {:reply, :ok, state}
+    end
+  end
+
+  def handle_call({:override, server, tool_name}, _from, state) do
+    {:reply, Map.get(state.overrides, {server, tool_name}), state}
+  end
+
+  def handle_call({:subscribe, server, pid}, _from, state) do
+    ref = Process.monitor(pid)
+    {:reply, :ok, put_in(state.subscribers[ref], {server, pid})}
+  end
+
+  @impl true
+  def handle_cast({:notify_changed, server}, state) do
+    broadcast(state, server)
+    {:noreply, state}
+  end
+
+  @impl true
+  def handle_info({:DOWN, ref, :process, _pid, _reason}, state) do
+    {:noreply, %{state | subscribers: Map.delete(state.subscribers, ref)}}
+  end
+
+  ## Private Functions
+
+  defp broadcast(state, server) do
+    notification = Protocol.notification("notifications/tools/list_changed")
+
+    for {_ref, {^server, pid}} <- state.subscribers do
+      send(pid, {:mcp_notification, notification})
+    end
+
+    :ok
+  end
+end
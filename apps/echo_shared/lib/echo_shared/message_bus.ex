@@ -1,8 +1,15 @@
 defmodule EchoShared.MessageBus do
   @moduledoc """
-  Redis-based message bus for inter-agent communication.
+  Message bus for inter-agent communication.
 
-  Provides real-time pub/sub messaging between ECHO agents using Redis.
+  Provides real-time pub/sub messaging between ECHO agents. Messages are
+  persisted to PostgreSQL; notifications go through a pluggable adapter
+  (see `EchoShared.MessageBus.Adapter`): Redis (default), PostgreSQL
+  LISTEN/NOTIFY, or in-process for single-node development and tests.
+
+  Select with `MESSAGE_BUS_ADAPTER=redis|postgres|local` or:
+
+      config :echo_shared, :message_bus_adapter, :local
 
   ## Channels
 
@@ -16,12 +23,98 @@ defmodule EchoShared.MessageBus do
   - `agents:heartbeat` - Agent health checks
   - `agents:status` - Agent status updates
 
+  ## Transports
+
+  With the Redis adapter, messages are delivered using one of two
+  transports (other adapters always use `:pubsub`):
+
+  - `:pubsub` (default) - Adapter publish. Fast, but a subscriber that isn't
+    connected at that moment misses the notification and relies on
+    `fetch_unread_messages/1`.
+  - `:streams` - Redis Streams with a consumer group per role (see
+    `EchoShared.MessageBus.StreamConsumer`). At-least-once delivery: entries
+    wait for offline agents and stay pending until `mark_message_processed/1`.
+
+  Select with `MESSAGE_BUS_TRANSPORT=streams` or:
+
+      config :echo_shared, :message_bus_transport, :streams
+
+  Decision events and heartbeats always use the adapter's publish.
+
+  ## Retries and Dead Letters
+
+  A handler that can't process a message calls `mark_message_failed/2`.
+  The message stays unread and is redelivered after an exponential
+  backoff (10s, 20s, 40s, ... capped at 10 minutes) by
+  `EchoShared.MessageBus.RetryScheduler`. After `max_attempts/0` failures
+  it moves to the `dead_letters` table, where it can be listed, requeued
+  or discarded (`list_dead_letters/1`, `requeue_dead_letter/1`,
+  `discard_dead_letter/1`).
+
+  Configure the attempt limit with `MESSAGE_MAX_ATTEMPTS=8` or:
+
+      config :echo_shared, :message_max_attempts, 8
+
+  ## Broadcast Receipts
+
+  Broadcasts are stored once with `to_role = "all"`. Delivery and
+  processing are tracked per recipient role in `message_receipts`, so
+  handlers pass their role when acknowledging a broadcast:
+
+      MessageBus.mark_message_processed(db_id, :cto)
+
+  On `subscribe_to_role/1`, broadcasts the role hasn't processed yet are
+  replayed to the subscriber.
+
+  ## Threads
+
+  Pass `in_reply_to: message_id` in a message's metadata to put it in the
+  same thread as that message (`reply/2` does this for responses).
+  Delivered messages carry `thread_id` and `in_reply_to`; read a thread
+  with `fetch_thread/1` and add to it with `continue_thread/4`.
+
+  ## Priority and Expiry
+
+  Metadata may also carry:
+
+  - `priority:` - `:low`, `:normal` (default), `:high` or `:urgent`;
+    escalations default to `:urgent`
+  - `ttl:` - Seconds until the message expires, or `expires_at:` a
+    `DateTime`
+
+  `fetch_unread_messages/1` returns the highest priority first and skips
+  expired messages; `EchoShared.MessageBus.ExpirySweeper` archives expired
+  messages that were never processed so they aren't executed after an
+  agent restarts.
+
+  ## Communication Policy
+
+  `publish_message/6` and `broadcast_message/5` only send what
+  `EchoShared.MessageBus.Policy` allows (e.g. only the C-suite may use the
+  leadership channel); anything else returns `{:error, :forbidden}` and is
+  recorded in `policy_violations`.
+
+  ## Signing
+
+  Message envelopes and request replies are signed with the sending
+  role's private key (see `EchoShared.MessageBus.Signing`). Handlers decode
+  payloads with `decode_message/2`, which rejects unsigned, forged, stale
+  or replayed messages on `messages:*` channels instead of trusting their
+  `from`. Decision events and heartbeats are signed and verified the same
+  way, as their publishing role. A node only redelivers messages from roles it holds keys for.
+
   ## Usage
 
   ```elixir
   # Publish a message
   MessageBus.publish_message(:ceo, :cto, :request, "Q3 Strategy Review", %{...})
 
+  # Send a request and wait for the response
+  {:ok, content} = MessageBus.request(:ceo, :cto, "Capacity Review", %{...}, timeout: 60_000)
+
+  # In the recipient's message handler
+  MessageBus.reply(message, %{capacity: "ok"})
+
   # Subscribe to messages
   MessageBus.subscribe_to_role(:ceo)
 
@@ -34,6 +127,26 @@ defmodule EchoShared.MessageBus do
 
   require Logger
 
+  alias EchoShared.MessageBus.{Adapters, Policy, Signing, StreamConsumer}
+  alias EchoShared.Repo
+  alias EchoShared.Schemas.{DeadLetter, Message, MessageReceipt, OutboxEntry, PolicyViolation}
+
+  import Ecto.Query
+
+  # Approximate cap on entries kept per stream
+  @stream_max_length 10_000
+
+  @default_request_timeout 30_000
+  # Unclaimed replies expire from Redis after this long
+  @reply_ttl_seconds 300
+
+  @default_max_attempts 5
+  @retry_base_seconds 10
+  @retry_max_seconds 600
+
+  @default_outbox_max_attempts 10
+  @outbox_retry_max_seconds 300
+
   @type role ::
           :ceo
           | :cto
@@ -50,49 +163,43 @@ defmodule EchoShared.MessageBus do
   @doc """
   Publish a message to a specific agent.
 
-  Uses dual-write pattern:
-  1. Persists to database first (durable)
-  2. Publishes to Redis (fast notification)
+  Uses dual-write pattern through a transactional outbox:
+  1. Persists the message and an outbox row in one transaction (durable)
+  2. Publishes to Redis (fast notification) and marks the row sent
 
   This ensures messages are never lost even if Redis is unavailable
-  or if the recipient agent is down.
+  or if the recipient agent is down: unsent rows are published by
+  `EchoShared.MessageBus.OutboxRelay`.
+
+  Called inside the caller's `Repo.transaction`, the message is only
+  written there and published by the relay after the commit, so a
+  state change and its notification succeed or fail together:
+
+      Repo.transaction(fn ->
+        {:ok, decision} = Repo.update(changeset)
+        {:ok, _} = MessageBus.publish_message(:ceo, :cto, :notification, "Approved", %{id: decision.id})
+      end)
+
+  Messages the communication policy doesn't allow (see
+  `EchoShared.MessageBus.Policy`) are not stored or delivered: the
+  attempt is logged to `policy_violations` and `{:error, :forbidden}`
+  returned.
   """
   @spec publish_message(role(), role(), message_type(), String.t(), map(), map()) ::
           {:ok, integer()} | {:error, term()}
   def publish_message(from, to, type, subject, content, metadata \\ %{}) do
-    # Step 1: Store in database FIRST (durable)
-    case store_message_in_db(from, to, type, subject, content) do
-      {:ok, db_message} ->
-        # Step 2: Publish to Redis for fast notification
-        message = %{
-          db_id: db_message.id,
-          id: generate_message_id(),
-          from: to_string(from),
-          to: to_string(to),
-          type: to_string(type),
-          subject: subject,
-          content: content,
-          metadata: Map.merge(metadata, %{timestamp: DateTime.utc_now() |> DateTime.to_iso8601()})
-        }
-
-        channel = "messages:#{to}"
-
-        case Jason.encode(message) do
-          {:ok, json} ->
-            # Best effort: if Redis fails, message is still in DB
-            case Redix.command(:redix, ["PUBLISH", channel, json]) do
-              {:ok, _} -> {:ok, db_message.id}
-              {:error, redis_error} ->
-                Logger.warning("Redis publish failed: #{inspect(redis_error)}, message stored in DB")
-                {:ok, db_message.id}
-            end
-
-          {:error, reason} ->
-            {:error, {:encode_error, reason}}
-        end
+    with :ok <- authorize(from, to, type, subject, metadata),
+         {:ok, {db_message, entry}} <- enqueue_message(from, to, type, subject, content, metadata) do
+      # Best effort: if Redis fails, the relay publishes the outbox row later
+      case publish_outbox_entry(entry) do
+        {:error, redis_error} ->
+          Logger.warning("Redis publish failed: #{inspect(redis_error)}, message queued in outbox")
 
-      {:error, reason} ->
-        {:error, {:db_error, reason}}
+        _published_or_deferred ->
+          :ok
+      end
+
+      {:ok, db_message.id}
     end
   end
 
@@ -100,93 +207,291 @@ defmodule EchoShared.MessageBus do
   Broadcast a message to all agents.
 
   Uses dual-write pattern (like publish_message):
-  1. Persists to database first with to_role="all" (durable)
+  1. Persists to database with to_role="all" plus an outbox row (durable)
   2. Publishes to Redis (fast notification)
   3. Monitors subscriber count for delivery verification
 
   This ensures broadcasts are never lost during agent restarts.
+
+  Returns `{:error, :forbidden}` when the policy doesn't allow `from` to
+  broadcast `type`, like `publish_message/6`.
   """
   @spec broadcast_message(role(), message_type(), String.t(), map(), map()) ::
           {:ok, integer()} | {:error, term()}
   def broadcast_message(from, type, subject, content, metadata \\ %{}) do
-    # Step 1: Store in database FIRST (durable) with to_role = "all"
-    case store_message_in_db(from, :all, type, subject, content) do
-      {:ok, db_message} ->
-        # Step 2: Publish to Redis for fast notification
-        message = %{
-          db_id: db_message.id,
-          id: generate_message_id(),
-          from: to_string(from),
-          to: "all",
-          type: to_string(type),
-          subject: subject,
-          content: content,
-          metadata: Map.merge(metadata, %{timestamp: DateTime.utc_now() |> DateTime.to_iso8601()})
-        }
-
-        channel = "messages:all"
-
-        case Jason.encode(message) do
-          {:ok, json} ->
-            # Best effort: if Redis fails, message is still in DB
-            case Redix.command(:redix, ["PUBLISH", channel, json]) do
-              {:ok, subscriber_count} ->
-                if subscriber_count == 0 do
-                  Logger.warning("Broadcast sent but no subscribers on #{channel} (message stored in DB)")
-                end
-                {:ok, db_message.id}
-
-              {:error, redis_error} ->
-                Logger.warning("Redis broadcast failed: #{inspect(redis_error)}, message stored in DB")
-                {:ok, db_message.id}
-            end
-
-          {:error, reason} ->
-            {:error, {:encode_error, reason}}
-        end
+    with :ok <- authorize(from, :all, type, subject, metadata),
+         {:ok, {db_message, entry}} <- enqueue_message(from, :all, type, subject, content, metadata) do
+      # Best effort: if Redis fails, the relay publishes the outbox row later
+      case publish_outbox_entry(entry) do
+        {:ok, subscriber_count} ->
+          # Stream entries wait for consumers, so only PUBLISH can go unheard
+          if transport() == :pubsub and subscriber_count == 0 do
+            Logger.warning("Broadcast sent but no subscribers on messages:all (message stored in DB)")
+          end
 
-      {:error, reason} ->
-        {:error, {:db_error, reason}}
+        {:error, redis_error} ->
+          Logger.warning("Redis broadcast failed: #{inspect(redis_error)}, message queued in outbox")
+
+        _deferred ->
+          :ok
+      end
+
+      {:ok, db_message.id}
+    end
+  end
+
+  @doc """
+  Publish pending outbox rows to Redis.
+
+  Called periodically by `EchoShared.MessageBus.OutboxRelay`. Rows are
+  published in insertion order and locked with `FOR UPDATE SKIP LOCKED`,
+  so concurrent relays don't publish the same row.
+
+  A row that fails to publish is retried after an exponential backoff
+  (1s, 2s, 4s, ... capped at 5 minutes). After `outbox_max_attempts/0`
+  failures it is marked failed and no longer relayed; its message stays
+  unread, so the recipient still gets it from `fetch_unread_messages/1`.
+
+  ## Options
+
+  - `:limit` - Maximum rows per call (default: 100)
+
+  ## Returns
+
+  - `{:ok, count}` - Number of rows published
+  - `{:error, reason}` - The batch couldn't be claimed
+  """
+  @spec relay_outbox(keyword()) :: {:ok, non_neg_integer()} | {:error, term()}
+  def relay_outbox(opts \\ []) do
+    limit = Keyword.get(opts, :limit, 100)
+
+    Repo.transaction(fn ->
+      now = DateTime.utc_now()
+
+      from(o in OutboxEntry,
+        where: is_nil(o.sent_at) and is_nil(o.failed_at),
+        where: is_nil(o.next_attempt_at) or o.next_attempt_at <= ^now,
+        order_by: [asc: o.id],
+        limit: ^limit,
+        lock: "FOR UPDATE SKIP LOCKED"
+      )
+      |> Repo.all()
+      |> Enum.count(&match?({:ok, _}, deliver_outbox_entry(&1)))
+    end)
+  end
+
+  @doc """
+  Send a request to an agent and wait for its response.
+
+  The request is published like `publish_message/6` with a correlation ID
+  and a reply-to key in its metadata. The recipient answers with `reply/2`;
+  the caller blocks until the matching response arrives or the timeout
+  expires. Replies are pushed to a Redis list, so a response sent before
+  the caller starts waiting is not lost.
+
+  ## Parameters
+
+  - `from` - Requesting role (or e.g. `:workflow_engine`)
+  - `to` - Role that should answer
+  - `subject` - Request subject (usually the tool or request type)
+  - `content` - Request payload
+  - `opts` - Options:
+    - `:timeout` - Milliseconds to wait for the response (default: 30s)
+
+  ## Returns
+
+  - `{:ok, content}` - The response content
+  - `{:error, :timeout}` - No response in time
+  - `{:error, {:remote_error, error}}` - The recipient replied with an error
+  - `{:error, reason}` - The request couldn't be sent
+  """
+  @spec request(atom(), atom(), String.t(), map(), keyword()) :: {:ok, map()} | {:error, term()}
+  def request(from, to, subject, content, opts \\ []) do
+    timeout = Keyword.get(opts, :timeout, @default_request_timeout)
+    correlation_id = generate_correlation_id()
+    reply_to = "replies:#{correlation_id}"
+
+    metadata = %{correlation_id: correlation_id, reply_to: reply_to}
+
+    with {:ok, _db_id} <- publish_message(from, to, :request, subject, content, metadata) do
+      Logger.debug("Awaiting #{to} response to #{subject} (#{correlation_id})")
+      await_reply(reply_to, timeout)
+    end
+  end
+
+  @doc """
+  Reply to a request received by a message handler.
+
+  Sends a `:response` message back to the requester (stored in the
+  database like any other message) and, if the request came from
+  `request/5`, wakes the waiting caller.
+
+  ## Parameters
+
+  - `request` - The decoded request message (string keys, as delivered to handlers)
+  - `result` - Response content: a map, a string (sent as `%{"text" => ...}`),
+    or `{:error, reason}` to fail the caller's `request/5`
+
+  ## Example
+
+      defp handle_request(message) do
+        MessageBus.reply(message, %{status: "approved"})
+      end
+  """
+  @spec reply(map(), map() | String.t() | {:error, term()}) :: {:ok, integer()} | {:error, term()}
+  def reply(request, result) do
+    metadata = request["metadata"] || %{}
+    {status, content} = reply_content(result)
+
+    reply_metadata = %{
+      correlation_id: metadata["correlation_id"],
+      in_reply_to: request["db_id"] || request["id"],
+      status: status
+    }
+
+    with {:ok, db_id} <-
+           publish_message(
+             request["to"] || "unknown",
+             request["from"],
+             :response,
+             "Re: #{request["subject"]}",
+             content,
+             reply_metadata
+           ) do
+      if reply_to = metadata["reply_to"] do
+        reply =
+          %{db_id: db_id, from: request["to"], status: status, content: content}
+          |> Signing.sign()
+          |> Jason.encode!()
+
+        adapter().push_reply(reply_to, reply, @reply_ttl_seconds)
+      end
+
+      {:ok, db_id}
+    end
+  end
+
+  @doc """
+  Get the configured adapter module.
+
+  Accepts `:redis`, `:postgres`, `:local` or an adapter module.
+  """
+  @spec adapter() :: module()
+  def adapter do
+    case System.get_env("MESSAGE_BUS_ADAPTER") do
+      nil -> adapter_module(Application.get_env(:echo_shared, :message_bus_adapter, :redis))
+      name -> adapter_module(String.to_existing_atom(name))
+    end
+  end
+
+  @doc """
+  Get the configured delivery transport (`:pubsub` or `:streams`).
+
+  `:streams` only applies to the Redis adapter.
+  """
+  @spec transport() :: :pubsub | :streams
+  def transport do
+    configured =
+      case System.get_env("MESSAGE_BUS_TRANSPORT") do
+        "streams" -> :streams
+        "pubsub" -> :pubsub
+        _ -> Application.get_env(:echo_shared, :message_bus_transport, :pubsub)
+      end
+
+    if adapter() == Adapters.Redis, do: configured, else: :pubsub
+  end
+
+  @doc """
+  Subscribe the calling process to channels (e.g. `decisions:new`).
+
+  Messages arrive as `{:redix_pubsub, pid, ref, :message, %{channel: ..., payload: ...}}`
+  whatever the adapter.
+  """
+  @spec subscribe(String.t() | [String.t()]) :: {:ok, reference()} | {:error, term()}
+  def subscribe(channels) do
+    adapter().subscribe(List.wrap(channels), self())
+  end
+
+  @doc """
+  Decode a payload received on `channel`.
+
+  Envelopes on `messages:*` and `decisions:*` channels and
+  `agents:heartbeat` must carry a fresh, valid signature from their
+  `from` role (when signing is enabled); others are rejected and logged,
+  so handlers never act on a forged sender or a replayed message. Must be
+  called by the receiving process, which the replay check is scoped to.
+
+  Missed broadcasts replayed by `subscribe_to_role/1` are read from the
+  database by this node and arrive as `{:stored, message}` instead of
+  JSON; they're returned as they are.
+
+  ## Returns
+
+  - `{:ok, message}` - Decoded (and verified) message
+  - `{:error, {:rejected, reason}}` - Unsigned, forged, stale or replayed message
+  - `{:error, %Jason.DecodeError{}}` - Not JSON
+  """
+  @spec decode_message(String.t(), String.t() | {:stored, map()}) :: {:ok, map()} | {:error, term()}
+  def decode_message(_channel, {:stored, message}) when is_map(message), do: {:ok, message}
+
+  def decode_message(channel, payload) do
+    with {:ok, message} <- Jason.decode(payload),
+         :ok <- verify_message(channel, message) do
+      {:ok, message}
     end
   end
 
   @doc """
   Subscribe to messages for a specific role.
+
+  With the `:streams` transport this starts a `StreamConsumer` linked to
+  the caller; messages arrive in the same `{:redix_pubsub, ...}` shape.
+
+  Broadcasts the role missed (no processed receipt, see
+  `fetch_unread_broadcasts/1`) are then replayed to the caller in that
+  same shape on the `messages:all` channel, so handlers catch up without
+  extra code.
   """
-  @spec subscribe_to_role(role()) :: {:ok, pid()} | {:error, term()}
+  @spec subscribe_to_role(role()) :: {:ok, pid() | reference()} | {:error, term()}
   def subscribe_to_role(role) do
-    channels = [
-      "messages:#{role}",
-      "messages:all"
-    ]
-
-    # Add leadership channel for executive roles
-    channels =
-      if role in [:ceo, :cto, :chro, :operations_head] do
-        ["messages:leadership" | channels]
+    result =
+      if transport() == :streams do
+        StreamConsumer.start_link(role: role, subscriber: self())
       else
-        channels
+        subscribe_to_role_channels(role)
       end
 
-    Redix.PubSub.subscribe(:redix_pubsub, channels, self())
+    with {:ok, _} <- result do
+      replay_missed_broadcasts(role, self())
+    end
+
+    result
   end
 
   @doc """
-  Publish a decision event.
+  Publish a decision event, signed as `from`.
+
+  ## Parameters
+
+  - `from` - Role publishing the event (the acting agent, or a system
+    role such as `:decision_scheduler` or `:human`)
+  - `event_type` - `:new`, `:vote_required`, `:completed` or `:escalated`
+  - `decision_data` - Event fields (decision ID, status, ...)
   """
-  @spec publish_decision_event(atom(), map()) :: {:ok, integer()} | {:error, term()}
-  def publish_decision_event(event_type, decision_data)
+  @spec publish_decision_event(role(), atom(), map()) :: {:ok, integer()} | {:error, term()}
+  def publish_decision_event(from, event_type, decision_data)
       when event_type in [:new, :vote_required, :completed, :escalated] do
     channel = "decisions:#{event_type}"
 
     event = Map.merge(decision_data, %{
       event: to_string(event_type),
+      from: to_string(from),
       timestamp: DateTime.utc_now() |> DateTime.to_iso8601()
     })
 
-    case Jason.encode(event) do
+    case Jason.encode(Signing.sign(event)) do
       {:ok, json} ->
-        Redix.command(:redix, ["PUBLISH", channel, json])
+        adapter().publish(channel, json)
 
       {:error, reason} ->
         {:error, {:encode_error, reason}}
@@ -194,21 +499,22 @@ defmodule EchoShared.MessageBus do
   end
 
   @doc """
-  Publish agent heartbeat.
+  Publish agent heartbeat, signed as `role`.
   """
   @spec publish_heartbeat(role(), map()) :: {:ok, integer()} | {:error, term()}
   def publish_heartbeat(role, status_data \\ %{}) do
     heartbeat = %{
       role: to_string(role),
+      from: to_string(role),
       timestamp: DateTime.utc_now() |> DateTime.to_iso8601(),
       status: Map.get(status_data, :status, "running")
     }
 
     channel = "agents:heartbeat"
 
-    case Jason.encode(heartbeat) do
+    case Jason.encode(Signing.sign(heartbeat)) do
       {:ok, json} ->
-        Redix.command(:redix, ["PUBLISH", channel, json])
+        adapter().publish(channel, json)
 
       {:error, reason} ->
         {:error, {:encode_error, reason}}
@@ -217,24 +523,103 @@ defmodule EchoShared.MessageBus do
 
   @doc """
   Store a message in the database for audit trail.
+
+  `metadata` (e.g. a request's correlation ID) is kept so that retried and
+  requeued messages are redelivered with it. An integer `in_reply_to` in
+  it links the message into the parent's thread; `priority`, `ttl` and
+  `expires_at` set the message's priority and expiry.
   """
-  @spec store_message_in_db(role(), role(), message_type(), String.t(), map()) ::
+  @spec store_message_in_db(role(), role(), message_type(), String.t(), map(), map()) ::
           {:ok, EchoShared.Schemas.Message.t()} | {:error, Ecto.Changeset.t()}
-  def store_message_in_db(from, to, type, subject, content) do
-    attrs = %{
-      from_role: to_string(from),
-      to_role: to_string(to),
-      type: type,
-      subject: subject,
-      content: content,
-      metadata: %{timestamp: DateTime.utc_now() |> DateTime.to_iso8601()}
-    }
+  def store_message_in_db(from, to, type, subject, content, metadata \\ %{}) do
+    attrs =
+      %{
+        from_role: to_string(from),
+        to_role: to_string(to),
+        type: type,
+        subject: subject,
+        content: content,
+        metadata: Map.merge(metadata, %{timestamp: DateTime.utc_now() |> DateTime.to_iso8601()})
+      }
+      |> Map.merge(thread_attrs(metadata))
+      |> Map.merge(delivery_attrs(type, metadata))
 
     %EchoShared.Schemas.Message{}
     |> EchoShared.Schemas.Message.changeset(attrs)
     |> EchoShared.Repo.insert()
   end
 
+  @doc """
+  Fetch every message in the thread containing `message_id`, oldest first.
+
+  ## Returns
+
+  - `{:ok, [message]}` - The thread, starting with its first message
+  - `{:error, :not_found}` - No such message
+  """
+  @spec fetch_thread(integer()) :: {:ok, [Message.t()]} | {:error, :not_found}
+  def fetch_thread(message_id) do
+    case Repo.get(Message, message_id) do
+      nil ->
+        {:error, :not_found}
+
+      message ->
+        thread_id = Message.thread_id(message)
+
+        {:ok,
+         Repo.all(
+           from m in Message,
+             where: m.id == ^thread_id or m.thread_id == ^thread_id,
+             order_by: [asc: m.inserted_at, asc: m.id]
+         )}
+    end
+  end
+
+  @doc """
+  Add a message to the thread containing `message_id`.
+
+  The new message replies to `message_id` and, unless `:to` is given, goes
+  to the other party of that message.
+
+  ## Parameters
+
+  - `message_id` - Message being answered or followed up
+  - `from` - Sending role
+  - `content` - Message content (a string is sent as `%{"text" => ...}`)
+  - `opts` - Options:
+    - `:to` - Recipient role (default: the other party of `message_id`)
+    - `:type` - Message type (default: `:response` when answering the
+      sender of `message_id`, otherwise `:request`)
+    - `:subject` - Subject (default: "Re: " plus the thread's subject)
+
+  ## Returns
+
+  - `{:ok, db_id}` - ID of the new message
+  - `{:error, :not_found}` - No such message
+  - `{:error, :forbidden}` - The policy doesn't allow it (see `publish_message/6`)
+  """
+  @spec continue_thread(integer(), atom(), map() | String.t(), keyword()) ::
+          {:ok, integer()} | {:error, term()}
+  def continue_thread(message_id, from, content, opts \\ []) do
+    case Repo.get(Message, message_id) do
+      nil ->
+        {:error, :not_found}
+
+      parent ->
+        from = to_string(from)
+        to = Keyword.get_lazy(opts, :to, fn -> other_party(parent, from) end)
+        subject = Keyword.get_lazy(opts, :subject, fn -> reply_subject(parent.subject) end)
+        content = if is_binary(content), do: %{"text" => content}, else: content
+
+        type =
+          Keyword.get_lazy(opts, :type, fn ->
+            if to_string(to) == parent.from_role, do: :response, else: :request
+          end)
+
+        publish_message(from, to, type, subject, content, %{in_reply_to: parent.id})
+    end
+  end
+
   @doc """
   Fetch unread messages for an agent.
 
@@ -242,74 +627,688 @@ defmodule EchoShared.MessageBus do
   1. On agent startup (to catch up on missed messages)
   2. Periodically as fallback if Redis is down
   3. On reconnect after network partition
+
+  Messages are returned highest priority first, oldest first within a
+  priority. Expired and archived messages are skipped, as are messages
+  waiting out a retry backoff until they're due.
   """
   @spec fetch_unread_messages(role()) :: [EchoShared.Schemas.Message.t()]
   def fetch_unread_messages(role) do
-    import Ecto.Query
+    now = DateTime.utc_now()
 
     EchoShared.Repo.all(
       from m in EchoShared.Schemas.Message,
       where: m.to_role == ^to_string(role) and m.read == false,
-      order_by: [asc: m.inserted_at]
+      where: is_nil(m.next_attempt_at) or m.next_attempt_at <= ^now,
+      where: is_nil(m.archived_at) and (is_nil(m.expires_at) or m.expires_at > ^now),
+      order_by: [desc: m.priority, asc: m.inserted_at, asc: m.id]
     )
   end
 
   @doc """
-  Fetch unread broadcast messages (to_role="all").
+  Fetch broadcast messages (to_role="all") not yet processed by a role.
 
   Fix #5: Allows agents to catch up on missed broadcasts during startup/restart.
   Called on agent initialization to recover broadcasts missed while offline.
+  A broadcast counts as processed once the role has a receipt with
+  `processed_at` (see `mark_message_processed/2`); other roles processing
+  it doesn't hide it. Ordered and filtered like `fetch_unread_messages/1`.
   """
   @spec fetch_unread_broadcasts(role()) :: [EchoShared.Schemas.Message.t()]
-  def fetch_unread_broadcasts(_role) do
-    import Ecto.Query
+  def fetch_unread_broadcasts(role) do
+    now = DateTime.utc_now()
+    role = to_string(role)
 
     EchoShared.Repo.all(
       from m in EchoShared.Schemas.Message,
+      left_join: r in MessageReceipt,
+      on: r.message_id == m.id and r.role == ^role,
       where: m.to_role == "all" and m.read == false,
-      order_by: [asc: m.inserted_at],
+      where: is_nil(m.next_attempt_at) or m.next_attempt_at <= ^now,
+      where: is_nil(m.archived_at) and (is_nil(m.expires_at) or m.expires_at > ^now),
+      where: is_nil(r.processed_at),
+      order_by: [desc: m.priority, asc: m.inserted_at, asc: m.id],
       limit: 50
     )
   end
 
+  @doc """
+  Record that a broadcast was delivered to a role.
+
+  Keeps the first delivery time; a no-op for direct messages.
+  """
+  @spec mark_message_delivered(integer(), role()) :: :ok | {:error, term()}
+  def mark_message_delivered(message_id, role) do
+    now = DateTime.utc_now() |> DateTime.truncate(:second)
+
+    %MessageReceipt{}
+    |> MessageReceipt.changeset(%{message_id: message_id, role: to_string(role), delivered_at: now})
+    |> Repo.insert(on_conflict: :nothing, conflict_target: [:message_id, :role])
+    |> case do
+      {:ok, _} -> :ok
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
   @doc """
   Mark a message as processed.
 
-  Called after an agent successfully handles a message.
+  Called after an agent successfully handles a message. With the
+  `:streams` transport this also acknowledges the stream entry.
+
+  For a broadcast, pass the handling agent's `role`: processing is
+  recorded on that role's receipt and the broadcast stays unread for the
+  others. Without a role the broadcast is marked read for everyone.
   """
-  @spec mark_message_processed(integer()) :: {:ok, EchoShared.Schemas.Message.t()} | {:error, term()}
-  def mark_message_processed(message_id) do
+  @spec mark_message_processed(integer(), role() | nil) ::
+          {:ok, Message.t() | MessageReceipt.t()} | {:error, term()}
+  def mark_message_processed(message_id, role \\ nil) do
     case EchoShared.Repo.get(EchoShared.Schemas.Message, message_id) do
       nil ->
         {:error, :not_found}
 
+      %Message{to_role: "all"} when not is_nil(role) ->
+        now = DateTime.utc_now() |> DateTime.truncate(:second)
+        result = upsert_receipt(message_id, role, %{processed_at: now, processing_error: nil})
+
+        StreamConsumer.ack(message_id)
+        result
+
       message ->
-        message
-        |> EchoShared.Schemas.Message.mark_processed()
-        |> EchoShared.Repo.update()
+        result =
+          message
+          |> EchoShared.Schemas.Message.mark_processed()
+          |> EchoShared.Repo.update()
+
+        StreamConsumer.ack(message_id)
+        result
     end
   end
 
   @doc """
   Mark a message processing as failed.
 
-  Called when an agent fails to handle a message.
+  Called when an agent fails to handle a message. The failure is counted
+  and the message is scheduled for redelivery with exponential backoff;
+  once it has failed `max_attempts/0` times it is moved to the
+  `dead_letters` table instead.
+
+  For a broadcast with a `role`, the error is recorded on that role's
+  receipt instead; the broadcast stays unprocessed for the role and is
+  replayed when it next subscribes.
+
+  ## Returns
+
+  - `{:ok, %Message{}}` - Retry scheduled (see `next_attempt_at`)
+  - `{:ok, %DeadLetter{}}` - Attempts exhausted, message dead-lettered
+  - `{:ok, %MessageReceipt{}}` - Broadcast failure recorded for `role`
+  - `{:error, :not_found}` - No such message
   """
-  @spec mark_message_failed(integer(), term()) :: {:ok, EchoShared.Schemas.Message.t()} | {:error, term()}
-  def mark_message_failed(message_id, error) do
-    case EchoShared.Repo.get(EchoShared.Schemas.Message, message_id) do
+  @spec mark_message_failed(integer(), term(), role() | nil) ::
+          {:ok, Message.t() | DeadLetter.t() | MessageReceipt.t()} | {:error, term()}
+  def mark_message_failed(message_id, error, role \\ nil) do
+    case Repo.get(Message, message_id) do
       nil ->
         {:error, :not_found}
 
+      %Message{to_role: "all"} when not is_nil(role) ->
+        result = upsert_receipt(message_id, role, %{processing_error: inspect(error)})
+
+        StreamConsumer.ack(message_id)
+        result
+
       message ->
-        message
-        |> EchoShared.Schemas.Message.mark_failed(error)
-        |> EchoShared.Repo.update()
+        result =
+          if message.attempts + 1 >= max_attempts() do
+            dead_letter(message, error)
+          else
+            next_attempt_at = DateTime.add(DateTime.utc_now(), retry_delay(message.attempts + 1), :second)
+
+            message
+            |> Message.schedule_retry(error, next_attempt_at)
+            |> Repo.update()
+          end
+
+        # The retry is a fresh delivery; don't leave the stream entry pending
+        StreamConsumer.ack(message_id)
+        result
+    end
+  end
+
+  @doc """
+  Get the number of failed attempts after which a message is dead-lettered.
+  """
+  @spec max_attempts() :: pos_integer()
+  def max_attempts do
+    case System.get_env("MESSAGE_MAX_ATTEMPTS") do
+      nil -> Application.get_env(:echo_shared, :message_max_attempts, @default_max_attempts)
+      value -> String.to_integer(value)
+    end
+  end
+
+  @doc """
+  Get the number of failed publish attempts after which an outbox row is
+  given up on (`OUTBOX_MAX_ATTEMPTS` env var, or
+  `config :echo_shared, :outbox_max_attempts`, default 10).
+  """
+  @spec outbox_max_attempts() :: pos_integer()
+  def outbox_max_attempts do
+    case System.get_env("OUTBOX_MAX_ATTEMPTS") do
+      nil -> Application.get_env(:echo_shared, :outbox_max_attempts, @default_outbox_max_attempts)
+      value -> String.to_integer(value)
+    end
+  end
+
+  @doc """
+  Redeliver failed messages whose retry backoff has elapsed.
+
+  Called periodically by `EchoShared.MessageBus.RetryScheduler`.
+
+  ## Returns
+
+  - `{:ok, count}` - Number of messages redelivered
+  """
+  @spec redeliver_due_messages() :: {:ok, non_neg_integer()}
+  def redeliver_due_messages do
+    now = DateTime.utc_now()
+
+    # With signing, a node can only redeliver as the roles it holds keys for
+    signable =
+      if Signing.enabled?(),
+        do: dynamic([m], m.from_role in ^Signing.signing_roles()),
+        else: dynamic(true)
+
+    # Clearing next_attempt_at claims the messages, so concurrent schedulers
+    # on other nodes don't redeliver them too
+    {count, messages} =
+      Repo.update_all(
+        from(m in Message,
+          where: m.read == false and m.next_attempt_at <= ^now,
+          where: is_nil(m.archived_at) and (is_nil(m.expires_at) or m.expires_at > ^now),
+          where: ^signable,
+          select: m
+        ),
+        set: [next_attempt_at: nil]
+      )
+
+    Enum.each(messages, &redeliver/1)
+    {:ok, count}
+  end
+
+  @doc """
+  Archive unprocessed messages whose `expires_at` has passed.
+
+  Called periodically by `EchoShared.MessageBus.ExpirySweeper`. Archived
+  messages keep `read: false` (they were never handled) but are no longer
+  fetched, retried or relayed; their pending outbox rows are dropped.
+
+  ## Returns
+
+  - `{:ok, count}` - Number of messages archived
+  """
+  @spec archive_expired_messages() :: {:ok, non_neg_integer()}
+  def archive_expired_messages do
+    now = DateTime.utc_now() |> DateTime.truncate(:second)
+
+    {:ok, count} =
+      Repo.transaction(fn ->
+        {count, ids} =
+          Repo.update_all(
+            from(m in Message,
+              where: m.read == false and is_nil(m.archived_at) and m.expires_at <= ^now,
+              select: m.id
+            ),
+            set: [archived_at: now, next_attempt_at: nil]
+          )
+
+        if ids != [] do
+          Repo.delete_all(from o in OutboxEntry, where: o.message_id in ^ids and is_nil(o.sent_at))
+        end
+
+        count
+      end)
+
+    {:ok, count}
+  end
+
+  @doc """
+  List dead-lettered messages, newest first.
+
+  ## Options
+
+  - `:to_role` - Only dead letters addressed to this role
+  - `:limit` - Maximum number returned (default: 50)
+  """
+  @spec list_dead_letters(keyword()) :: [DeadLetter.t()]
+  def list_dead_letters(opts \\ []) do
+    query =
+      from d in DeadLetter,
+        order_by: [desc: d.inserted_at, desc: d.id],
+        limit: ^Keyword.get(opts, :limit, 50)
+
+    query =
+      case Keyword.get(opts, :to_role) do
+        nil -> query
+        role -> where(query, [d], d.to_role == ^to_string(role))
+      end
+
+    Repo.all(query)
+  end
+
+  @doc """
+  Requeue a dead-lettered message.
+
+  The message is stored again with a fresh attempt count, delivered to its
+  recipient, and removed from the dead letters.
+
+  ## Returns
+
+  - `{:ok, message_id}` - ID of the requeued message
+  - `{:error, :not_found}` - No such dead letter
+  """
+  @spec requeue_dead_letter(integer()) :: {:ok, integer()} | {:error, term()}
+  def requeue_dead_letter(dead_letter_id) do
+    result =
+      Repo.transaction(fn ->
+        with %DeadLetter{} = dead_letter <- Repo.get(DeadLetter, dead_letter_id),
+             {:ok, message} <-
+               store_message_in_db(
+                 dead_letter.from_role,
+                 dead_letter.to_role,
+                 dead_letter.type,
+                 dead_letter.subject,
+                 dead_letter.content,
+                 (dead_letter.metadata || %{})
+                 |> Map.delete("timestamp")
+                 |> Map.put("requeued_from", dead_letter.message_id)
+               ),
+             {:ok, _} <- Repo.delete(dead_letter) do
+          message
+        else
+          nil -> Repo.rollback(:not_found)
+          {:error, reason} -> Repo.rollback(reason)
+        end
+      end)
+
+    with {:ok, message} <- result do
+      Logger.info("Requeued dead letter #{dead_letter_id} as message #{message.id} to #{message.to_role}")
+      redeliver(message)
+      {:ok, message.id}
+    end
+  end
+
+  @doc """
+  Permanently discard a dead-lettered message.
+  """
+  @spec discard_dead_letter(integer()) :: {:ok, DeadLetter.t()} | {:error, term()}
+  def discard_dead_letter(dead_letter_id) do
+    case Repo.get(DeadLetter, dead_letter_id) do
+      nil -> {:error, :not_found}
+      dead_letter -> Repo.delete(dead_letter)
     end
   end
 
   ## Private Functions
 
+  # Refused messages are audited but never stored or delivered
+  defp authorize(from, to, type, subject, metadata) do
+    if Policy.allowed?(from, to, type, metadata) do
+      :ok
+    else
+      Logger.warning("Policy refused #{type} from #{from} to #{to}: #{inspect(subject)}")
+
+      %PolicyViolation{}
+      |> PolicyViolation.changeset(%{
+        from_role: to_string(from),
+        to_role: to_string(to),
+        type: to_string(type),
+        subject: subject && String.slice(subject, 0, 255)
+      })
+      |> Repo.insert()
+
+      {:error, :forbidden}
+    end
+  end
+
+  # Message row and outbox row are written together; inside a caller's
+  # transaction this joins it
+  defp enqueue_message(from, to, type, subject, content, metadata) do
+    Repo.transaction(fn ->
+      with {:ok, db_message} <- store(from, to, type, subject, content, metadata),
+           {:ok, json} <- encode(message_payload(db_message, from, to, type, subject, content, metadata)),
+           {:ok, entry} <- insert_outbox_entry(db_message, "messages:#{to}", json) do
+        {db_message, entry}
+      else
+        {:error, reason} -> Repo.rollback(reason)
+      end
+    end)
+  end
+
+  defp store(from, to, type, subject, content, metadata) do
+    case store_message_in_db(from, to, type, subject, content, metadata) do
+      {:ok, db_message} -> {:ok, db_message}
+      {:error, reason} -> {:error, {:db_error, reason}}
+    end
+  end
+
+  # Signed once we know the envelope encodes
+  defp encode(message) do
+    case Jason.encode(message) do
+      {:ok, _json} -> {:ok, message |> Signing.sign() |> Jason.encode!()}
+      {:error, reason} -> {:error, {:encode_error, reason}}
+    end
+  end
+
+  defp insert_outbox_entry(db_message, channel, json) do
+    %OutboxEntry{}
+    |> OutboxEntry.changeset(%{message_id: db_message.id, channel: channel, payload: json})
+    |> Repo.insert()
+    |> case do
+      {:ok, entry} -> {:ok, entry}
+      {:error, reason} -> {:error, {:db_error, reason}}
+    end
+  end
+
+  defp message_payload(db_message, from, to, type, subject, content, metadata) do
+    %{
+      db_id: db_message.id,
+      id: generate_message_id(),
+      thread_id: Message.thread_id(db_message),
+      in_reply_to: db_message.in_reply_to,
+      priority: db_message.priority,
+      expires_at: db_message.expires_at,
+      from: to_string(from),
+      to: to_string(to),
+      type: to_string(type),
+      subject: subject,
+      content: content,
+      metadata: Map.merge(metadata, %{timestamp: DateTime.utc_now() |> DateTime.to_iso8601()})
+    }
+  end
+
+  # Publish a freshly enqueued row right away, unless it isn't committed yet
+  # (caller's transaction) or the relay already claimed it
+  defp publish_outbox_entry(entry) do
+    if Repo.in_transaction?() do
+      :deferred
+    else
+      {:ok, result} =
+        Repo.transaction(fn ->
+          from(o in OutboxEntry,
+            where: o.id == ^entry.id and is_nil(o.sent_at),
+            lock: "FOR UPDATE SKIP LOCKED"
+          )
+          |> Repo.one()
+          |> case do
+            nil -> :deferred
+            locked -> deliver_outbox_entry(locked)
+          end
+        end)
+
+      result
+    end
+  end
+
+  defp deliver_outbox_entry(entry) do
+    case deliver(entry.channel, entry.payload) do
+      {:ok, redis_result} ->
+        Repo.update!(OutboxEntry.mark_sent(entry))
+        {:ok, redis_result}
+
+      {:error, reason} ->
+        if entry.attempts + 1 >= outbox_max_attempts() do
+          Logger.error(
+            "Giving up on outbox entry #{entry.id} to #{entry.channel} after #{entry.attempts + 1} attempts: #{inspect(reason)}"
+          )
+
+          Repo.update!(OutboxEntry.mark_failed(entry, reason))
+        else
+          next_attempt_at = DateTime.add(DateTime.utc_now(), outbox_retry_delay(entry.attempts + 1), :second)
+          Repo.update!(OutboxEntry.mark_attempt_failed(entry, reason, next_attempt_at))
+        end
+
+        {:error, reason}
+    end
+  end
+
+  defp dead_letter(message, error) do
+    message = %{message | attempts: message.attempts + 1, processing_error: inspect(error)}
+
+    Repo.transaction(fn ->
+      with {:ok, dead_letter} <-
+             %DeadLetter{}
+             |> DeadLetter.changeset(DeadLetter.from_message(message))
+             |> Repo.insert(),
+           {:ok, _} <- Repo.delete(message) do
+        Logger.error(
+          "Message #{message.id} to #{message.to_role} dead-lettered after #{message.attempts} attempts: #{message.processing_error}"
+        )
+
+        dead_letter
+      else
+        {:error, reason} -> Repo.rollback(reason)
+      end
+    end)
+  end
+
+  # 10s, 20s, 40s, ... up to @retry_max_seconds
+  defp retry_delay(attempt) do
+    min(@retry_base_seconds * Integer.pow(2, attempt - 1), @retry_max_seconds)
+  end
+
+  # 1s, 2s, 4s, ... up to @outbox_retry_max_seconds
+  defp outbox_retry_delay(attempt) do
+    min(Integer.pow(2, attempt - 1), @outbox_retry_max_seconds)
+  end
+
+  # Publish a stored message to its recipient again, under its original db_id
+  defp redeliver(message) do
+    if Signing.enabled?() and not Signing.can_sign?(message.from_role) do
+      # Left to the retry scheduler of a node holding the sender's key
+      Repo.update_all(from(m in Message, where: m.id == ^message.id), set: [next_attempt_at: DateTime.utc_now()])
+      :ok
+    else
+      payload = stored_message_payload(message, %{"attempt" => message.attempts + 1})
+
+      with {:ok, json} <- Jason.encode(payload),
+           {:ok, _} <- deliver("messages:#{message.to_role}", json) do
+        :ok
+      else
+        {:error, reason} ->
+          # Still unread in the DB; fetch_unread_messages/1 will pick it up
+          Logger.warning("Redelivery of message #{message.id} failed: #{inspect(reason)}")
+      end
+    end
+  end
+
+  # Same shape as the payload publish_message/6 sends
+  defp stored_message_payload(message, extra_metadata) do
+    %{
+      db_id: message.id,
+      id: generate_message_id(),
+      thread_id: Message.thread_id(message),
+      in_reply_to: message.in_reply_to,
+      priority: message.priority,
+      expires_at: message.expires_at,
+      from: message.from_role,
+      to: message.to_role,
+      type: to_string(message.type),
+      subject: message.subject,
+      content: message.content,
+      metadata:
+        (message.metadata || %{})
+        |> Map.merge(extra_metadata)
+        |> Map.put("timestamp", DateTime.utc_now() |> DateTime.to_iso8601())
+    }
+    |> Signing.sign()
+  end
+
+  # Runs outside the caller so a slow or unavailable database doesn't
+  # block agent startup; the caller gets the broadcasts as mailbox messages
+  defp replay_missed_broadcasts(role, subscriber) do
+    Task.start(fn ->
+      try do
+        missed = fetch_unread_broadcasts(role)
+
+        if missed != [] do
+          Logger.info("#{role} catching up on #{length(missed)} missed broadcasts")
+        end
+
+        ref = make_ref()
+
+        Enum.each(missed, fn message ->
+          mark_message_delivered(message.id, role)
+          # Read from the database here, so handed over as is rather than
+          # as JSON that would need the sender's signature
+          payload = {:stored, stored_message_payload(message, %{"replayed" => true})}
+          send(subscriber, {:redix_pubsub, self(), ref, :message, %{channel: "messages:all", payload: payload}})
+        end)
+      rescue
+        error ->
+          Logger.warning("#{role} couldn't fetch missed broadcasts (database may be busy): #{inspect(error)}")
+      end
+    end)
+  end
+
+  defp upsert_receipt(message_id, role, attrs) do
+    now = DateTime.utc_now() |> DateTime.truncate(:second)
+    attrs = Map.merge(%{message_id: message_id, role: to_string(role), delivered_at: now}, attrs)
+
+    %MessageReceipt{}
+    |> MessageReceipt.changeset(attrs)
+    |> Repo.insert(
+      on_conflict: {:replace, (Map.keys(attrs) -- [:message_id, :role, :delivered_at]) ++ [:updated_at]},
+      conflict_target: [:message_id, :role],
+      returning: true
+    )
+  end
+
+  defp subscribe_to_role_channels(role) do
+    channels = [
+      "messages:#{role}",
+      "messages:all"
+    ]
+
+    # Add leadership channel for executive roles
+    channels =
+      if role in [:ceo, :cto, :chro, :operations_head] do
+        ["messages:leadership" | channels]
+      else
+        channels
+      end
+
+    adapter().subscribe(channels, self())
+  end
+
+  defp await_reply(reply_to, timeout) do
+    case adapter().await_reply(reply_to, timeout) do
+      {:ok, json} ->
+        reply = Jason.decode!(json)
+
+        case {Signing.verify(reply), reply} do
+          {{:error, reason}, _reply} -> {:error, {:rejected, reason}}
+          {:ok, %{"status" => "error", "content" => content}} -> {:error, {:remote_error, content["error"]}}
+          {:ok, %{"content" => content}} -> {:ok, content}
+        end
+
+      {:error, reason} ->
+        {:error, reason}
+    end
+  end
+
+  # A reply joins its parent's thread; a missing parent (e.g. dead-lettered)
+  # still anchors the thread by ID
+  defp thread_attrs(metadata) do
+    case metadata[:in_reply_to] || metadata["in_reply_to"] do
+      parent_id when is_integer(parent_id) ->
+        thread_id =
+          case Repo.get(Message, parent_id) do
+            nil -> metadata[:thread_id] || metadata["thread_id"] || parent_id
+            parent -> Message.thread_id(parent)
+          end
+
+        %{in_reply_to: parent_id, thread_id: thread_id}
+
+      _none ->
+        %{}
+    end
+  end
+
+  defp verify_message("messages:" <> _ = channel, message), do: check_signature(channel, message)
+  defp verify_message("decisions:" <> _ = channel, message), do: check_signature(channel, message)
+  defp verify_message("agents:heartbeat" = channel, message), do: check_signature(channel, message)
+  defp verify_message(_channel, _message), do: :ok
+
+  defp check_signature(channel, message) do
+    case Signing.verify(message) do
+      :ok ->
+        :ok
+
+      {:error, reason} ->
+        Logger.error(
+          "Rejected #{reason} message on #{channel} claiming to be from #{inspect(message["from"])}: #{inspect(message["subject"])}"
+        )
+
+        {:error, {:rejected, reason}}
+    end
+  end
+
+  defp delivery_attrs(type, metadata) do
+    %{
+      priority: metadata[:priority] || metadata["priority"] || default_priority(type),
+      expires_at: expires_at(metadata)
+    }
+  end
+
+  defp default_priority(type) when type in [:escalation, "escalation"], do: :urgent
+  defp default_priority(_type), do: :normal
+
+  # An explicit expires_at (DateTime or ISO 8601 string) wins over a ttl
+  defp expires_at(metadata) do
+    case {metadata[:expires_at] || metadata["expires_at"], metadata[:ttl] || metadata["ttl"]} do
+      {nil, ttl} when is_integer(ttl) and ttl > 0 -> DateTime.add(DateTime.utc_now(), ttl, :second)
+      {expires_at, _ttl} -> expires_at
+    end
+  end
+
+  defp other_party(%Message{from_role: from, to_role: to}, from), do: to
+  defp other_party(%Message{from_role: sender}, _from), do: sender
+
+  defp reply_subject("Re: " <> _ = subject), do: subject
+  defp reply_subject(subject), do: "Re: " <> subject
+
+  defp adapter_module(:redis), do: Adapters.Redis
+  defp adapter_module(:postgres), do: Adapters.Postgres
+  defp adapter_module(:local), do: Adapters.Local
+  defp adapter_module(module) when is_atom(module), do: module
+
+  defp reply_content({:error, reason}), do: {"error", %{"error" => inspect(reason)}}
+  defp reply_content(text) when is_binary(text), do: {"ok", %{"text" => text}}
+  defp reply_content(content) when is_map(content), do: {"ok", content}
+
+  defp generate_correlation_id do
+    "corr_" <> (:crypto.strong_rand_bytes(12) |> Base.encode16(case: :lower))
+  end
+
+  defp deliver(channel, json) do
+    case transport() do
+      :streams ->
+        Redix.command(:redix, [
+          "XADD",
+          StreamConsumer.stream_key(channel),
+          "MAXLEN",
+          "~",
+          @stream_max_length,
+          "*",
+          "payload",
+          json
+        ])
+
+      :pubsub ->
+        adapter().publish(channel, json)
+    end
+  end
+
   defp generate_message_id do
     "msg_" <> (:crypto.strong_rand_bytes(8) |> Base.encode16(case: :lower))
   end
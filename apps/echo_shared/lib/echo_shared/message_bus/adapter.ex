@@ -0,0 +1,60 @@
+defmodule EchoShared.MessageBus.Adapter do
+  @moduledoc """
+  Behaviour for `EchoShared.MessageBus` delivery backends.
+
+  Messages are always persisted to PostgreSQL by `MessageBus`; an adapter
+  only carries the real-time notification and request/reply hand-off.
+
+  ## Implementations
+
+  - `EchoShared.MessageBus.Adapters.Redis` (default) - Redis PUBLISH and
+    lists; required for the `:streams` transport
+  - `EchoShared.MessageBus.Adapters.Postgres` - PostgreSQL LISTEN/NOTIFY;
+    multi-node without Redis
+  - `EchoShared.MessageBus.Adapters.Local` - In-process; single-node
+    development and tests
+
+  Select with `MESSAGE_BUS_ADAPTER=redis|postgres|local` or:
+
+      config :echo_shared, :message_bus_adapter, :local
+
+  ## Subscriber Messages
+
+  Whatever the adapter, subscribers receive the `Redix.PubSub` message
+  shapes, so handlers don't depend on the backend:
+
+      {:redix_pubsub, adapter_pid, ref, :subscribed, %{channel: channel}}
+      {:redix_pubsub, adapter_pid, ref, :message, %{channel: channel, payload: payload}}
+  """
+
+  @doc """
+  Processes the adapter needs, started under `EchoShared.Application`.
+  """
+  @callback children() :: [Supervisor.child_spec() | {module(), term()} | module()]
+
+  @doc """
+  Publish a payload on a channel.
+
+  Returns the number of subscribers reached, where the backend knows it.
+  """
+  @callback publish(channel :: String.t(), payload :: String.t()) ::
+              {:ok, non_neg_integer()} | {:error, term()}
+
+  @doc """
+  Subscribe `pid` to channels.
+  """
+  @callback subscribe(channels :: [String.t()], pid()) :: {:ok, reference()} | {:error, term()}
+
+  @doc """
+  Hand a reply to whoever awaits `key`, kept for `ttl_seconds` if no one
+  is waiting yet.
+  """
+  @callback push_reply(key :: String.t(), payload :: String.t(), ttl_seconds :: pos_integer()) ::
+              :ok | {:error, term()}
+
+  @doc """
+  Wait up to `timeout` milliseconds for a reply pushed to `key`.
+  """
+  @callback await_reply(key :: String.t(), timeout()) ::
+              {:ok, String.t()} | {:error, :timeout} | {:error, term()}
+end
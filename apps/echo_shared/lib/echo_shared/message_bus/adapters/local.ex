@@ -0,0 +1,144 @@
+defmodule EchoShared.MessageBus.Adapters.Local do
+  @moduledoc """
+  In-process `MessageBus` adapter.
+
+  Channels and replies live in a single GenServer on the local node, so
+  agents only see each other when they run in the same BEAM. Intended for
+  single-node development and the ExUnit suite, which then need no Redis:
+
+      config :echo_shared, :message_bus_adapter, :local
+  """
+
+  @behaviour EchoShared.MessageBus.Adapter
+
+  use GenServer
+
+  ## Adapter Callbacks
+
+  @impl EchoShared.MessageBus.Adapter
+  def children, do: [__MODULE__]
+
+  @impl EchoShared.MessageBus.Adapter
+  def publish(channel, payload) do
+    GenServer.call(__MODULE__, {:publish, channel, payload})
+  end
+
+  @impl EchoShared.MessageBus.Adapter
+  def subscribe(channels, pid) do
+    GenServer.call(__MODULE__, {:subscribe, List.wrap(channels), pid})
+  end
+
+  @impl EchoShared.MessageBus.Adapter
+  def push_reply(key, payload, ttl_seconds) do
+    GenServer.call(__MODULE__, {:push_reply, key, payload, ttl_seconds})
+  end
+
+  @impl EchoShared.MessageBus.Adapter
+  def await_reply(key, timeout) do
+    GenServer.call(__MODULE__, {:await_reply, key}, timeout)
+  catch
+    :exit, {:timeout, _} ->
+      GenServer.cast(__MODULE__, {:cancel_wait, key, self()})
+      {:error, :timeout}
+  end
+
+  ## Client API
+
+  def start_link(opts \\ []) do
+    GenServer.start_link(__MODULE__, opts, name: __MODULE__)
+  end
+
+  ## Server Callbacks
+
+  @impl GenServer
+  def init(_opts) do
+    # subscribers: channel => [{pid, ref}]; replies: key => [payload];
+    # waiters: key => [from]
+    {:ok, %{subscribers: %{}, monitors: %{}, replies: %{}, waiters: %{}}}
+  end
+
+  @impl GenServer
+  def handle_call({:publish, channel, payload}, _from, state) do
+    subscribers = Map.get(state.subscribers, channel, [])
+
+    Enum.each(subscribers, fn {pid, ref} ->
+      send(pid, {:redix_pubsub, self(), ref, :message, %{channel: channel, payload: payload}})
+    end)
+
+    {:reply, {:ok, length(subscribers)}, state}
+  end
+
+  def handle_call({:subscribe, channels, pid}, _from, state) do
+    ref = make_ref()
+
+    subscribers =
+      Enum.reduce(channels, state.subscribers, fn channel, acc ->
+        send(pid, {:redix_pubsub, self(), ref, :subscribed, %{channel: channel}})
+        Map.update(acc, channel, [{pid, ref}], &[{pid, ref} | &1])
+      end)
+
+    monitors =
+      if Map.has_key?(state.monitors, pid) do
+        state.monitors
+      else
+        Map.put(state.monitors, pid, Process.monitor(pid))
+      end
+
+    {:reply, {:ok, ref}, %{state | subscribers: subscribers, monitors: monitors}}
+  end
+
+  def handle_call({:push_reply, key, payload, ttl_seconds}, _from, state) do
+    state =
+      case Map.get(state.waiters, key, []) do
+        [waiter | rest] ->
+          GenServer.reply(waiter, {:ok, payload})
+          %{state | waiters: put_or_delete(state.waiters, key, rest)}
+
+        [] ->
+          Process.send_after(self(), {:expire_replies, key}, ttl_seconds * 1000)
+          %{state | replies: Map.update(state.replies, key, [payload], &(&1 ++ [payload]))}
+      end
+
+    {:reply, :ok, state}
+  end
+
+  def handle_call({:await_reply, key}, from, state) do
+    case Map.get(state.replies, key, []) do
+      [payload | rest] ->
+        {:reply, {:ok, payload}, %{state | replies: put_or_delete(state.replies, key, rest)}}
+
+      [] ->
+        {:noreply, %{state | waiters: Map.update(state.waiters, key, [from], &(&1 ++ [from]))}}
+    end
+  end
+
+  @impl GenServer
+  def handle_cast({:cancel_wait, key, pid}, state) do
+    remaining = Enum.reject(Map.get(state.waiters, key, []), fn {waiter, _tag} -> waiter == pid end)
+    {:noreply, %{state | waiters: put_or_delete(state.waiters, key, remaining)}}
+  end
+
+  @impl GenServer
+  def handle_info({:expire_replies, key}, state) do
+    {:noreply, %{state | replies: Map.delete(state.replies, key)}}
+  end
+
+  def handle_info({:DOWN, _ref, :process, pid, _reason}, state) do
+    subscribers =
+      state.subscribers
+      |> Enum.map(fn {channel, subs} -> {channel, Enum.reject(subs, fn {sub, _} -> sub == pid end)} end)
+      |> Enum.reject(fn {_channel, subs} -> subs == [] end)
+      |> Map.new()
+
+    {:noreply, %{state | subscribers: subscribers, monitors: Map.delete(state.monitors, pid)}}
+  end
+
+  def handle_info(_msg, state) do
+    {:noreply, state}
+  end
+
+  ## Private Functions
+
+  defp put_or_delete(map, key, []), do: Map.delete(map, key)
+  defp put_or_delete(map, key, list), do: Map.put(map, key, list)
+end
@@ -0,0 +1,236 @@
+defmodule EchoShared.MessageBus.Adapters.Postgres do
+  @moduledoc """
+  PostgreSQL LISTEN/NOTIFY `MessageBus` adapter.
+
+  Lets agents on several nodes talk without Redis, using the database
+  they already share. Publishing runs `pg_notify/2` through
+  `EchoShared.Repo` (inside a transaction the notification is sent on
+  commit); this process holds one `Postgrex.Notifications` connection,
+  LISTENs on the channels its subscribers need and forwards notifications
+  in the `Redix.PubSub` shape.
+
+  Replies are stored in the `message_replies` table and announced with a
+  NOTIFY on the reply key, so a reply sent before the requester waits is
+  not lost.
+
+  NOTIFY payloads are limited to 8000 bytes; larger messages fail to
+  publish and are picked up from the database (`fetch_unread_messages/1`).
+
+      config :echo_shared, :message_bus_adapter, :postgres
+  """
+
+  @behaviour EchoShared.MessageBus.Adapter
+
+  use GenServer
+  require Logger
+
+  alias EchoShared.Repo
+
+  @max_payload_bytes 8000
+
+  ## Adapter Callbacks
+
+  @impl EchoShared.MessageBus.Adapter
+  def children, do: [__MODULE__]
+
+  @impl EchoShared.MessageBus.Adapter
+  def publish(_channel, payload) when byte_size(payload) >= @max_payload_bytes do
+    {:error, :payload_too_large}
+  end
+
+  def publish(channel, payload) do
+    case Repo.query("SELECT pg_notify($1, $2)", [channel, payload]) do
+      # NOTIFY doesn't report listeners; count the notification as sent
+      {:ok, _} -> {:ok, 1}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @impl EchoShared.MessageBus.Adapter
+  def subscribe(channels, pid) do
+    GenServer.call(__MODULE__, {:subscribe, List.wrap(channels), pid})
+  end
+
+  @impl EchoShared.MessageBus.Adapter
+  def push_reply(key, payload, ttl_seconds) do
+    now = DateTime.utc_now() |> DateTime.truncate(:second)
+
+    result =
+      Repo.transaction(fn ->
+        Repo.query!("DELETE FROM message_replies WHERE expires_at < $1", [now])
+
+        Repo.query!(
+          "INSERT INTO message_replies (key, payload, expires_at, inserted_at) VALUES ($1, $2, $3, $4)",
+          [key, payload, DateTime.add(now, ttl_seconds, :second), now]
+        )
+
+        # Delivered on commit, after the row is visible
+        Repo.query!("SELECT pg_notify($1, '')", [key])
+      end)
+
+    case result do
+      {:ok, _} -> :ok
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @impl EchoShared.MessageBus.Adapter
+  def await_reply(key, timeout) do
+    GenServer.call(__MODULE__, {:await_reply, key}, timeout)
+  catch
+    :exit, {:timeout, _} ->
+      GenServer.cast(__MODULE__, {:cancel_wait, key, self()})
+      {:error, :timeout}
+  end
+
+  ## Client API
+
+  def start_link(opts \\ []) do
+    GenServer.start_link(__MODULE__, opts, name: __MODULE__)
+  end
+
+  ## Server Callbacks
+
+  @impl GenServer
+  def init(_opts) do
+    {:ok, conn} = Postgrex.Notifications.start_link(Repo.config())
+
+    # subscribers: channel => [{pid, ref}]; listens: channel => listen ref;
+    # waiters: reply key => [from]
+    {:ok, %{conn: conn, subscribers: %{}, listens: %{}, monitors: %{}, waiters: %{}}}
+  end
+
+  @impl GenServer
+  def handle_call({:subscribe, channels, pid}, _from, state) do
+    ref = make_ref()
+
+    state =
+      Enum.reduce(channels, state, fn channel, acc ->
+        acc = listen(acc, channel)
+        send(pid, {:redix_pubsub, self(), ref, :subscribed, %{channel: channel}})
+        %{acc | subscribers: Map.update(acc.subscribers, channel, [{pid, ref}], &[{pid, ref} | &1])}
+      end)
+
+    monitors =
+      if Map.has_key?(state.monitors, pid) do
+        state.monitors
+      else
+        Map.put(state.monitors, pid, Process.monitor(pid))
+      end
+
+    {:reply, {:ok, ref}, %{state | monitors: monitors}}
+  end
+
+  def handle_call({:await_reply, key}, from, state) do
+    # LISTEN before checking the table so a reply landing in between isn't missed
+    state = listen(state, key)
+
+    case take_reply(key) do
+      {:ok, payload} ->
+        {:reply, {:ok, payload}, maybe_unlisten(state, key)}
+
+      :none ->
+        {:noreply, %{state | waiters: Map.update(state.waiters, key, [from], &(&1 ++ [from]))}}
+    end
+  end
+
+  @impl GenServer
+  def handle_cast({:cancel_wait, key, pid}, state) do
+    remaining = Enum.reject(Map.get(state.waiters, key, []), fn {waiter, _tag} -> waiter == pid end)
+    state = %{state | waiters: put_or_delete(state.waiters, key, remaining)}
+    {:noreply, maybe_unlisten(state, key)}
+  end
+
+  @impl GenServer
+  def handle_info({:notification, _conn, _listen_ref, channel, payload}, state) do
+    Enum.each(Map.get(state.subscribers, channel, []), fn {pid, ref} ->
+      send(pid, {:redix_pubsub, self(), ref, :message, %{channel: channel, payload: payload}})
+    end)
+
+    state =
+      case Map.get(state.waiters, channel, []) do
+        [waiter | rest] ->
+          case take_reply(channel) do
+            {:ok, reply} ->
+              GenServer.reply(waiter, {:ok, reply})
+              %{state | waiters: put_or_delete(state.waiters, channel, rest)}
+
+            :none ->
+              state
+          end
+
+        [] ->
+          state
+      end
+
+    {:noreply, maybe_unlisten(state, channel)}
+  end
+
+  def handle_info({:DOWN, _ref, :process, pid, _reason}, state) do
+    subscribers =
+      state.subscribers
+      |> Enum.map(fn {channel, subs} -> {channel, Enum.reject(subs, fn {sub, _} -> sub == pid end)} end)
+      |> Map.new()
+
+    state = %{state | subscribers: subscribers, monitors: Map.delete(state.monitors, pid)}
+    {:noreply, Enum.reduce(Map.keys(subscribers), state, &maybe_unlisten(&2, &1))}
+  end
+
+  def handle_info(_msg, state) do
+    {:noreply, state}
+  end
+
+  ## Private Functions
+
+  defp listen(state, channel) do
+    if Map.has_key?(state.listens, channel) do
+      state
+    else
+      {:ok, listen_ref} = Postgrex.Notifications.listen(state.conn, channel)
+      %{state | listens: Map.put(state.listens, channel, listen_ref)}
+    end
+  end
+
+  # Stop listening once no subscriber or reply waiter needs the channel
+  defp maybe_unlisten(state, channel) do
+    in_use? =
+      Map.get(state.subscribers, channel, []) != [] or Map.get(state.waiters, channel, []) != []
+
+    case {in_use?, Map.fetch(state.listens, channel)} do
+      {false, {:ok, listen_ref}} ->
+        Postgrex.Notifications.unlisten(state.conn, listen_ref)
+
+        %{
+          state
+          | listens: Map.delete(state.listens, channel),
+            subscribers: Map.delete(state.subscribers, channel)
+        }
+
+      _ ->
+        state
+    end
+  end
+
+  defp take_reply(key) do
+    now = DateTime.utc_now() |> DateTime.truncate(:second)
+
+    query = """
+    DELETE FROM message_replies
+    WHERE id = (
+      SELECT id FROM message_replies
+      WHERE key = $1 AND expires_at >= $2
+      ORDER BY id LIMIT 1
+      FOR UPDATE SKIP LOCKED
+    )
+    RETURNING payload
+    """
+
+    case Repo.query!(query, [key, now]) do
+      %{rows: [[payload]]} -> {:ok, payload}
+      %{rows: []} -> :none
+    end
+  end
+
+  defp put_or_delete(map, key, []), do: Map.delete(map, key)
+  defp put_or_delete(map, key, list), do: Map.put(map, key, list)
+end
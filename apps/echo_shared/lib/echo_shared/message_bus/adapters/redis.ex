@@ -0,0 +1,71 @@
+defmodule EchoShared.MessageBus.Adapters.Redis do
+  @moduledoc """
+  Redis `MessageBus` adapter (default).
+
+  Publishes with PUBLISH on the shared `:redix` connection and subscribes
+  through `Redix.PubSub` (`:redix_pubsub`). Replies are pushed to a Redis
+  list and awaited with BLPOP, so a reply sent before the requester starts
+  waiting is not lost.
+
+  Connects to `REDIS_HOST` (default: localhost) and `REDIS_PORT`
+  (default: 6383).
+  """
+
+  @behaviour EchoShared.MessageBus.Adapter
+
+  @impl true
+  def children do
+    [
+      # Redis connection pool (for commands)
+      {Redix, name: :redix, host: redis_host(), port: redis_port()},
+
+      # Shared pub/sub connection for all subscribers on this node
+      %{
+        id: Redix.PubSub,
+        start: {Redix.PubSub, :start_link, [[name: :redix_pubsub, host: redis_host(), port: redis_port()]]}
+      }
+    ]
+  end
+
+  @impl true
+  def publish(channel, payload) do
+    Redix.command(:redix, ["PUBLISH", channel, payload])
+  end
+
+  @impl true
+  def subscribe(channels, pid) do
+    Redix.PubSub.subscribe(:redix_pubsub, channels, pid)
+  end
+
+  @impl true
+  def push_reply(key, payload, ttl_seconds) do
+    case Redix.pipeline(:redix, [["RPUSH", key, payload], ["EXPIRE", key, ttl_seconds]]) do
+      {:ok, _} -> :ok
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  # BLPOP blocks its connection, so wait on a dedicated one
+  @impl true
+  def await_reply(key, timeout) do
+    {:ok, conn} = Redix.start_link(host: redis_host(), port: redis_port())
+
+    # BLPOP takes seconds; 0 would block forever
+    seconds = max(div(timeout + 999, 1000), 1)
+
+    result =
+      case Redix.command(conn, ["BLPOP", key, seconds], timeout: timeout + 5_000) do
+        {:ok, [_key, payload]} -> {:ok, payload}
+        {:ok, nil} -> {:error, :timeout}
+        {:error, reason} -> {:error, reason}
+      end
+
+    Redix.stop(conn)
+    result
+  end
+
+  ## Private Functions
+
+  defp redis_host, do: System.get_env("REDIS_HOST", "localhost")
+  defp redis_port, do: String.to_integer(System.get_env("REDIS_PORT", "6383"))
+end
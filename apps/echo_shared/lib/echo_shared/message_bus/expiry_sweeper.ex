@@ -0,0 +1,60 @@
+defmodule EchoShared.MessageBus.ExpirySweeper do
+  @moduledoc """
+  Archives messages whose `expires_at` passed before they were processed.
+
+  Every 30 seconds this process calls
+  `MessageBus.archive_expired_messages/0`, so stale requests are never
+  picked up by `MessageBus.fetch_unread_messages/1`, retried or relayed
+  after an agent restarts.
+
+  Every agent node runs a sweeper; archiving is a single UPDATE, so
+  concurrent sweeps don't conflict.
+  """
+
+  use GenServer
+  require Logger
+
+  alias EchoShared.MessageBus
+
+  @sweep_interval 30_000 # 30 seconds
+
+  ## Client API
+
+  def start_link(opts \\ []) do
+    GenServer.start_link(__MODULE__, opts, name: __MODULE__)
+  end
+
+  ## Server Callbacks
+
+  @impl true
+  def init(_opts) do
+    schedule_sweep()
+    {:ok, %{}}
+  end
+
+  @impl true
+  def handle_info(:sweep, state) do
+    try do
+      case MessageBus.archive_expired_messages() do
+        {:ok, 0} -> :ok
+        {:ok, count} -> Logger.info("Archived #{count} expired message(s)")
+      end
+    rescue
+      error ->
+        Logger.debug("Expiry sweeper couldn't query database (may be busy during startup): #{inspect(error)}")
+    end
+
+    schedule_sweep()
+    {:noreply, state}
+  end
+
+  def handle_info(_msg, state) do
+    {:noreply, state}
+  end
+
+  ## Private Functions
+
+  defp schedule_sweep do
+    Process.send_after(self(), :sweep, @sweep_interval)
+  end
+end
@@ -0,0 +1,66 @@
+defmodule EchoShared.MessageBus.NonceCache do
+  @moduledoc """
+  Nonces of recently verified message envelopes.
+
+  `EchoShared.MessageBus.Signing.verify/1` records each envelope's nonce
+  under the receiving process and rejects a nonce that process has already
+  seen, so a captured envelope can't be replayed while its signature is
+  still fresh. Envelopes past `Signing.max_age_seconds/0` are rejected as
+  stale anyway, so every minute this process drops entries older than
+  that.
+  """
+
+  use GenServer
+
+  alias EchoShared.MessageBus.Signing
+
+  @table __MODULE__
+  @sweep_interval 60_000 # 1 minute
+
+  ## Client API
+
+  def start_link(opts \\ []) do
+    GenServer.start_link(__MODULE__, opts, name: __MODULE__)
+  end
+
+  @doc """
+  Record `nonce` as seen by `receiver`.
+
+  ## Returns
+
+  - `:ok` - First time `receiver` sees the nonce
+  - `{:error, :replayed}` - Seen before
+  """
+  @spec record(pid(), String.t(), integer()) :: :ok | {:error, :replayed}
+  def record(receiver, nonce, signed_at) do
+    if :ets.insert_new(@table, {{receiver, nonce}, signed_at}), do: :ok, else: {:error, :replayed}
+  end
+
+  ## Server Callbacks
+
+  @impl true
+  def init(_opts) do
+    :ets.new(@table, [:named_table, :public, :set, write_concurrency: true])
+    schedule_sweep()
+    {:ok, %{}}
+  end
+
+  @impl true
+  def handle_info(:sweep, state) do
+    cutoff = System.system_time(:second) - 2 * Signing.max_age_seconds()
+    :ets.select_delete(@table, [{{:_, :"$1"}, [{:<, :"$1", cutoff}], [true]}])
+
+    schedule_sweep()
+    {:noreply, state}
+  end
+
+  def handle_info(_msg, state) do
+    {:noreply, state}
+  end
+
+  ## Private Functions
+
+  defp schedule_sweep do
+    Process.send_after(self(), :sweep, @sweep_interval)
+  end
+end
@@ -0,0 +1,65 @@
+defmodule EchoShared.MessageBus.OutboxRelay do
+  @moduledoc """
+  Publishes pending message outbox rows through the MessageBus adapter.
+
+  `MessageBus.publish_message/6` and `broadcast_message/5` write the
+  message and its outbox row in one transaction. Outside a caller's
+  transaction they publish right away; inside one, or when the bus was
+  unavailable, the row stays pending until this relay calls
+  `MessageBus.relay_outbox/1`.
+
+  Every agent node runs a relay; rows are claimed with
+  `FOR UPDATE SKIP LOCKED`, so each is published by one of them.
+
+  Rows that fail to publish are retried with exponential backoff and given
+  up on after `MessageBus.outbox_max_attempts/0` attempts.
+  """
+
+  use GenServer
+  require Logger
+
+  alias EchoShared.MessageBus
+
+  @relay_interval 1_000 # 1 second
+
+  ## Client API
+
+  def start_link(opts \\ []) do
+    GenServer.start_link(__MODULE__, opts, name: __MODULE__)
+  end
+
+  ## Server Callbacks
+
+  @impl true
+  def init(_opts) do
+    schedule_relay()
+    {:ok, %{}}
+  end
+
+  @impl true
+  def handle_info(:relay, state) do
+    try do
+      case MessageBus.relay_outbox() do
+        {:ok, 0} -> :ok
+        {:ok, count} -> Logger.debug("Relayed #{count} outbox message(s)")
+        {:error, reason} -> Logger.warning("Outbox relay failed: #{inspect(reason)}")
+      end
+    rescue
+      error ->
+        Logger.debug("Outbox relay couldn't query database (may be busy during startup): #{inspect(error)}")
+    end
+
+    schedule_relay()
+    {:noreply, state}
+  end
+
+  def handle_info(_msg, state) do
+    {:noreply, state}
+  end
+
+  ## Private Functions
+
+  defp schedule_relay do
+    Process.send_after(self(), :relay, @relay_interval)
+  end
+end
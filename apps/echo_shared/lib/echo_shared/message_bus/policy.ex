@@ -0,0 +1,155 @@
+defmodule EchoShared.MessageBus.Policy do
+  @moduledoc """
+  Declarative policy of which roles may message which.
+
+  `EchoShared.MessageBus.publish_message/6` and `broadcast_message/5`
+  check every message against the policy; a message is allowed when any
+  rule matches, otherwise it is refused with `{:error, :forbidden}` and
+  recorded in `policy_violations`.
+
+  ## Rules
+
+  A rule is `{senders, recipients, types}` or
+  `{senders, recipients, types, :reply}`:
+
+  - `senders` - A role, a list of roles or a group
+  - `recipients` - A role, a list of roles or a group; the shared
+    `:leadership` and `:all` channels are recipients too
+  - `types` - A list of message types, or `:any`
+  - `:reply` - Only matches a message whose `in_reply_to` (message id) or
+    `correlation_id` metadata points at a stored message the recipient
+    sent to the sender (directly or to `:all`)
+
+  Groups:
+
+  - `:c_suite` - CEO, CTO, CHRO and Head of Operations
+  - `:agents` - The nine agent roles
+  - `:system` - The orchestrators: `:workflow_engine`, `:flow_engine`,
+    `:delegator`, `:decision_scheduler` and `:human`
+  - `:direct` - Any single role (not `:leadership` or `:all`)
+  - `:any` - Anyone, including the shared channels
+
+  Override the default rules with `config :echo_shared, :message_policy,
+  [rule, ...]`, or `:allow_all` to disable enforcement.
+  """
+
+  import Ecto.Query
+
+  alias EchoShared.Repo
+  alias EchoShared.Schemas.Message
+
+  @c_suite [:ceo, :cto, :chro, :operations_head]
+  @agents @c_suite ++ [:product_manager, :senior_architect, :uiux_engineer, :senior_developer, :test_lead]
+  @system [:workflow_engine, :flow_engine, :delegator, :decision_scheduler, :human]
+  @channels [:leadership, :all]
+
+  @default_rules [
+    # C-suite may send anything anywhere, including the leadership channel
+    {:c_suite, :any, :any},
+    # Orchestrators (workflow engine, delegator) route work between agents
+    {:system, :any, :any},
+    # Requests may always be answered, by the role they were sent to
+    {:any, :direct, [:response], :reply},
+    # Anyone reports and escalates up to the C-suite and announces to all
+    {:agents, :c_suite, [:notification, :escalation]},
+    {:agents, :all, [:notification]},
+    # ...and up their reporting line (see EchoShared.Decisions.Escalation)
+    {[:senior_developer, :test_lead], :senior_architect, [:escalation]},
+    {:uiux_engineer, :product_manager, [:escalation]},
+    # Individual contributors work with their collaborators
+    {:product_manager, [:senior_architect, :uiux_engineer, :senior_developer], [:request, :notification]},
+    {:senior_architect, [:product_manager, :uiux_engineer, :senior_developer, :test_lead], [:request, :notification]},
+    {:uiux_engineer, [:product_manager, :senior_developer], [:request, :notification]},
+    {:senior_developer, [:senior_architect, :test_lead], [:request, :notification]},
+    {:test_lead, [:senior_architect, :senior_developer], [:request, :notification]}
+  ]
+
+  @doc """
+  Check whether `from` may send a message of `type` with `metadata` to `to`.
+
+  Roles and types may be atoms or strings. `metadata` is only looked at by
+  `:reply` rules.
+  """
+  @spec allowed?(atom() | String.t(), atom() | String.t(), atom() | String.t(), map()) :: boolean()
+  def allowed?(from, to, type, metadata \\ %{}) do
+    case rules() do
+      :allow_all ->
+        true
+
+      rules ->
+        from = normalize(from)
+        to = normalize(to)
+        type = normalize(type)
+
+        Enum.any?(rules, fn
+          {senders, recipients, types} ->
+            matches?(senders, recipients, types, from, to, type)
+
+          {senders, recipients, types, :reply} ->
+            matches?(senders, recipients, types, from, to, type) and reply?(from, to, metadata)
+        end)
+    end
+  end
+
+  @doc """
+  The rules in force (configured, or the defaults).
+  """
+  @spec rules() :: [tuple()] | :allow_all
+  def rules, do: Application.get_env(:echo_shared, :message_policy, @default_rules)
+
+  ## Private Functions
+
+  defp matches?(senders, recipients, types, from, to, type) do
+    sender?(senders, from) and recipient?(recipients, to) and (types == :any or type in types)
+  end
+
+  defp sender?(:any, _from), do: true
+  defp sender?(:c_suite, from), do: from in @c_suite
+  defp sender?(:agents, from), do: from in @agents
+  defp sender?(:system, from), do: from in @system
+  defp sender?(senders, from), do: from in List.wrap(senders)
+
+  defp recipient?(:any, _to), do: true
+  defp recipient?(:direct, to), do: to not in @channels
+  defp recipient?(:c_suite, to), do: to in @c_suite
+  defp recipient?(:agents, to), do: to in @agents
+  defp recipient?(recipients, to), do: to in List.wrap(recipients)
+
+  # The message answers one `to` sent to `from`, or broadcast to everyone
+  defp reply?(from, to, metadata) do
+    case reply_query(metadata) do
+      nil ->
+        false
+
+      query ->
+        query
+        |> where([m], m.from_role == ^to_string(to) and m.to_role in ^[to_string(from), "all"])
+        |> Repo.exists?()
+    end
+  end
+
+  defp reply_query(metadata) do
+    in_reply_to = metadata[:in_reply_to] || metadata["in_reply_to"]
+    correlation_id = metadata[:correlation_id] || metadata["correlation_id"]
+
+    cond do
+      is_integer(in_reply_to) ->
+        from(m in Message, where: m.id == ^in_reply_to)
+
+      is_binary(correlation_id) ->
+        from(m in Message, where: fragment("?->>'correlation_id' = ?", m.metadata, ^correlation_id))
+
+      true ->
+        nil
+    end
+  end
+
+  # Unknown role strings stay strings, so they only match :any/:direct
+  defp normalize(value) when is_atom(value), do: value
+
+  defp normalize(value) when is_binary(value) do
+    String.to_existing_atom(value)
+  rescue
+    ArgumentError -> value
+  end
+end
@@ -0,0 +1,60 @@
+defmodule EchoShared.MessageBus.RetryScheduler do
+  @moduledoc """
+  Redelivers failed messages once their retry backoff has elapsed.
+
+  `MessageBus.mark_message_failed/2` leaves a failed message unread with a
+  `next_attempt_at` (exponential backoff). Every few seconds this process
+  calls `MessageBus.redeliver_due_messages/0`, which claims due messages
+  and publishes them to their recipient again.
+
+  Every agent node runs a scheduler; claiming clears `next_attempt_at` in
+  a single UPDATE, so each retry is redelivered by exactly one of them.
+  """
+
+  use GenServer
+  require Logger
+
+  alias EchoShared.MessageBus
+
+  @check_interval 5_000 # 5 seconds
+
+  ## Client API
+
+  def start_link(opts \\ []) do
+    GenServer.start_link(__MODULE__, opts, name: __MODULE__)
+  end
+
+  ## Server Callbacks
+
+  @impl true
+  def init(_opts) do
+    schedule_check()
+    {:ok, %{}}
+  end
+
+  @impl true
+  def handle_info(:check, state) do
+    try do
+      case MessageBus.redeliver_due_messages() do
+        {:ok, 0} -> :ok
+        {:ok, count} -> Logger.info("Redelivered #{count} failed message(s)")
+      end
+    rescue
+      error ->
+        Logger.debug("Retry scheduler couldn't query database (may be busy during startup): #{inspect(error)}")
+    end
+
+    schedule_check()
+    {:noreply, state}
+  end
+
+  def handle_info(_msg, state) do
+    {:noreply, state}
+  end
+
+  ## Private Functions
+
+  defp schedule_check do
+    Process.send_after(self(), :check, @check_interval)
+  end
+end
@@ -0,0 +1,219 @@
+defmodule EchoShared.MessageBus.Signing do
+  @moduledoc """
+  Ed25519 signatures on message envelopes.
+
+  `EchoShared.MessageBus` signs every agent message and request reply it
+  builds with the private key of the envelope's `from` role, and receivers
+  check the signature against that role's public key with
+  `EchoShared.MessageBus.decode_message/2`. Each node only holds the
+  private keys of the roles it sends as, so neither a client that can
+  PUBLISH to Redis nor a compromised agent can send messages as another
+  role.
+
+  ## Keys
+
+  Keys are Base64-encoded raw Ed25519 keys (see `generate_keypair/0`).
+  Every node verifying messages needs the public keys of all senders:
+
+      MESSAGE_SIGNING_PUBLIC_KEY_CEO=...
+
+      config :echo_shared, :message_signing_public_keys, %{ceo: "...", cto: "..."}
+
+  and the private keys of the roles it runs (its agent, plus e.g.
+  `decision_scheduler` and `human` for the system components it hosts):
+
+      MESSAGE_SIGNING_PRIVATE_KEY_CTO=...
+
+      config :echo_shared, :message_signing_private_keys, %{cto: "..."}
+
+  Signing is enabled when `:message_signing_public_keys` is configured;
+  env keys only override those. When signing is disabled, envelopes go out
+  unsigned and are accepted unverified.
+
+  ## Signature
+
+  The signature covers the whole envelope except the `signature` field,
+  encoded as JSON with map keys sorted, so it survives the JSON round trip
+  to the receiver. It includes a `signed_at` Unix timestamp and a random
+  `nonce`: envelopes older than `max_age_seconds/0` are rejected as stale,
+  and a receiving process rejects a nonce it has already seen
+  (`EchoShared.MessageBus.NonceCache`), so captured envelopes can't be
+  replayed. Outbox rows relayed after that window are rejected too; their
+  recipients still pick them up with `MessageBus.fetch_unread_messages/1`.
+  """
+
+  alias EchoShared.MessageBus.NonceCache
+
+  @default_max_age_seconds 300
+
+  @doc """
+  Whether message signing is configured.
+  """
+  @spec enabled?() :: boolean()
+  def enabled? do
+    Application.get_env(:echo_shared, :message_signing_public_keys, %{}) != %{}
+  end
+
+  @doc """
+  Whether this node holds the private key of `role`.
+  """
+  @spec can_sign?(atom() | String.t()) :: boolean()
+  def can_sign?(role), do: private_key(to_string(role)) != nil
+
+  @doc """
+  Roles this node holds private keys for.
+  """
+  @spec signing_roles() :: [String.t()]
+  def signing_roles do
+    configured =
+      :echo_shared
+      |> Application.get_env(:message_signing_private_keys, %{})
+      |> Map.keys()
+      |> Enum.map(&to_string/1)
+
+    from_env =
+      for {"MESSAGE_SIGNING_PRIVATE_KEY_" <> role, _key} <- System.get_env(), do: String.downcase(role)
+
+    Enum.uniq(configured ++ from_env)
+  end
+
+  @doc """
+  Get the maximum age of a signature in seconds (default: 300).
+
+  Also the tolerated clock skew between nodes.
+  """
+  @spec max_age_seconds() :: pos_integer()
+  def max_age_seconds do
+    case System.get_env("MESSAGE_SIGNATURE_MAX_AGE_SECONDS") do
+      nil -> Application.get_env(:echo_shared, :message_signature_max_age_seconds, @default_max_age_seconds)
+      seconds -> String.to_integer(seconds)
+    end
+  end
+
+  @doc """
+  Generate a Base64-encoded Ed25519 key pair for a role.
+
+      mix run -e 'IO.inspect(EchoShared.MessageBus.Signing.generate_keypair())'
+  """
+  @spec generate_keypair() :: %{public_key: String.t(), private_key: String.t()}
+  def generate_keypair do
+    {public_key, private_key} = :crypto.generate_key(:eddsa, :ed25519)
+    %{public_key: Base.encode64(public_key), private_key: Base.encode64(private_key)}
+  end
+
+  @doc """
+  Sign an envelope with its `from` role's private key.
+
+  Returns the envelope with string keys (as the receiver decodes it) plus
+  `"signed_at"`, `"nonce"` and `"signature"`; unchanged apart from the keys
+  when this node has no key for the role. Re-signing replaces all three.
+  """
+  @spec sign(map()) :: map()
+  def sign(envelope) do
+    envelope = envelope |> Jason.encode!() |> Jason.decode!()
+
+    case private_key(envelope["from"]) do
+      nil ->
+        envelope
+
+      key ->
+        envelope =
+          Map.merge(envelope, %{
+            "signed_at" => System.system_time(:second),
+            "nonce" => :crypto.strong_rand_bytes(16) |> Base.encode16(case: :lower)
+          })
+
+        Map.put(envelope, "signature", signature(envelope, key))
+    end
+  end
+
+  @doc """
+  Verify a decoded envelope received by the calling process.
+
+  ## Returns
+
+  - `:ok` - Valid signature, or signing is disabled
+  - `{:error, :unsigned}` - No signature
+  - `{:error, :invalid_signature}` - Forged, tampered, signed with another
+    role's key, or from a role without a public key
+  - `{:error, :stale}` - Signed longer than `max_age_seconds/0` ago (or in
+    the future)
+  - `{:error, :replayed}` - This process already accepted the envelope
+  """
+  @spec verify(map()) :: :ok | {:error, :unsigned | :invalid_signature | :stale | :replayed}
+  def verify(envelope) do
+    cond do
+      not enabled?() ->
+        :ok
+
+      not is_binary(envelope["signature"]) ->
+        {:error, :unsigned}
+
+      true ->
+        with :ok <- check_signature(envelope),
+             :ok <- check_fresh(envelope) do
+          NonceCache.record(self(), envelope["nonce"], envelope["signed_at"])
+        end
+    end
+  end
+
+  ## Private Functions
+
+  defp check_signature(envelope) do
+    with key when not is_nil(key) <- public_key(envelope["from"]),
+         {:ok, signature} <- Base.decode64(envelope["signature"]),
+         true <- :crypto.verify(:eddsa, :none, payload(envelope), signature, [key, :ed25519]) do
+      :ok
+    else
+      _ -> {:error, :invalid_signature}
+    end
+  end
+
+  defp check_fresh(%{"signed_at" => signed_at, "nonce" => nonce})
+       when is_integer(signed_at) and is_binary(nonce) do
+    if abs(System.system_time(:second) - signed_at) <= max_age_seconds() do
+      :ok
+    else
+      {:error, :stale}
+    end
+  end
+
+  defp check_fresh(_envelope), do: {:error, :stale}
+
+  defp signature(envelope, key) do
+    :crypto.sign(:eddsa, :none, payload(envelope), [key, :ed25519]) |> Base.encode64()
+  end
+
+  defp payload(envelope) do
+    envelope |> Map.delete("signature") |> canonical() |> Jason.encode!()
+  end
+
+  # Maps become key-sorted pair lists so the encoding doesn't depend on map order
+  defp canonical(map) when is_map(map) do
+    map
+    |> Enum.sort_by(fn {key, _value} -> key end)
+    |> Enum.map(fn {key, value} -> [key, canonical(value)] end)
+  end
+
+  defp canonical(list) when is_list(list), do: Enum.map(list, &canonical/1)
+  defp canonical(value), do: value
+
+  defp public_key(role), do: key(role, "MESSAGE_SIGNING_PUBLIC_KEY_", :message_signing_public_keys)
+
+  defp private_key(role), do: key(role, "MESSAGE_SIGNING_PRIVATE_KEY_", :message_signing_private_keys)
+
+  defp key(role, env_prefix, config_key) when is_binary(role) do
+    configured = Application.get_env(:echo_shared, config_key, %{})
+
+    encoded =
+      System.get_env(env_prefix <> String.upcase(role)) ||
+        Enum.find_value(configured, fn {name, key} -> to_string(name) == role && key end)
+
+    case encoded && Base.decode64(encoded) do
+      {:ok, key} -> key
+      _ -> nil
+    end
+  end
+
+  defp key(_role, _env_prefix, _config_key), do: nil
+end
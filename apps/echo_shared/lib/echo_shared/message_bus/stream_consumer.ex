@@ -0,0 +1,227 @@
+defmodule EchoShared.MessageBus.StreamConsumer do
+  @moduledoc """
+  Redis Streams consumer for an agent role (at-least-once delivery).
+
+  Used by `EchoShared.MessageBus` when the `:streams` transport is enabled.
+  Each role reads its own stream (`stream:messages:{role}`) and the
+  broadcast stream (`stream:messages:all`) through a consumer group named
+  after the role, so every role sees every broadcast and entries published
+  while an agent is down are delivered when it comes back.
+
+  Entries are delivered to the subscriber in the same shape as
+  `Redix.PubSub` messages, so existing message handlers work unchanged:
+
+      {:redix_pubsub, consumer_pid, ref, :message, %{channel: "messages:ceo", payload: json}}
+
+  An entry stays pending until the subscriber calls
+  `MessageBus.mark_message_processed/1` (or `mark_message_failed/2`) with
+  its `db_id`, which XACKs it. Entries left pending for over a minute
+  (crashed consumer, handler that never acks) are reclaimed and
+  redelivered, up to 5 deliveries before being dropped.
+  """
+
+  use GenServer
+  require Logger
+
+  @table __MODULE__
+  @batch_size 10
+  @block_ms 5_000
+  @reclaim_interval_ms 30_000
+  @min_idle_ms 60_000
+  @max_deliveries 5
+
+  ## Client API
+
+  @doc """
+  Start a consumer for a role.
+
+  ## Options
+
+  - `:role` - (Required) Agent role whose streams to consume
+  - `:subscriber` - (Required) Process receiving the messages
+  """
+  def start_link(opts) do
+    GenServer.start_link(__MODULE__, opts)
+  end
+
+  @doc """
+  Stream key for a pub/sub channel name.
+  """
+  @spec stream_key(String.t()) :: String.t()
+  def stream_key(channel), do: "stream:" <> channel
+
+  @doc """
+  Acknowledge the stream entry carrying a database message ID.
+
+  No-op when the message wasn't delivered through this node's consumer.
+  """
+  @spec ack(integer()) :: :ok
+  def ack(db_id) do
+    with ref when ref != :undefined <- :ets.whereis(@table),
+         [{^db_id, key, group, entry_id}] <- :ets.take(@table, db_id) do
+      case Redix.command(:redix, ["XACK", key, group, entry_id]) do
+        {:ok, _} -> :ok
+        {:error, reason} -> Logger.warning("XACK #{key} #{entry_id} failed: #{inspect(reason)}")
+      end
+    end
+
+    :ok
+  end
+
+  ## Server Callbacks
+
+  @impl true
+  def init(opts) do
+    role = Keyword.fetch!(opts, :role)
+    subscriber = Keyword.fetch!(opts, :subscriber)
+
+    # Blocking reads need their own connection so they never stall :redix
+    {:ok, conn} = Redix.start_link(host: redis_host(), port: redis_port())
+
+    if :ets.whereis(@table) == :undefined do
+      :ets.new(@table, [:named_table, :public, :set])
+    end
+
+    group = to_string(role)
+    keys = Enum.map(channels_for(role), &stream_key/1)
+
+    Enum.each(keys, &create_group(conn, &1, group))
+
+    state = %{
+      conn: conn,
+      role: role,
+      group: group,
+      # Stable per host so a restarted agent picks up its own pending entries
+      consumer: "#{role}@#{hostname()}",
+      keys: keys,
+      subscriber: subscriber,
+      ref: make_ref()
+    }
+
+    Logger.info("#{role} consuming Redis streams #{Enum.join(keys, ", ")}")
+
+    # Redeliver anything this consumer had pending before a restart
+    send(self(), {:read, "0"})
+    Process.send_after(self(), :reclaim, @reclaim_interval_ms)
+
+    {:ok, state}
+  end
+
+  @impl true
+  def handle_info({:read, from_id}, state) do
+    ids = Enum.map(state.keys, fn _ -> from_id end)
+
+    # "0" replays this consumer's whole pending list once; ">" reads new entries
+    command =
+      ["XREADGROUP", "GROUP", state.group, state.consumer] ++
+        if(from_id == ">", do: ["COUNT", @batch_size, "BLOCK", @block_ms], else: []) ++
+        ["STREAMS" | state.keys] ++ ids
+
+    case Redix.command(state.conn, command, timeout: @block_ms + 2_000) do
+      {:ok, nil} ->
+        send(self(), {:read, ">"})
+
+      {:ok, streams} ->
+        Enum.each(streams, fn [key, entries] -> Enum.each(entries, &deliver(state, key, &1)) end)
+        send(self(), {:read, ">"})
+
+      {:error, reason} ->
+        Logger.warning("XREADGROUP failed: #{inspect(reason)}, retrying")
+        Process.send_after(self(), {:read, from_id}, 1_000)
+    end
+
+    {:noreply, state}
+  end
+
+  def handle_info(:reclaim, state) do
+    Enum.each(state.keys, &reclaim(state, &1))
+    Process.send_after(self(), :reclaim, @reclaim_interval_ms)
+    {:noreply, state}
+  end
+
+  def handle_info(_msg, state) do
+    {:noreply, state}
+  end
+
+  ## Private Functions
+
+  defp create_group(conn, key, group) do
+    case Redix.command(conn, ["XGROUP", "CREATE", key, group, "$", "MKSTREAM"]) do
+      {:ok, _} -> :ok
+      {:error, %Redix.Error{message: "BUSYGROUP" <> _}} -> :ok
+      {:error, reason} -> Logger.error("Failed to create consumer group #{group} on #{key}: #{inspect(reason)}")
+    end
+  end
+
+  # Claim entries idle in other consumers' (or our own) pending lists
+  defp reclaim(state, key) do
+    case Redix.command(state.conn, ["XPENDING", key, state.group, "-", "+", 100]) do
+      {:ok, pending} ->
+        stale = Enum.filter(pending, fn [_id, _consumer, idle, _count] -> idle >= @min_idle_ms end)
+
+        {exhausted, retry} = Enum.split_with(stale, fn [_id, _consumer, _idle, count] -> count >= @max_deliveries end)
+
+        for [id, _consumer, _idle, count] <- exhausted do
+          Logger.error("Dropping #{key} entry #{id} after #{count} deliveries")
+          Redix.command(state.conn, ["XACK", key, state.group, id])
+        end
+
+        if retry != [] do
+          ids = Enum.map(retry, &hd/1)
+
+          case Redix.command(state.conn, ["XCLAIM", key, state.group, state.consumer, @min_idle_ms | ids]) do
+            {:ok, entries} ->
+              Logger.info("Reclaimed #{length(entries)} pending entries on #{key}")
+              Enum.each(entries, &deliver(state, key, &1))
+
+            {:error, reason} ->
+              Logger.warning("XCLAIM on #{key} failed: #{inspect(reason)}")
+          end
+        end
+
+      {:error, reason} ->
+        Logger.warning("XPENDING on #{key} failed: #{inspect(reason)}")
+    end
+  end
+
+  # XCLAIM returns nil for entries trimmed from the stream
+  defp deliver(_state, _key, nil), do: :ok
+
+  defp deliver(state, key, [entry_id, fields]) do
+    payload = fields |> Enum.chunk_every(2) |> Map.new(fn [k, v] -> {k, v} end) |> Map.get("payload")
+    "stream:" <> channel = key
+
+    case payload && Jason.decode(payload) do
+      {:ok, %{"db_id" => db_id}} when not is_nil(db_id) ->
+        :ets.insert(@table, {db_id, key, state.group, entry_id})
+
+      _no_db_id ->
+        # Nothing to correlate an ack with; treat delivery as processing
+        Redix.command(state.conn, ["XACK", key, state.group, entry_id])
+    end
+
+    if payload do
+      send(state.subscriber, {:redix_pubsub, self(), state.ref, :message, %{channel: channel, payload: payload}})
+    end
+
+    :ok
+  end
+
+  defp channels_for(role) do
+    channels = ["messages:#{role}", "messages:all"]
+
+    if role in [:ceo, :cto, :chro, :operations_head] do
+      ["messages:leadership" | channels]
+    else
+      channels
+    end
+  end
+
+  defp hostname do
+    {:ok, name} = :inet.gethostname()
+    to_string(name)
+  end
+
+  defp redis_host, do: System.get_env("REDIS_HOST", "localhost")
+  defp redis_port, do: String.to_integer(System.get_env("REDIS_PORT", "6383"))
+end
@@ -0,0 +1,61 @@
+defmodule EchoShared.Schemas.DeadLetter do
+  @moduledoc """
+  Ecto schema for dead-lettered inter-agent messages.
+
+  A message lands here when its recipient has failed to process it
+  `MessageBus.max_attempts/0` times. The original message row is removed;
+  this copy keeps its content and the last error so it can be inspected,
+  requeued (`MessageBus.requeue_dead_letter/1`) or discarded.
+  """
+
+  use Ecto.Schema
+  import Ecto.Changeset
+
+  @primary_key {:id, :id, autogenerate: true}
+  @derive {Jason.Encoder, only: [:id, :message_id, :from_role, :to_role, :type, :subject,
+                                  :content, :metadata, :attempts, :last_error,
+                                  :message_inserted_at, :inserted_at]}
+
+  schema "dead_letters" do
+    field :message_id, :integer
+    field :from_role, :string
+    field :to_role, :string
+    field :type, Ecto.Enum, values: [:request, :response, :notification, :escalation]
+    field :subject, :string
+    field :content, :map
+    field :metadata, :map
+    field :attempts, :integer
+    field :last_error, :string
+    field :message_inserted_at, :utc_datetime
+
+    timestamps(type: :utc_datetime, updated_at: false)
+  end
+
+  @doc """
+  Changeset for dead-lettering a message.
+  """
+  def changeset(dead_letter, attrs) do
+    dead_letter
+    |> cast(attrs, [:message_id, :from_role, :to_role, :type, :subject, :content, :metadata,
+                    :attempts, :last_error, :message_inserted_at])
+    |> validate_required([:message_id, :from_role, :to_role, :type, :subject, :content, :attempts])
+  end
+
+  @doc """
+  Build dead-letter attributes from a failed message.
+  """
+  def from_message(message) do
+    %{
+      message_id: message.id,
+      from_role: message.from_role,
+      to_role: message.to_role,
+      type: message.type,
+      subject: message.subject,
+      content: message.content,
+      metadata: message.metadata,
+      attempts: message.attempts,
+      last_error: message.processing_error,
+      message_inserted_at: message.inserted_at
+    }
+  end
+end
@@ -7,6 +7,29 @@ defmodule EchoShared.Schemas.Decision do
   - Collaborative decisions requiring consensus
   - Hierarchical decisions that were escalated
   - Human-in-the-loop decisions requiring approval
+
+  ## Deadlines
+
+  A pending decision with a `deadline` is handled by
+  `EchoShared.Decisions.DeadlineScheduler`: participants who haven't voted
+  are reminded shortly before it, and once it passes the decision is
+  settled according to `timeout_policy`:
+
+  - `:close` - Close the vote and let the decision type's rule decide;
+    decisions that aren't put to a vote are escalated
+  - `:escalate` - Escalate the decision
+  - `:approve` / `:reject` - Default to that outcome
+
+  If escalating fails (e.g. nobody to escalate to, or the policy forbids
+  it), the error is recorded in `escalation_error` and the decision stays
+  pending without being retried; clear it to have the scheduler try again.
+
+  ## Escalation
+
+  `EchoShared.Decisions.Escalation` routes a decision up the reporting
+  chain. `escalated_to` is the role that now holds it and
+  `escalation_hops` the history of every hop (`"from"`, `"to"`,
+  `"reason"`, `"authority"`, `"at"`).
   """
 
   use Ecto.Schema
@@ -27,6 +50,13 @@ defmodule EchoShared.Schemas.Decision do
 
     field :consensus_score, :float
     field :outcome, :map
+    field :deadline, :utc_datetime
+    field :timeout_policy, Ecto.Enum, values: [:close, :escalate, :approve, :reject], default: :close
+    field :reminder_sent_at, :utc_datetime
+    field :escalation_error, :string
+    field :metadata, :map
+    field :escalated_to, :string
+    field :escalation_hops, {:array, :map}, default: []
 
     timestamps(type: :utc_datetime)
     field :completed_at, :utc_datetime
@@ -46,7 +76,13 @@ defmodule EchoShared.Schemas.Decision do
       :status,
       :consensus_score,
       :outcome,
-      :completed_at
+      :completed_at,
+      :deadline,
+      :timeout_policy,
+      :escalation_error,
+      :metadata,
+      :escalated_to,
+      :escalation_hops
     ])
     |> validate_required([:decision_type, :initiator_role, :mode, :context])
     |> validate_inclusion(:mode, [:autonomous, :collaborative, :hierarchical, :human])
@@ -0,0 +1,51 @@
+defmodule EchoShared.Schemas.DecisionRule do
+  @moduledoc """
+  Ecto schema for per-decision-type voting rules.
+
+  Rows here override the `:decision_rules` app config for their
+  `decision_type` (see `EchoShared.Decisions.Rules`).
+  """
+
+  use Ecto.Schema
+  import Ecto.Changeset
+
+  @primary_key {:id, :id, autogenerate: true}
+
+  schema "decision_rules" do
+    field :decision_type, :string
+    field :method, Ecto.Enum, values: [:majority, :supermajority, :unanimous, :weighted], default: :majority
+    field :threshold, :float
+    field :quorum, :float, default: 0.5
+    field :role_weights, :map, default: %{}
+    field :vetoes, {:array, :string}, default: []
+    field :abstentions, Ecto.Enum, values: [:exclude, :count_as_reject], default: :exclude
+    field :no_shows, Ecto.Enum, values: [:exclude, :count_as_abstain, :count_as_reject], default: :exclude
+    field :on_no_quorum, Ecto.Enum, values: [:reject, :escalate], default: :reject
+    field :weigh_confidence, :boolean, default: true
+
+    timestamps(type: :utc_datetime)
+  end
+
+  @doc """
+  Changeset for creating or updating a rule.
+  """
+  def changeset(rule, attrs) do
+    rule
+    |> cast(attrs, [
+      :decision_type,
+      :method,
+      :threshold,
+      :quorum,
+      :role_weights,
+      :vetoes,
+      :abstentions,
+      :no_shows,
+      :on_no_quorum,
+      :weigh_confidence
+    ])
+    |> validate_required([:decision_type, :method])
+    |> validate_number(:threshold, greater_than_or_equal_to: 0.0, less_than_or_equal_to: 1.0)
+    |> validate_number(:quorum, greater_than_or_equal_to: 0.0, less_than_or_equal_to: 1.0)
+    |> unique_constraint(:decision_type)
+  end
+end
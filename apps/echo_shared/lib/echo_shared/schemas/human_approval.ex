@@ -0,0 +1,61 @@
+defmodule EchoShared.Schemas.HumanApproval do
+  @moduledoc """
+  Ecto schema for the human approval inbox.
+
+  One item per decision waiting on a human (escalated to `"human"`, or
+  paused workflows and flows). See `EchoShared.Decisions.HumanApprovals`.
+  A paused workflow or flow is linked by `workflow_execution_id` or
+  `flow_execution_id` and resumed once the item is resolved
+  (`resumed_at`), unless it can't be (`resume_error`).
+  """
+
+  use Ecto.Schema
+  import Ecto.Changeset
+
+  @primary_key {:id, :id, autogenerate: true}
+  @foreign_key_type :binary_id
+
+  schema "human_approvals" do
+    belongs_to :decision, EchoShared.Schemas.Decision
+    field :status, Ecto.Enum, values: [:pending, :approved, :rejected], default: :pending
+    field :requested_by, :string
+    field :reason, :string
+    field :urgency, :string
+    field :workflow_execution_id, :string
+    field :flow_execution_id, :string
+    field :comments, {:array, :map}, default: []
+    field :resolved_by, :string
+    field :resolution_note, :string
+    field :resolved_at, :utc_datetime
+    field :resumed_at, :utc_datetime
+    field :resume_error, :string
+
+    timestamps(type: :utc_datetime)
+  end
+
+  @doc """
+  Changeset for requesting or resolving an approval.
+  """
+  def changeset(approval, attrs) do
+    approval
+    |> cast(attrs, [
+      :decision_id,
+      :status,
+      :requested_by,
+      :reason,
+      :urgency,
+      :workflow_execution_id,
+      :flow_execution_id,
+      :comments,
+      :resolved_by,
+      :resolution_note,
+      :resolved_at,
+      :resumed_at,
+      :resume_error
+    ])
+    |> validate_required([:decision_id, :requested_by])
+    |> validate_inclusion(:status, [:pending, :approved, :rejected])
+    |> validate_inclusion(:urgency, ["low", "medium", "high", "critical"])
+    |> unique_constraint(:decision_id, name: :human_approvals_decision_id_index)
+  end
+end
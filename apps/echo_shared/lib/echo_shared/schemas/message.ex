@@ -7,6 +7,20 @@ defmodule EchoShared.Schemas.Message do
   - Responses to previous requests
   - Notifications and updates
   - Escalations to higher authority
+
+  ## Threads
+
+  A message that answers or follows up another carries `in_reply_to`
+  (the parent's ID) and `thread_id` (the ID of the thread's first
+  message). A message starting a thread has neither; its own ID is the
+  thread ID.
+
+  ## Priority and Expiry
+
+  `priority` is `:low`, `:normal`, `:high` or `:urgent` (stored as 0-3 so
+  unread messages sort highest first). A message with `expires_at` is no
+  longer delivered after that time; the sweeper sets `archived_at` on
+  expired messages that were never processed.
   """
 
   use Ecto.Schema
@@ -14,6 +28,8 @@ defmodule EchoShared.Schemas.Message do
 
   @primary_key {:id, :id, autogenerate: true}
 
+  @priorities [low: 0, normal: 1, high: 2, urgent: 3]
+
   schema "messages" do
     field :from_role, :string
     field :to_role, :string
@@ -24,6 +40,13 @@ defmodule EchoShared.Schemas.Message do
     field :read, :boolean, default: false
     field :processed_at, :utc_datetime
     field :processing_error, :string
+    field :attempts, :integer, default: 0
+    field :next_attempt_at, :utc_datetime
+    field :thread_id, :integer
+    field :in_reply_to, :integer
+    field :priority, Ecto.Enum, values: @priorities, default: :normal
+    field :expires_at, :utc_datetime
+    field :archived_at, :utc_datetime
 
     timestamps(type: :utc_datetime, updated_at: false)
   end
@@ -33,12 +56,30 @@ defmodule EchoShared.Schemas.Message do
   """
   def changeset(message, attrs) do
     message
-    |> cast(attrs, [:from_role, :to_role, :type, :subject, :content, :metadata, :read, :processed_at, :processing_error])
+    |> cast(attrs, [:from_role, :to_role, :type, :subject, :content, :metadata, :read, :processed_at, :processing_error, :thread_id, :in_reply_to, :priority, :expires_at])
     |> validate_required([:from_role, :to_role, :type, :subject, :content])
     |> validate_inclusion(:type, [:request, :response, :notification, :escalation])
     |> validate_length(:subject, max: 255)
   end
 
+  @doc """
+  Priority levels, lowest first.
+  """
+  def priorities, do: Keyword.keys(@priorities)
+
+  @doc """
+  Whether the message has expired at `now`.
+  """
+  def expired?(message, now \\ DateTime.utc_now())
+  def expired?(%{expires_at: nil}, _now), do: false
+  def expired?(%{expires_at: expires_at}, now), do: DateTime.compare(expires_at, now) != :gt
+
+  @doc """
+  ID of the thread the message belongs to.
+  """
+  def thread_id(%{thread_id: nil, id: id}), do: id
+  def thread_id(%{thread_id: thread_id}), do: thread_id
+
   @doc """
   Mark message as read/processed.
   """
@@ -59,4 +100,18 @@ defmodule EchoShared.Schemas.Message do
       processing_error: inspect(error)
     })
   end
+
+  @doc """
+  Record a failed attempt and schedule redelivery at `next_attempt_at`.
+
+  The message stays unread so it is redelivered (and picked up by
+  `MessageBus.fetch_unread_messages/1`) once the backoff has elapsed.
+  """
+  def schedule_retry(message, error, next_attempt_at) do
+    change(message, %{
+      attempts: message.attempts + 1,
+      next_attempt_at: DateTime.truncate(next_attempt_at, :second),
+      processing_error: inspect(error)
+    })
+  end
 end
@@ -0,0 +1,36 @@
+defmodule EchoShared.Schemas.MessageReceipt do
+  @moduledoc """
+  Ecto schema for per-recipient receipts of broadcast messages.
+
+  A broadcast is stored once (`to_role = "all"`), so its own `read` flag
+  can't say which agents have seen it. Each recipient role gets a receipt
+  recording when the broadcast was delivered to it and when (or whether)
+  it was processed.
+  """
+
+  use Ecto.Schema
+  import Ecto.Changeset
+
+  @primary_key {:id, :id, autogenerate: true}
+
+  schema "message_receipts" do
+    belongs_to :message, EchoShared.Schemas.Message
+    field :role, :string
+    field :delivered_at, :utc_datetime
+    field :processed_at, :utc_datetime
+    field :processing_error, :string
+
+    timestamps(type: :utc_datetime)
+  end
+
+  @doc """
+  Changeset for recording a receipt.
+  """
+  def changeset(receipt, attrs) do
+    receipt
+    |> cast(attrs, [:message_id, :role, :delivered_at, :processed_at, :processing_error])
+    |> validate_required([:message_id, :role])
+    |> foreign_key_constraint(:message_id)
+    |> unique_constraint([:message_id, :role])
+  end
+end
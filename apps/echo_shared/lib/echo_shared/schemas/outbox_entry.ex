@@ -0,0 +1,71 @@
+defmodule EchoShared.Schemas.OutboxEntry do
+  @moduledoc """
+  Ecto schema for the message outbox.
+
+  `MessageBus` writes an outbox row in the same transaction as the message
+  it announces. `EchoShared.MessageBus.OutboxRelay` publishes rows with no
+  `sent_at` and marks them sent, so a message is never committed without
+  eventually being delivered.
+
+  A failed publish is retried at `next_attempt_at`, with exponential
+  backoff. After `MessageBus.outbox_max_attempts/0` failures the row gets
+  `failed_at` and the relay stops trying; the message itself stays unread
+  for `MessageBus.fetch_unread_messages/1`.
+  """
+
+  use Ecto.Schema
+  import Ecto.Changeset
+
+  @primary_key {:id, :id, autogenerate: true}
+
+  schema "message_outbox" do
+    belongs_to :message, EchoShared.Schemas.Message
+    field :channel, :string
+    field :payload, :string
+    field :attempts, :integer, default: 0
+    field :last_error, :string
+    field :next_attempt_at, :utc_datetime
+    field :sent_at, :utc_datetime
+    field :failed_at, :utc_datetime
+
+    timestamps(type: :utc_datetime, updated_at: false)
+  end
+
+  @doc """
+  Changeset for enqueuing a payload.
+  """
+  def changeset(entry, attrs) do
+    entry
+    |> cast(attrs, [:message_id, :channel, :payload])
+    |> validate_required([:channel, :payload])
+  end
+
+  @doc """
+  Mark the entry as published.
+  """
+  def mark_sent(entry) do
+    change(entry, %{sent_at: DateTime.utc_now() |> DateTime.truncate(:second)})
+  end
+
+  @doc """
+  Record a failed publish attempt; the relay tries again at `next_attempt_at`.
+  """
+  def mark_attempt_failed(entry, error, next_attempt_at) do
+    change(entry, %{
+      attempts: entry.attempts + 1,
+      last_error: inspect(error),
+      next_attempt_at: DateTime.truncate(next_attempt_at, :second)
+    })
+  end
+
+  @doc """
+  Record the last failed publish attempt; the relay gives up on the entry.
+  """
+  def mark_failed(entry, error) do
+    change(entry, %{
+      attempts: entry.attempts + 1,
+      last_error: inspect(error),
+      failed_at: DateTime.utc_now() |> DateTime.truncate(:second)
+    })
+  end
+end
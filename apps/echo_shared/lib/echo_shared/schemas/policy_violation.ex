@@ -0,0 +1,33 @@
+defmodule EchoShared.Schemas.PolicyViolation do
+  @moduledoc """
+  Ecto schema for messages refused by the communication policy.
+
+  Audit record of a role trying to message a recipient it isn't allowed
+  to (see `EchoShared.MessageBus.Policy`). The message itself is not
+  stored or delivered.
+  """
+
+  use Ecto.Schema
+  import Ecto.Changeset
+
+  @primary_key {:id, :id, autogenerate: true}
+
+  schema "policy_violations" do
+    field :from_role, :string
+    field :to_role, :string
+    field :type, :string
+    field :subject, :string
+
+    timestamps(type: :utc_datetime, updated_at: false)
+  end
+
+  @doc """
+  Changeset for recording a violation.
+  """
+  def changeset(violation, attrs) do
+    violation
+    |> cast(attrs, [:from_role, :to_role, :type, :subject])
+    |> validate_required([:from_role, :to_role, :type])
+    |> validate_length(:subject, max: 255)
+  end
+end
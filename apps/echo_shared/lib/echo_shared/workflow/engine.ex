@@ -8,11 +8,24 @@ defmodule EchoShared.Workflow.Engine do
   - Conditional branching
   - Human-in-the-loop pauses
   - Timeouts and error handling
+
+  `:request` steps use `MessageBus.request/5` and wait for the agent's
+  reply; the response is stored in the execution context under
+  `:responses` (keyed by agent).
+
+  `{:pause, reason}` steps open an item in the human approval inbox
+  (`EchoShared.Decisions.HumanApprovals`). Once it is approved or
+  rejected, the remaining steps run with the resolution in the context
+  under `:human_approval`. Only the engine that started a workflow can
+  resume it, since the steps live in its memory; it checks for resolved
+  approvals every 10 seconds. A workflow paused when its engine restarted
+  can't be resumed: it is marked failed instead.
   """
 
   use GenServer
   require Logger
 
+  alias EchoShared.Decisions.HumanApprovals
   alias EchoShared.Workflow.Execution
   alias EchoShared.Schemas.WorkflowExecution
   alias EchoShared.MessageBus
@@ -20,6 +33,11 @@ defmodule EchoShared.Workflow.Engine do
 
   import Ecto.Query
 
+  # How long a :request step waits for the agent's reply
+  @request_timeout 60_000
+
+  @approval_check_interval 10_000 # 10 seconds
+
   ## Client API
 
   def start_link(opts \\ []) do
@@ -42,6 +60,17 @@ defmodule EchoShared.Workflow.Engine do
 
   @doc """
   Resume a paused workflow (human approval).
+
+  `approval_data` is merged into the context and the steps after the
+  pause run.
+
+  ## Returns
+
+  - `:ok` - Resumed
+  - `{:error, :not_found}` - No such execution
+  - `{:error, :not_paused}` - The execution isn't paused
+  - `{:error, :definition_unavailable}` - Started before this engine
+    (re)started, so its steps are unknown; the execution is marked failed
   """
   def resume_workflow(execution_id, approval_data) do
     GenServer.call(__MODULE__, {:resume, execution_id, approval_data})
@@ -58,7 +87,9 @@ defmodule EchoShared.Workflow.Engine do
 
     Logger.info("Recovered #{map_size(in_flight_executions)} in-flight workflows")
 
-    {:ok, %{executions: in_flight_executions}}
+    schedule_approval_check()
+
+    {:ok, %{executions: in_flight_executions, definitions: %{}}}
   end
 
   @impl true
@@ -80,8 +111,11 @@ defmodule EchoShared.Workflow.Engine do
     # Persist to database
     case persist_execution(execution) do
       {:ok, _} ->
-        # Store execution in memory
-        new_state = put_in(state, [:executions, execution_id], execution)
+        # Store execution (and its steps, for resuming) in memory
+        new_state =
+          state
+          |> put_in([:executions, execution_id], execution)
+          |> put_in([:definitions, execution_id], workflow_def)
 
         # Start executing steps asynchronously
         Task.start(fn -> execute_steps(workflow_def, execution) end)
@@ -108,16 +142,64 @@ defmodule EchoShared.Workflow.Engine do
       nil ->
         {:reply, {:error, :not_found}, state}
 
-      execution ->
-        updated_execution = %{execution |
-          status: :running,
-          context: Map.merge(execution.context, approval_data)
-        }
-        new_state = put_in(state, [:executions, execution_id], updated_execution)
-        {:reply, :ok, new_state}
+      %Execution{status: :paused} = execution ->
+        case Map.get(state.definitions, execution_id) do
+          nil ->
+            Logger.error("Workflow #{execution_id} can't resume: its steps were lost when the engine restarted")
+
+            failed_execution = Execution.fail(execution, :definition_unavailable)
+            persist_execution(failed_execution)
+
+            new_state = put_in(state, [:executions, execution_id], failed_execution)
+            {:reply, {:error, :definition_unavailable}, new_state}
+
+          workflow_def ->
+            # Continue with the step after the pause
+            resumed_execution = %{execution |
+              status: :running,
+              pause_reason: nil,
+              context: Map.merge(execution.context, approval_data),
+              current_step: execution.current_step + 1
+            }
+
+            persist_execution(resumed_execution)
+
+            remaining_def = %{workflow_def | steps: Enum.drop(workflow_def.steps, resumed_execution.current_step)}
+            Task.start(fn -> execute_steps(remaining_def, resumed_execution) end)
+
+            Logger.info("Resuming workflow #{execution.workflow_name} (#{execution_id})")
+            new_state = put_in(state, [:executions, execution_id], resumed_execution)
+            {:reply, :ok, new_state}
+        end
+
+      _execution ->
+        {:reply, {:error, :not_paused}, state}
     end
   end
 
+  @impl true
+  def handle_info(:check_approvals, state) do
+    # Off the engine process: resuming calls back into it
+    Task.start(fn ->
+      try do
+        case HumanApprovals.resume_approved_workflows() do
+          {:ok, 0} -> :ok
+          {:ok, count} -> Logger.info("Resumed #{count} workflow(s) after human approval")
+        end
+      rescue
+        error ->
+          Logger.debug("Workflow engine couldn't check approvals (database may be busy during startup): #{inspect(error)}")
+      end
+    end)
+
+    schedule_approval_check()
+    {:noreply, state}
+  end
+
+  def handle_info(_msg, state) do
+    {:noreply, state}
+  end
+
   ## Private Functions
 
   defp execute_steps(workflow_def, execution) do
@@ -139,7 +221,7 @@ defmodule EchoShared.Workflow.Engine do
 
       {:pause, paused_exec} ->
         Logger.info("Workflow #{workflow_def.name} paused for human input")
-        update_execution_status(paused_exec.id, :paused)
+        GenServer.cast(__MODULE__, {:paused, paused_exec})
 
       {:error, reason} ->
         Logger.error("Workflow #{workflow_def.name} failed: #{inspect(reason)}")
@@ -164,17 +246,23 @@ defmodule EchoShared.Workflow.Engine do
         {:ok, execution}
 
       {:request, agent, request_type, data} ->
-        # Send request to agent
-        MessageBus.publish_message(
-          :workflow_engine,
-          agent,
-          :request,
-          request_type,
-          Map.merge(data, %{execution_id: execution.id})
-        )
-        # Give agent time to process (temporary: should wait for actual response)
-        Process.sleep(2000)  # 2 second delay per agent request
-        {:ok, execution}
+        # Send request to agent and wait for its reply
+        case MessageBus.request(
+               :workflow_engine,
+               agent,
+               request_type,
+               Map.merge(data, %{execution_id: execution.id}),
+               timeout: @request_timeout
+             ) do
+          {:ok, response} ->
+            # Later steps (e.g. :conditional) can branch on the agent's answer
+            responses = Map.get(execution.context, :responses, %{})
+            context = Map.put(execution.context, :responses, Map.put(responses, agent, response))
+            {:ok, %{execution | context: context}}
+
+          {:error, reason} ->
+            {:error, {:request_failed, agent, request_type, reason}}
+        end
 
       {:pause, reason} ->
         # Pause for human approval
@@ -239,13 +327,42 @@ defmodule EchoShared.Workflow.Engine do
         # Persist status change to database
         persist_execution(updated_execution)
 
-        new_state = put_in(state, [:executions, execution_id], updated_execution)
+        # Finished workflows are never resumed, so their steps can go
+        definitions =
+          if status in [:completed, :failed],
+            do: Map.delete(state.definitions, execution_id),
+            else: state.definitions
+
+        new_state = %{state | executions: Map.put(state.executions, execution_id, updated_execution), definitions: definitions}
         {:noreply, new_state}
     end
   end
 
+  # The approval is only opened once the engine knows the execution is
+  # paused, so resolving it can't race the pause
+  def handle_cast({:paused, execution}, state) do
+    persist_execution(execution)
+
+    case HumanApprovals.request_for_execution(:workflow, execution.id, %{
+           reason: execution.pause_reason,
+           context: %{workflow_name: execution.workflow_name}
+         }) do
+      {:ok, approval} ->
+        Logger.info("Workflow #{execution.id} awaiting human approval ##{approval.id}")
+
+      {:error, reason} ->
+        Logger.error("Failed to open human approval for workflow #{execution.id}: #{inspect(reason)}")
+    end
+
+    {:noreply, put_in(state, [:executions, execution.id], execution)}
+  end
+
   ## Private Helper Functions
 
+  defp schedule_approval_check do
+    Process.send_after(self(), :check_approvals, @approval_check_interval)
+  end
+
   defp generate_execution_id do
     "wf_" <> (:crypto.strong_rand_bytes(8) |> Base.encode16(case: :lower))
   end
@@ -91,7 +91,7 @@ defmodule EchoShared.Workflow.Examples.FeatureApprovalFlow do
 
     # Publish request to CEO via Redis
     MessageBus.publish_message(
-      :workflow,
+      :flow_engine,
       :ceo,
       :request,
       "Approve high-cost feature",
@@ -120,7 +120,7 @@ defmodule EchoShared.Workflow.Examples.FeatureApprovalFlow do
 
     # Publish request to CTO via Redis
     MessageBus.publish_message(
-      :workflow,
+      :flow_engine,
       :cto,
       :request,
       "Review high-complexity feature",
@@ -147,7 +147,7 @@ defmodule EchoShared.Workflow.Examples.FeatureApprovalFlow do
 
     # Broadcast approval to all stakeholders
     MessageBus.broadcast_message(
-      :workflow,
+      :flow_engine,
       :notification,
       "Feature auto-approved: #{state[:feature_name]}",
       %{
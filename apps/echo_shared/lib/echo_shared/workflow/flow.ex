@@ -31,19 +31,19 @@ defmodule EchoShared.Workflow.Flow do
         @listen "ceo_approval"
         def ceo_reviews(state) do
           # Publish message to CEO via Redis
-          MessageBus.publish_message(:workflow, :ceo, :request, "Approve feature", state)
+          MessageBus.publish_message(:flow_engine, :ceo, :request, "Approve feature", state)
           state
         end
 
         @listen "cto_approval"
         def cto_reviews(state) do
-          MessageBus.publish_message(:workflow, :cto, :request, "Review architecture", state)
+          MessageBus.publish_message(:flow_engine, :cto, :request, "Review architecture", state)
           state
         end
 
         @listen "auto_approve"
         def auto_approve(state) do
-          MessageBus.broadcast_message(:workflow, :notification, "Feature auto-approved", state)
+          MessageBus.broadcast_message(:flow_engine, :notification, "Feature auto-approved", state)
           Map.put(state, :approved, true)
         end
       end
@@ -16,7 +16,7 @@ defmodule EchoShared.Workflow.FlowCoordinator do
     request_id = generate_request_id()
 
     MessageBus.publish_message(
-      :workflow,
+      :flow_engine,
       :ceo,
       :request,
       "Approve feature",
@@ -46,6 +46,7 @@ defmodule EchoShared.Workflow.FlowCoordinator do
   use GenServer
   require Logger
 
+  alias EchoShared.MessageBus
   alias EchoShared.Workflow.FlowEngine
   alias EchoShared.Schemas.FlowExecution
   alias EchoShared.Repo
@@ -94,7 +95,7 @@ defmodule EchoShared.Workflow.FlowCoordinator do
 
     Enum.each(agent_roles, fn role ->
       channel = "messages:workflow_responses:#{role}"
-      case Redix.PubSub.subscribe(:redix_pubsub, channel, self()) do
+      case MessageBus.subscribe(channel) do
         {:ok, ref} ->
           Logger.info("Subscribed to #{channel} (ref: #{inspect(ref)})")
         {:error, reason} ->
@@ -103,7 +104,7 @@ defmodule EchoShared.Workflow.FlowCoordinator do
     end)
 
     # Also subscribe to generic workflow channel
-    case Redix.PubSub.subscribe(:redix_pubsub, "workflow:agent_responses", self()) do
+    case MessageBus.subscribe("workflow:agent_responses") do
       {:ok, ref} ->
         Logger.info("Subscribed to workflow:agent_responses (ref: #{inspect(ref)})")
       {:error, reason} ->
@@ -149,7 +150,7 @@ defmodule EchoShared.Workflow.FlowCoordinator do
   def handle_info({:redix_pubsub, _pid, _ref, :message, %{channel: channel, payload: payload}}, state) do
     Logger.info("Received message on channel: #{channel}")
 
-    case Jason.decode(payload) do
+    case MessageBus.decode_message(channel, payload) do
       {:ok, message} ->
         handle_agent_response(message, state)
 
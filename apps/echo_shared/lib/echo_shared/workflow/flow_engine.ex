@@ -23,6 +23,18 @@ defmodule EchoShared.Workflow.FlowEngine do
   - FlowCoordinator listens for response
   - On response → update state and continue
 
+  ## Human Approval
+
+  A listener can pause the flow for a human by returning its state with
+  `:await_human_approval` set to `%{reason: ...}` (optionally with
+  `:decision_id`, `:decision_type`, `:context` or `:urgency`, see
+  `EchoShared.Decisions.HumanApprovals.request_for_execution/3`):
+  - Flow execution status = :paused
+  - An item is opened in the human approval inbox
+  - Once it is resolved → `resume_after_approval/2` continues the flow
+    with the resolution in state under `"human_approval"`, in a task under
+    `EchoShared.Workflow.FlowEngine.TaskSupervisor`
+
   ## State Persistence
 
   All state is persisted to PostgreSQL after each step, enabling:
@@ -32,6 +44,9 @@ defmodule EchoShared.Workflow.FlowEngine do
   """
 
   require Logger
+  import Ecto.Query, only: [from: 2]
+
+  alias EchoShared.Decisions.HumanApprovals
   alias EchoShared.Repo
   alias EchoShared.Schemas.FlowExecution
 
@@ -44,6 +59,9 @@ defmodule EchoShared.Workflow.FlowEngine do
   # Maximum state size (1MB) - prevents DoS via large state
   @max_state_size 1_000_000
 
+  # Runs flows resumed after human approval (started by EchoShared.Application)
+  @task_sup __MODULE__.TaskSupervisor
+
   @doc """
   Start a flow execution.
 
@@ -132,6 +150,48 @@ defmodule EchoShared.Workflow.FlowEngine do
     end
   end
 
+  @doc """
+  Resume a flow paused for human approval.
+
+  Called by `EchoShared.Decisions.HumanApprovals` when the approval is
+  resolved; `resolution` is stored in state under `"human_approval"`. The
+  flow continues in a supervised task, so this returns once it's claimed.
+
+  ## Returns
+  {:ok, execution}, {:error, :not_paused} or {:error, reason}
+  """
+  def resume_after_approval(execution_id, resolution) do
+    # Claim the pause, so the flow is resumed once
+    {claimed, _} =
+      Repo.update_all(
+        from(f in FlowExecution, where: f.id == ^execution_id and f.status == :paused),
+        set: [status: :running, pause_reason: nil],
+        inc: [version: 1]
+      )
+
+    with 1 <- claimed,
+         %FlowExecution{} = execution <- Repo.get(FlowExecution, execution_id),
+         {:ok, flow_module} <- validate_and_load_flow_module(execution.flow_module) do
+      Logger.info("Resuming flow #{execution_id} after human approval")
+
+      {:ok, execution} =
+        execution
+        |> Ecto.Changeset.change(%{state: Map.put(execution.state, "human_approval", resolution)})
+        |> Repo.update()
+
+      step_name = String.to_existing_atom(execution.current_step)
+
+      {:ok, _pid} =
+        Task.Supervisor.start_child(@task_sup, fn -> continue_after_step(flow_module, execution, step_name) end)
+
+      {:ok, execution}
+    else
+      0 -> {:error, :not_paused}
+      nil -> {:error, :not_found}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
   @doc """
   Get flow execution status.
   """
@@ -327,7 +387,8 @@ defmodule EchoShared.Workflow.FlowEngine do
       {:ok, execution} = Repo.update(changeset)
 
       # Execute listener function
-      new_state = flow_module.execute_step(listener_fn, execution.state)
+      {approval_request, new_state} =
+        flow_module.execute_step(listener_fn, execution.state) |> Map.pop(:await_human_approval)
 
       # Update state
       changeset = Ecto.Changeset.change(execution, %{state: new_state})
@@ -337,8 +398,13 @@ defmodule EchoShared.Workflow.FlowEngine do
       changeset = FlowExecution.complete_step(updated_execution, to_string(listener_fn))
       {:ok, updated_execution} = Repo.update(changeset)
 
-      # Check for router after this listener
-      continue_after_step(flow_module, updated_execution, listener_fn)
+      if approval_request do
+        # Continues in resume_after_approval/2
+        pause_for_approval(updated_execution, approval_request)
+      else
+        # Check for router after this listener
+        continue_after_step(flow_module, updated_execution, listener_fn)
+      end
     rescue
       error ->
         Logger.error("Listener #{listener_fn} failed: #{inspect(error)}")
@@ -347,6 +413,19 @@ defmodule EchoShared.Workflow.FlowEngine do
     end
   end
 
+  defp pause_for_approval(execution, approval_request) do
+    changeset = Ecto.Changeset.change(execution, %{status: :paused, pause_reason: approval_request[:reason]})
+    {:ok, paused_execution} = Repo.update(changeset)
+
+    case HumanApprovals.request_for_execution(:flow, paused_execution.id, approval_request) do
+      {:ok, approval} ->
+        Logger.info("Flow #{paused_execution.id} awaiting human approval ##{approval.id}")
+
+      {:error, reason} ->
+        mark_failed(paused_execution, "Human approval request failed: #{inspect(reason)}")
+    end
+  end
+
   defp update_status(execution_id, status) do
     case Repo.get(FlowExecution, execution_id) do
       nil -> {:error, :not_found}
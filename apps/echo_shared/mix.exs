@@ -10,6 +10,7 @@ defmodule EchoShared.MixProject do
       deps_path: "../../deps",
       lockfile: "../../mix.lock",
       elixir: "~> 1.18",
+      elixirc_paths: elixirc_paths(Mix.env()),
       start_permanent: Mix.env() == :prod,
       deps: deps(),
 
@@ -30,6 +31,10 @@ defmodule EchoShared.MixProject do
     ]
   end
 
+  # Test support modules
+  defp elixirc_paths(:test), do: ["lib", "test/support"]
+  defp elixirc_paths(_), do: ["lib"]
+
   defp deps do
     [
       # Database
@@ -45,6 +50,10 @@ defmodule EchoShared.MixProject do
       # UUID generation
       {:uuid, "~> 1.1"},
 
+      # HTTP server for the MCP streamable-HTTP transport
+      {:plug, "~> 1.16"},
+      {:bandit, "~> 1.5"},
+
       # HTTP client for LLM integration
       {:req, "~> 0.5"},
       {:telemetry, "~> 1.2"},
@@ -0,0 +1,30 @@
+defmodule EchoShared.Repo.Migrations.AddMessageRetriesAndDeadLetters do
+  use Ecto.Migration
+
+  def change do
+    alter table(:messages) do
+      add :attempts, :integer, default: 0, null: false
+      add :next_attempt_at, :utc_datetime
+    end
+
+    create index(:messages, [:next_attempt_at], where: "read = false AND next_attempt_at IS NOT NULL")
+
+    create table(:dead_letters) do
+      add :message_id, :integer, null: false
+      add :from_role, :string, null: false
+      add :to_role, :string, null: false
+      add :type, :string, null: false
+      add :subject, :string, null: false
+      add :content, :map, null: false
+      add :metadata, :map
+      add :attempts, :integer, null: false
+      add :last_error, :text
+      add :message_inserted_at, :utc_datetime
+
+      timestamps(type: :utc_datetime, updated_at: false)
+    end
+
+    create index(:dead_letters, [:to_role, :inserted_at])
+    create index(:dead_letters, [:message_id])
+  end
+end
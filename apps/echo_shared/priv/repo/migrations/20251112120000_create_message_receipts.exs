@@ -0,0 +1,18 @@
+defmodule EchoShared.Repo.Migrations.CreateMessageReceipts do
+  use Ecto.Migration
+
+  def change do
+    create table(:message_receipts) do
+      add :message_id, references(:messages, on_delete: :delete_all), null: false
+      add :role, :string, null: false
+      add :delivered_at, :utc_datetime
+      add :processed_at, :utc_datetime
+      add :processing_error, :text
+
+      timestamps(type: :utc_datetime)
+    end
+
+    create unique_index(:message_receipts, [:message_id, :role])
+    create index(:message_receipts, [:role, :processed_at])
+  end
+end
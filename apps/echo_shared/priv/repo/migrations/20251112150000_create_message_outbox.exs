@@ -0,0 +1,21 @@
+defmodule EchoShared.Repo.Migrations.CreateMessageOutbox do
+  use Ecto.Migration
+
+  def change do
+    create table(:message_outbox) do
+      add :message_id, references(:messages, on_delete: :nilify_all)
+      add :channel, :string, null: false
+      add :payload, :text, null: false
+      add :attempts, :integer, default: 0, null: false
+      add :last_error, :text
+      add :next_attempt_at, :utc_datetime
+      add :sent_at, :utc_datetime
+      add :failed_at, :utc_datetime
+
+      timestamps(type: :utc_datetime, updated_at: false)
+    end
+
+    create index(:message_outbox, [:id], where: "sent_at IS NULL AND failed_at IS NULL", name: :message_outbox_pending_index)
+    create index(:message_outbox, [:message_id])
+  end
+end
@@ -0,0 +1,17 @@
+defmodule EchoShared.Repo.Migrations.CreateMessageReplies do
+  use Ecto.Migration
+
+  def change do
+    # Pending request/reply hand-offs for the Postgres MessageBus adapter
+    create table(:message_replies) do
+      add :key, :string, null: false
+      add :payload, :text, null: false
+      add :expires_at, :utc_datetime, null: false
+
+      timestamps(type: :utc_datetime, updated_at: false)
+    end
+
+    create index(:message_replies, [:key])
+    create index(:message_replies, [:expires_at])
+  end
+end
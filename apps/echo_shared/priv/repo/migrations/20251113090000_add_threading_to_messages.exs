@@ -0,0 +1,15 @@
+defmodule EchoShared.Repo.Migrations.AddThreadingToMessages do
+  use Ecto.Migration
+
+  def change do
+    # Plain IDs rather than foreign keys: a thread stays readable when one of
+    # its messages is dead-lettered
+    alter table(:messages) do
+      add :thread_id, :bigint
+      add :in_reply_to, :bigint
+    end
+
+    create index(:messages, [:thread_id, :inserted_at])
+    create index(:messages, [:in_reply_to])
+  end
+end
@@ -0,0 +1,15 @@
+defmodule EchoShared.Repo.Migrations.AddPriorityAndExpiryToMessages do
+  use Ecto.Migration
+
+  def change do
+    # 0 = low, 1 = normal, 2 = high, 3 = urgent (see Schemas.Message)
+    alter table(:messages) do
+      add :priority, :integer, default: 1, null: false
+      add :expires_at, :utc_datetime
+      add :archived_at, :utc_datetime
+    end
+
+    create index(:messages, [:to_role, :priority, :inserted_at], where: "read = false AND archived_at IS NULL")
+    create index(:messages, [:expires_at], where: "read = false AND archived_at IS NULL AND expires_at IS NOT NULL")
+  end
+end
@@ -0,0 +1,16 @@
+defmodule EchoShared.Repo.Migrations.CreatePolicyViolations do
+  use Ecto.Migration
+
+  def change do
+    create table(:policy_violations) do
+      add :from_role, :string, null: false
+      add :to_role, :string, null: false
+      add :type, :string, null: false
+      add :subject, :string
+
+      timestamps(type: :utc_datetime, updated_at: false)
+    end
+
+    create index(:policy_violations, [:from_role, :inserted_at])
+  end
+end
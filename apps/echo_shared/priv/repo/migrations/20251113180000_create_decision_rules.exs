@@ -0,0 +1,22 @@
+defmodule EchoShared.Repo.Migrations.CreateDecisionRules do
+  use Ecto.Migration
+
+  def change do
+    create table(:decision_rules) do
+      add :decision_type, :string, null: false
+      add :method, :string, null: false, default: "majority"
+      add :threshold, :float
+      add :quorum, :float, null: false, default: 0.5
+      add :role_weights, :map, null: false, default: %{}
+      add :vetoes, {:array, :string}, null: false, default: []
+      add :abstentions, :string, null: false, default: "exclude"
+      add :no_shows, :string, null: false, default: "exclude"
+      add :on_no_quorum, :string, null: false, default: "reject"
+      add :weigh_confidence, :boolean, null: false, default: true
+
+      timestamps(type: :utc_datetime)
+    end
+
+    create unique_index(:decision_rules, [:decision_type])
+  end
+end
@@ -0,0 +1,15 @@
+defmodule EchoShared.Repo.Migrations.AddDeadlinesToDecisions do
+  use Ecto.Migration
+
+  def change do
+    alter table(:decisions) do
+      add :deadline, :utc_datetime
+      add :timeout_policy, :string, null: false, default: "close"
+      add :reminder_sent_at, :utc_datetime
+      add :escalation_error, :string
+      add :metadata, :map
+    end
+
+    create index(:decisions, [:deadline], where: "status = 'pending' AND deadline IS NOT NULL AND escalation_error IS NULL")
+  end
+end
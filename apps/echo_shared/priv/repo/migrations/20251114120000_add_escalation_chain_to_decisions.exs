@@ -0,0 +1,12 @@
+defmodule EchoShared.Repo.Migrations.AddEscalationChainToDecisions do
+  use Ecto.Migration
+
+  def change do
+    alter table(:decisions) do
+      add :escalated_to, :string
+      add :escalation_hops, {:array, :map}, null: false, default: []
+    end
+
+    create index(:decisions, [:escalated_to], where: "status = 'escalated'")
+  end
+end
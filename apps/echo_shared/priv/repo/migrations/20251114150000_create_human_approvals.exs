@@ -0,0 +1,32 @@
+defmodule EchoShared.Repo.Migrations.CreateHumanApprovals do
+  use Ecto.Migration
+
+  def change do
+    create table(:human_approvals) do
+      add :decision_id, references(:decisions, type: :uuid, on_delete: :delete_all), null: false
+      add :status, :string, null: false, default: "pending"
+      add :requested_by, :string, null: false
+      add :reason, :text
+      add :urgency, :string
+      add :workflow_execution_id, :string
+      add :flow_execution_id, :string
+      add :comments, {:array, :map}, null: false, default: []
+      add :resolved_by, :string
+      add :resolution_note, :text
+      add :resolved_at, :utc_datetime
+      add :resumed_at, :utc_datetime
+      add :resume_error, :string
+
+      timestamps(type: :utc_datetime)
+    end
+
+    # One open approval per decision
+    create unique_index(:human_approvals, [:decision_id], where: "status = 'pending'")
+    create index(:human_approvals, [:status, :inserted_at])
+
+    # Resolved approvals whose workflow hasn't been resumed yet
+    create index(:human_approvals, [:workflow_execution_id],
+             where: "status <> 'pending' AND workflow_execution_id IS NOT NULL AND resumed_at IS NULL"
+           )
+  end
+end
@@ -11,11 +11,16 @@ defmodule EchoShared.DistributedSystemsTest do
   use ExUnit.Case, async: false
 
   alias EchoShared.Workflow.{Engine, Definition, Execution}
-  alias EchoShared.Schemas.{WorkflowExecution, Message}
+  alias EchoShared.Schemas.{WorkflowExecution, Message, DeadLetter, OutboxEntry, PolicyViolation}
   alias EchoShared.{MessageBus, AgentHealthMonitor, Repo}
 
   import Ecto.Query
 
+  # A bus that is down: every publish fails
+  defmodule DownAdapter do
+    def publish(_channel, _payload), do: {:error, :unavailable}
+  end
+
   setup do
     # Setup sandbox for isolated test execution
     :ok = Ecto.Adapters.SQL.Sandbox.checkout(Repo)
@@ -26,6 +31,9 @@ defmodule EchoShared.DistributedSystemsTest do
     # Clean database before each test
     Repo.delete_all(WorkflowExecution)
     Repo.delete_all(Message)
+    Repo.delete_all(DeadLetter)
+    Repo.delete_all(OutboxEntry)
+    Repo.delete_all(PolicyViolation)
 
     :ok
   end
@@ -148,11 +156,226 @@ defmodule EchoShared.DistributedSystemsTest do
 
       # Mark as failed
       error = {:error, "Something went wrong"}
-      {:ok, updated} = MessageBus.mark_message_failed(message_id, error)
+      {:ok, %Message{} = updated} = MessageBus.mark_message_failed(message_id, error)
 
-      assert updated.read == true
-      assert updated.processed_at != nil
+      # Stays unread and is scheduled for redelivery
+      assert updated.read == false
+      assert updated.attempts == 1
+      assert DateTime.compare(updated.next_attempt_at, DateTime.utc_now()) == :gt
       assert updated.processing_error != nil
+
+      # Not handed out again until the backoff has elapsed
+      assert MessageBus.fetch_unread_messages(:cto) == []
+    end
+
+    test "due retries are redelivered once" do
+      {:ok, message_id} = MessageBus.publish_message(:ceo, :cto, :request, "Test", %{})
+      {:ok, _} = MessageBus.mark_message_failed(message_id, :ollama_down)
+
+      Repo.update_all(
+        from(m in Message, where: m.id == ^message_id),
+        set: [next_attempt_at: DateTime.add(DateTime.utc_now(), -1, :second) |> DateTime.truncate(:second)]
+      )
+
+      assert {:ok, 1} = MessageBus.redeliver_due_messages()
+      assert {:ok, 0} = MessageBus.redeliver_due_messages()
+      assert [%Message{id: ^message_id}] = MessageBus.fetch_unread_messages(:cto)
+    end
+
+    test "messages are dead-lettered after max attempts" do
+      {:ok, message_id} = MessageBus.publish_message(:ceo, :cto, :request, "Test", %{data: "x"})
+
+      for _ <- 1..(MessageBus.max_attempts() - 1) do
+        {:ok, %Message{}} = MessageBus.mark_message_failed(message_id, :ollama_down)
+      end
+
+      {:ok, %DeadLetter{} = dead_letter} = MessageBus.mark_message_failed(message_id, :ollama_down)
+
+      assert dead_letter.message_id == message_id
+      assert dead_letter.attempts == MessageBus.max_attempts()
+      assert dead_letter.last_error == inspect(:ollama_down)
+      assert Repo.get(Message, message_id) == nil
+      assert [%DeadLetter{}] = MessageBus.list_dead_letters(to_role: :cto)
+    end
+
+    test "dead letters can be requeued and discarded" do
+      {:ok, dead_letter} =
+        %DeadLetter{}
+        |> DeadLetter.changeset(%{
+          message_id: 1,
+          from_role: "ceo",
+          to_role: "cto",
+          type: :request,
+          subject: "Requeue me",
+          content: %{"data" => "x"},
+          attempts: 5
+        })
+        |> Repo.insert()
+
+      {:ok, message_id} = MessageBus.requeue_dead_letter(dead_letter.id)
+
+      message = Repo.get(Message, message_id)
+      assert message.subject == "Requeue me"
+      assert message.attempts == 0
+      assert MessageBus.list_dead_letters() == []
+
+      assert {:error, :not_found} = MessageBus.requeue_dead_letter(dead_letter.id)
+      assert {:error, :not_found} = MessageBus.discard_dead_letter(dead_letter.id)
+    end
+  end
+
+  describe "Transactional Outbox" do
+    test "messages published in a transaction are left for the relay" do
+      {:ok, message_id} =
+        Repo.transaction(fn ->
+          {:ok, message_id} = MessageBus.publish_message(:ceo, :cto, :notification, "Approved", %{})
+          message_id
+        end)
+
+      entry = Repo.get_by(OutboxEntry, message_id: message_id)
+      assert entry.channel == "messages:cto"
+      assert entry.sent_at == nil
+      assert Jason.decode!(entry.payload)["db_id"] == message_id
+    end
+
+    test "rolling back the caller's transaction discards the message" do
+      {:error, :decision_failed} =
+        Repo.transaction(fn ->
+          {:ok, _} = MessageBus.publish_message(:ceo, :cto, :notification, "Approved", %{})
+          Repo.rollback(:decision_failed)
+        end)
+
+      assert Repo.aggregate(Message, :count) == 0
+      assert Repo.aggregate(OutboxEntry, :count) == 0
+    end
+
+    test "failed publishes back off and are given up on after outbox_max_attempts/0" do
+      previous = Application.get_env(:echo_shared, :message_bus_adapter)
+      Application.put_env(:echo_shared, :message_bus_adapter, __MODULE__.DownAdapter)
+      Application.put_env(:echo_shared, :outbox_max_attempts, 2)
+
+      on_exit(fn ->
+        Application.put_env(:echo_shared, :message_bus_adapter, previous)
+        Application.delete_env(:echo_shared, :outbox_max_attempts)
+      end)
+
+      {:ok, message_id} = MessageBus.publish_message(:ceo, :cto, :notification, "Approved", %{})
+
+      entry = Repo.get_by(OutboxEntry, message_id: message_id)
+      assert entry.attempts == 1
+      assert entry.next_attempt_at
+      assert entry.failed_at == nil
+
+      # Not due yet
+      later = DateTime.utc_now() |> DateTime.add(60, :second) |> DateTime.truncate(:second)
+      Repo.update_all(from(o in OutboxEntry, where: o.id == ^entry.id), set: [next_attempt_at: later])
+      assert MessageBus.relay_outbox() == {:ok, 0}
+      assert Repo.get!(OutboxEntry, entry.id).attempts == 1
+
+      earlier = DateTime.utc_now() |> DateTime.add(-1, :second) |> DateTime.truncate(:second)
+      Repo.update_all(from(o in OutboxEntry, where: o.id == ^entry.id), set: [next_attempt_at: earlier])
+      assert MessageBus.relay_outbox() == {:ok, 0}
+
+      entry = Repo.get!(OutboxEntry, entry.id)
+      assert entry.attempts == 2
+      assert entry.failed_at
+      assert entry.last_error == ":unavailable"
+
+      # Given up on, but still unread for the recipient
+      assert MessageBus.relay_outbox() == {:ok, 0}
+      assert Repo.get!(OutboxEntry, entry.id).attempts == 2
+      assert Repo.get!(Message, message_id).read == false
+    end
+  end
+
+  describe "Broadcast Receipts" do
+    test "processing a broadcast only marks it for that role" do
+      {:ok, message_id} = MessageBus.broadcast_message(:ceo, :notification, "All hands", %{})
+
+      {:ok, receipt} = MessageBus.mark_message_processed(message_id, :cto)
+      assert receipt.role == "cto"
+      assert receipt.processed_at != nil
+
+      assert MessageBus.fetch_unread_broadcasts(:cto) == []
+      assert [%Message{id: ^message_id}] = MessageBus.fetch_unread_broadcasts(:chro)
+      assert Repo.get(Message, message_id).read == false
+    end
+
+    test "failed broadcasts stay unread for the failing role" do
+      {:ok, message_id} = MessageBus.broadcast_message(:ceo, :notification, "All hands", %{})
+
+      :ok = MessageBus.mark_message_delivered(message_id, :cto)
+      {:ok, receipt} = MessageBus.mark_message_failed(message_id, :timeout, :cto)
+
+      assert receipt.delivered_at != nil
+      assert receipt.processing_error == inspect(:timeout)
+      assert [%Message{id: ^message_id}] = MessageBus.fetch_unread_broadcasts(:cto)
+    end
+  end
+
+  describe "Message Threads" do
+    test "replies join the thread of the message they answer" do
+      {:ok, root_id} = MessageBus.publish_message(:ceo, :cto, :request, "Roadmap", %{q: "Q3?"})
+      {:ok, reply_id} = MessageBus.continue_thread(root_id, :cto, "Q3 works")
+      {:ok, follow_up_id} = MessageBus.continue_thread(reply_id, :ceo, "Confirmed")
+
+      reply = Repo.get(Message, reply_id)
+      assert reply.to_role == "ceo"
+      assert reply.subject == "Re: Roadmap"
+      assert reply.in_reply_to == root_id
+      assert reply.thread_id == root_id
+
+      {:ok, thread} = MessageBus.fetch_thread(follow_up_id)
+      assert Enum.map(thread, & &1.id) == [root_id, reply_id, follow_up_id]
+      assert Repo.get(Message, follow_up_id).to_role == "cto"
+    end
+
+    test "unknown messages have no thread" do
+      assert MessageBus.fetch_thread(-1) == {:error, :not_found}
+      assert MessageBus.continue_thread(-1, :ceo, "Hello?") == {:error, :not_found}
+    end
+  end
+
+  describe "Message Priority and Expiry" do
+    test "unread messages are fetched highest priority first" do
+      {:ok, routine_id} = MessageBus.publish_message(:cto, :ceo, :notification, "Weekly update", %{})
+      {:ok, low_id} = MessageBus.publish_message(:cto, :ceo, :request, "Tidy docs", %{}, %{priority: :low})
+      {:ok, escalation_id} = MessageBus.publish_message(:cto, :ceo, :escalation, "Outage", %{})
+      {:ok, high_id} = MessageBus.publish_message(:cto, :ceo, :request, "Budget", %{}, %{priority: "high"})
+
+      assert Repo.get(Message, escalation_id).priority == :urgent
+
+      assert Enum.map(MessageBus.fetch_unread_messages(:ceo), & &1.id) ==
+               [escalation_id, high_id, routine_id, low_id]
+    end
+
+    test "expired messages are skipped and archived" do
+      past = DateTime.add(DateTime.utc_now(), -60, :second)
+
+      {:ok, stale_id} = MessageBus.publish_message(:cto, :ceo, :request, "Stale", %{}, %{expires_at: past})
+      {:ok, fresh_id} = MessageBus.publish_message(:cto, :ceo, :request, "Fresh", %{}, %{ttl: 3600})
+
+      assert [%Message{id: ^fresh_id}] = MessageBus.fetch_unread_messages(:ceo)
+
+      assert {:ok, 1} = MessageBus.archive_expired_messages()
+      assert Repo.get(Message, stale_id).archived_at != nil
+      assert Repo.get(Message, fresh_id).archived_at == nil
+      assert {:ok, 0} = MessageBus.archive_expired_messages()
+    end
+  end
+
+  describe "Communication Policy" do
+    test "forbidden messages are refused and audited" do
+      assert {:error, :forbidden} =
+               MessageBus.publish_message(:senior_developer, :leadership, :request, "Rewrite it", %{})
+
+      assert {:error, :forbidden} =
+               MessageBus.broadcast_message(:test_lead, :request, "Everyone test this", %{})
+
+      assert Repo.all(from m in Message, where: m.from_role in ["senior_developer", "test_lead"]) == []
+
+      assert [%PolicyViolation{from_role: "senior_developer", to_role: "leadership", type: "request"}, _] =
+               Repo.all(from v in PolicyViolation, order_by: v.id)
     end
   end
 
@@ -0,0 +1,81 @@
+defmodule EchoShared.Decisions.DeadlinesTest do
+  use EchoShared.DataCase
+
+  import ExUnit.CaptureLog
+
+  alias EchoShared.Decisions.{Deadlines, Voting}
+  alias EchoShared.Schemas.{Decision, Message}
+
+  defp in_seconds(seconds), do: DateTime.utc_now() |> DateTime.add(seconds, :second)
+
+  test "participants who haven't voted are reminded once before the deadline" do
+    decision = insert_decision(%{deadline: in_seconds(600)})
+    {:ok, _} = Voting.cast_vote(decision.id, :cto, :approve)
+
+    assert {:ok, 1} = Deadlines.send_reminders()
+    assert {:ok, 0} = Deadlines.send_reminders()
+
+    reminded =
+      Repo.all(
+        from m in Message,
+          where: m.from_role == "decision_scheduler" and m.subject == "Vote Reminder: budget",
+          select: m.to_role
+      )
+
+    assert Enum.sort(reminded) == ["chro", "operations_head"]
+    assert Repo.get(Decision, decision.id).reminder_sent_at != nil
+  end
+
+  test "decisions whose deadline is further away than the lead time aren't reminded" do
+    insert_decision(%{deadline: in_seconds(Deadlines.reminder_lead_seconds() + 600)})
+
+    assert {:ok, 0} = Deadlines.send_reminders()
+  end
+
+  test "an overdue collaborative vote is closed with the votes cast so far" do
+    decision = insert_decision(%{deadline: in_seconds(-60)})
+    {:ok, _} = Voting.cast_vote(decision.id, :cto, :approve)
+    {:ok, _} = Voting.cast_vote(decision.id, :chro, :approve)
+
+    assert {:ok, 1} = Deadlines.expire_overdue()
+
+    decision = Repo.get(Decision, decision.id)
+    assert decision.status == :approved
+    assert decision.completed_at != nil
+  end
+
+  test "the timeout policy defaults the outcome of overdue decisions" do
+    escalated = insert_decision(%{deadline: in_seconds(-60), timeout_policy: :escalate})
+    approved = insert_decision(%{deadline: in_seconds(-60), timeout_policy: :approve})
+    rejected = insert_decision(%{deadline: in_seconds(-60), mode: :hierarchical, timeout_policy: :reject})
+    closed = insert_decision(%{deadline: in_seconds(-60), mode: :hierarchical})
+    upcoming = insert_decision(%{deadline: in_seconds(600), timeout_policy: :approve})
+
+    assert {:ok, 4} = Deadlines.expire_overdue()
+
+    assert %Decision{status: :escalated, escalated_to: "human"} = Repo.get(Decision, escalated.id)
+    assert Repo.get(Decision, approved.id).status == :approved
+    assert Repo.get(Decision, rejected.id).status == :rejected
+    assert Repo.get(Decision, closed.id).status == :escalated
+    assert Repo.get(Decision, upcoming.id).status == :pending
+
+    assert %{"decided_by" => "timeout"} = Repo.get(Decision, approved.id).outcome
+    assert {:ok, 0} = Deadlines.expire_overdue()
+  end
+
+  test "a decision whose escalation fails is recorded and left out of later sweeps" do
+    # Nobody may message anybody, so the escalation message is refused
+    Application.put_env(:echo_shared, :message_policy, [])
+    on_exit(fn -> Application.delete_env(:echo_shared, :message_policy) end)
+
+    decision =
+      insert_decision(%{deadline: in_seconds(-60), initiator_role: "senior_developer", timeout_policy: :escalate})
+
+    assert capture_log(fn -> assert {:ok, 0} = Deadlines.expire_overdue() end) =~ "couldn't be escalated"
+    assert %Decision{status: :pending, escalation_error: ":forbidden"} = Repo.get(Decision, decision.id)
+
+    Application.delete_env(:echo_shared, :message_policy)
+    assert {:ok, 0} = Deadlines.expire_overdue()
+    assert Repo.get(Decision, decision.id).status == :pending
+  end
+end
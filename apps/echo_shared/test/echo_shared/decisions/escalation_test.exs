@@ -0,0 +1,117 @@
+defmodule EchoShared.Decisions.EscalationTest do
+  use EchoShared.DataCase
+
+  alias EchoShared.Decisions.Escalation
+  alias EchoShared.MessageBus
+  alias EchoShared.Schemas.{Decision, Message}
+
+  defp developer_decision(decision_type, status \\ :pending) do
+    insert_decision(%{
+      decision_type: decision_type,
+      initiator_role: "senior_developer",
+      participants: [],
+      mode: :hierarchical,
+      status: status,
+      context: %{"summary" => "Replace the job queue"}
+    })
+  end
+
+  test "the chain follows the reporting lines up to the human" do
+    assert Escalation.chain(:senior_developer) == ["senior_architect", "cto", "ceo", "human"]
+    assert Escalation.chain("ceo") == ["human"]
+    assert Escalation.chain("human") == []
+  end
+
+  test "escalation stops at the first role with authority and records each hop" do
+    decision = developer_decision("technology_strategy")
+
+    assert {:ok, escalated} = Escalation.escalate(decision, "Affects every team", urgency: "high")
+
+    assert escalated.status == :escalated
+    assert escalated.escalated_to == "cto"
+
+    assert [
+             %{"from" => "senior_developer", "to" => "senior_architect", "authority" => false},
+             %{"from" => "senior_architect", "to" => "cto", "authority" => true, "urgency" => "high"}
+           ] = Repo.get(Decision, decision.id).escalation_hops
+
+    assert [_message] =
+             Repo.all(
+               from m in Message,
+                 where: m.to_role == "cto" and m.type == :escalation and m.from_role == "senior_developer"
+             )
+  end
+
+  test "escalating again continues from the current holder" do
+    decision = developer_decision("architecture")
+
+    {:ok, %Decision{escalated_to: "senior_architect"}} = Escalation.escalate(decision, "Needs a design review")
+    {:ok, escalated} = Escalation.escalate(decision.id, "Conflicts with the platform roadmap")
+
+    # Nobody above the architect has authority over architecture, so it ends with the human
+    assert escalated.escalated_to == "human"
+    assert Enum.map(escalated.escalation_hops, & &1["to"]) == ["senior_architect", "cto", "ceo", "human"]
+
+    assert {:error, {:no_reporting_line, "human"}} = Escalation.escalate(decision.id, "Again")
+  end
+
+  test "escalating to a given role stops there, with or without authority" do
+    decision = developer_decision("system_design")
+
+    assert {:error, {:not_above, "test_lead"}} = Escalation.escalate(decision, "Wrong line", to: :test_lead)
+    assert {:ok, escalated} = Escalation.escalate(decision, "Board needs to know", to: :ceo)
+
+    assert escalated.escalated_to == "ceo"
+    assert Enum.map(escalated.escalation_hops, & &1["to"]) == ["senior_architect", "cto", "ceo"]
+    assert Repo.exists?(from m in Message, where: m.to_role == "ceo" and m.type == :escalation)
+  end
+
+  test "configured authority and reporting lines override the defaults" do
+    Application.put_env(:echo_shared, :decision_authority, %{cto: ["architecture"]})
+    Application.put_env(:echo_shared, :reports_to, %{senior_developer: :cto})
+
+    on_exit(fn ->
+      Application.delete_env(:echo_shared, :decision_authority)
+      Application.delete_env(:echo_shared, :reports_to)
+    end)
+
+    decision = developer_decision("architecture")
+
+    assert {:ok, %Decision{escalated_to: "cto", escalation_hops: [%{"from" => "senior_developer"}]}} =
+             Escalation.escalate(decision, "Skip the architect")
+  end
+
+  test "inside a caller's transaction the caller announces the escalation after its commit" do
+    {:ok, _ref} = MessageBus.subscribe("decisions:escalated")
+    decision = developer_decision("architecture")
+
+    {:error, :changed_my_mind} =
+      Repo.transaction(fn ->
+        {:ok, _} = Escalation.escalate(decision, "Needs a design review")
+        Repo.rollback(:changed_my_mind)
+      end)
+
+    assert Repo.get(Decision, decision.id).status == :pending
+
+    {:ok, escalated} =
+      Repo.transaction(fn ->
+        {:ok, escalated} = Escalation.escalate(decision, "Needs a design review", urgency: "low")
+        escalated
+      end)
+
+    refute_receive {:redix_pubsub, _, _, :message, %{channel: "decisions:escalated"}}, 100
+
+    {:ok, _} = Escalation.announce(escalated, "senior_developer")
+    assert_receive {:redix_pubsub, _, _, :message, %{channel: "decisions:escalated", payload: payload}}
+
+    assert {:ok, %{"escalated_by" => "senior_developer", "escalated_to" => "senior_architect", "urgency" => "low"}} =
+             MessageBus.decode_message("decisions:escalated", payload)
+  end
+
+  test "settled decisions can't be escalated" do
+    decision = developer_decision("architecture", :approved)
+
+    assert {:error, :closed} = Escalation.escalate(decision, "Too late")
+    assert {:error, :not_found} = Escalation.escalate("not-a-uuid", "Missing")
+  end
+end
@@ -0,0 +1,121 @@
+defmodule EchoShared.Decisions.HumanApprovalsTest do
+  use EchoShared.DataCase
+
+  alias EchoShared.Decisions.{Escalation, HumanApprovals}
+  alias EchoShared.Schemas.{Decision, FlowExecution, HumanApproval, Message, WorkflowExecution}
+  alias EchoShared.Workflow.Engine
+
+  setup do
+    decision =
+      insert_decision(%{
+        decision_type: "acquisition",
+        participants: [],
+        mode: :hierarchical,
+        context: %{"target" => "Acme"}
+      })
+
+    {:ok, decision: decision}
+  end
+
+  test "escalating to the human opens an approval whose outcome applies to the decision", %{decision: decision} do
+    {:ok, %Decision{escalated_to: "human"}} = Escalation.escalate(decision, "Board-level call", urgency: "high")
+
+    assert [%HumanApproval{requested_by: "ceo", urgency: "high"} = approval] = HumanApprovals.list()
+    assert approval.decision.id == decision.id
+
+    assert {:ok, %HumanApproval{status: :approved, resolved_by: "board"}} =
+             HumanApprovals.approve(approval.id, by: "board", note: "Go ahead")
+
+    decision = Repo.get(Decision, decision.id)
+    assert decision.status == :approved
+    assert %{"decided_by" => "human", "resolved_by" => "board", "note" => "Go ahead"} = decision.outcome
+
+    assert [_notification] =
+             Repo.all(from m in Message, where: m.from_role == "human" and m.to_role == "ceo")
+
+    assert HumanApprovals.list() == []
+    assert {:error, :already_resolved} = HumanApprovals.reject(approval.id)
+  end
+
+  test "one approval stays open per decision", %{decision: decision} do
+    {:ok, first} = HumanApprovals.request(decision, reason: "Needs sign-off")
+    {:ok, second} = HumanApprovals.request(decision.id, reason: "Again")
+
+    assert first.id == second.id
+  end
+
+  test "comments are recorded and sent to the requester", %{decision: decision} do
+    {:ok, approval} = HumanApprovals.request(decision, requested_by: :cto, reason: "Needs sign-off")
+
+    {:ok, _} = HumanApprovals.comment(approval.id, "What does legal say?")
+    {:ok, approval} = HumanApprovals.comment(approval.id, "Fine by me", by: "cfo")
+
+    assert [%{"by" => "human"}, %{"by" => "cfo", "text" => "Fine by me"}] = approval.comments
+    assert Repo.aggregate(from(m in Message, where: m.to_role == "cto"), :count) == 2
+    assert {:error, :not_found} = HumanApprovals.comment(-1, "Anyone?")
+
+    {:ok, _} = HumanApprovals.approve(approval.id)
+    assert {:error, :already_resolved} = HumanApprovals.comment(approval.id, "Too late")
+  end
+
+  test "a flow paused on an approval resumes with the resolution" do
+    {:ok, flow} =
+      %FlowExecution{}
+      |> FlowExecution.changeset(%{
+        id: "flow_approval_test",
+        flow_module: "EchoShared.Workflow.Examples.FeatureApprovalFlow",
+        status: :paused,
+        current_step: "request_ceo_approval",
+        state: %{"feature_name" => "OAuth2"}
+      })
+      |> Repo.insert()
+
+    {:ok, approval} = HumanApprovals.request_for_execution(:flow, flow.id, %{reason: "Over budget"})
+    assert %Decision{mode: :human, initiator_role: "flow_engine"} = Repo.get(Decision, approval.decision_id)
+
+    assert {:ok, %HumanApproval{resumed_at: %DateTime{}}} = HumanApprovals.reject(approval.id)
+
+    # The flow continues in a supervised task
+    flow = await_flow(flow.id, :completed)
+    assert %{"human_approval" => %{"status" => "rejected"}} = flow.state
+  end
+
+  test "workflows wait for the engine that runs them", %{decision: decision} do
+    {:ok, approval} = HumanApprovals.request(decision, workflow_execution_id: "wf_elsewhere")
+
+    {:ok, approval} = HumanApprovals.approve(approval.id)
+
+    assert approval.resumed_at == nil
+    assert Repo.get(Decision, decision.id).status == :approved
+    assert {:error, :engine_not_running} = HumanApprovals.resume(approval)
+  end
+
+  test "a workflow paused before its engine restarted fails instead of resuming", %{decision: decision} do
+    {:ok, _} =
+      %WorkflowExecution{}
+      |> WorkflowExecution.changeset(%{id: "wf_restarted", workflow_name: "Launch", status: :paused, current_step: 1})
+      |> Repo.insert()
+
+    # The engine recovers the execution, but not its steps
+    start_supervised!(Engine)
+
+    {:ok, approval} = HumanApprovals.request(decision, workflow_execution_id: "wf_restarted")
+    {:ok, approval} = HumanApprovals.approve(approval.id)
+
+    assert %HumanApproval{resumed_at: nil, resume_error: "definition_unavailable"} =
+             Repo.get(HumanApproval, approval.id)
+    assert Repo.get(WorkflowExecution, "wf_restarted").status == :failed
+    assert HumanApprovals.resume_approved_workflows() == {:ok, 0}
+  end
+
+  defp await_flow(flow_id, status, attempts \\ 50) do
+    flow = Repo.get(FlowExecution, flow_id)
+
+    if flow.status == status or attempts == 0 do
+      flow
+    else
+      Process.sleep(20)
+      await_flow(flow_id, status, attempts - 1)
+    end
+  end
+end
@@ -0,0 +1,79 @@
+defmodule EchoShared.Decisions.RulesTest do
+  use ExUnit.Case, async: true
+
+  alias EchoShared.Decisions.Rules
+
+  @participants ["cto", "chro", "operations_head"]
+
+  defp rule(overrides) do
+    Map.merge(
+      %{
+        method: :majority,
+        threshold: nil,
+        quorum: 0.5,
+        role_weights: %{},
+        vetoes: [],
+        abstentions: :exclude,
+        no_shows: :exclude,
+        on_no_quorum: :reject,
+        weigh_confidence: true
+      },
+      overrides
+    )
+  end
+
+  defp vote(role, vote, confidence \\ 1.0), do: %{voter_role: role, vote: vote, confidence: confidence}
+
+  test "a veto rejects before everyone has voted" do
+    votes = [vote("chro", :approve), vote("cto", :reject, 0.2)]
+
+    assert {:decided, :rejected, %{reason: :veto, vetoed_by: "cto"}} =
+             Rules.evaluate(rule(%{vetoes: ["cto"]}), @participants, votes)
+  end
+
+  test "the decision waits for every participant unless the vote is closing" do
+    votes = [vote("cto", :approve), vote("chro", :approve)]
+
+    assert {:pending, _tally} = Rules.evaluate(rule(%{}), @participants, votes)
+    assert {:decided, :approved, %{no_shows: ["operations_head"]}} =
+             Rules.evaluate(rule(%{}), @participants, votes, true)
+  end
+
+  test "supermajority counts no-shows as configured" do
+    votes = [vote("cto", :approve), vote("chro", :approve)]
+
+    assert {:decided, :approved, _} = Rules.evaluate(rule(%{method: :supermajority}), @participants, votes, true)
+
+    strict = rule(%{method: :supermajority, threshold: 0.75, no_shows: :count_as_reject})
+
+    assert {:decided, :rejected, %{consensus_score: score}} = Rules.evaluate(strict, @participants, votes, true)
+
+    assert_in_delta score, 2 / 3, 0.001
+  end
+
+  test "unanimity fails on a single reject and abstentions can count against" do
+    votes = [vote("cto", :approve), vote("chro", :approve), vote("operations_head", :abstain)]
+
+    assert {:decided, :approved, _} = Rules.evaluate(rule(%{method: :unanimous}), @participants, votes)
+
+    assert {:decided, :rejected, _} =
+             Rules.evaluate(rule(%{method: :unanimous, abstentions: :count_as_reject}), @participants, votes)
+  end
+
+  test "role weights can outvote a headcount majority" do
+    votes = [vote("cto", :approve), vote("chro", :reject), vote("operations_head", :reject)]
+    weighted = rule(%{method: :weighted, role_weights: %{"cto" => 3.0}, weigh_confidence: false})
+
+    assert {:decided, :rejected, _} = Rules.evaluate(rule(%{}), @participants, votes)
+    assert {:decided, :approved, %{consensus_score: 0.6}} = Rules.evaluate(weighted, @participants, votes)
+  end
+
+  test "missing quorum rejects or escalates" do
+    votes = [vote("cto", :approve)]
+
+    assert {:decided, :rejected, %{reason: :no_quorum}} = Rules.evaluate(rule(%{}), @participants, votes, true)
+
+    assert {:decided, :escalated, %{reason: :no_quorum}} =
+             Rules.evaluate(rule(%{on_no_quorum: :escalate}), @participants, votes, true)
+  end
+end
@@ -0,0 +1,79 @@
+defmodule EchoShared.Decisions.VotingTest do
+  use EchoShared.DataCase
+
+  alias EchoShared.Decisions.Voting
+  alias EchoShared.MessageBus
+  alias EchoShared.Schemas.{Decision, DecisionRule, DecisionVote}
+
+  setup do
+    {:ok, decision: insert_decision()}
+  end
+
+  test "the decision is finalized with a weighted consensus once everyone votes", %{decision: decision} do
+    assert {:ok, _} = Voting.open_vote(decision.id)
+
+    {:ok, _} = Voting.cast_vote(decision.id, :cto, :approve, confidence: 0.9)
+    {:ok, _} = Voting.cast_vote(decision.id, "chro", "reject", confidence: 0.3, rationale: "Hiring freeze")
+    assert Repo.get(Decision, decision.id).status == :pending
+
+    {:ok, %DecisionVote{vote: :abstain}} = Voting.cast_vote(decision.id, :operations_head, :abstain, confidence: 0.5)
+
+    decision = Repo.get(Decision, decision.id)
+    assert decision.status == :approved
+    assert_in_delta decision.consensus_score, 0.75, 0.001
+    assert decision.completed_at != nil
+
+    assert {:error, :closed} = Voting.cast_vote(decision.id, :cto, :reject)
+  end
+
+  test "votes can be changed until the vote is finalized", %{decision: decision} do
+    {:ok, _} = Voting.cast_vote(decision.id, :cto, :approve)
+    {:ok, _} = Voting.cast_vote(decision.id, :cto, :reject, confidence: 0.6)
+
+    assert [%DecisionVote{vote: :reject, confidence: 0.6}] = Voting.list_votes(decision.id)
+
+    {:ok, decision} = Voting.finalize(decision.id)
+    assert decision.status == :rejected
+    assert decision.consensus_score == 0.0
+  end
+
+  test "rules stored in the database apply to their decision type", %{decision: decision} do
+    {:ok, _rule} =
+      %DecisionRule{}
+      |> DecisionRule.changeset(%{decision_type: "budget", method: :majority, vetoes: ["chro"]})
+      |> Repo.insert()
+
+    {:ok, _} = Voting.cast_vote(decision.id, :chro, :reject, confidence: 0.1)
+
+    decision = Repo.get(Decision, decision.id)
+    assert decision.status == :rejected
+    assert decision.outcome["reason"] == "veto"
+  end
+
+  test "a vote closed without quorum goes up the initiator's reporting chain", %{decision: decision} do
+    {:ok, _rule} =
+      %DecisionRule{}
+      |> DecisionRule.changeset(%{decision_type: "budget", quorum: 0.75, on_no_quorum: :escalate})
+      |> Repo.insert()
+
+    {:ok, _ref} = MessageBus.subscribe("decisions:escalated")
+    {:ok, _} = Voting.cast_vote(decision.id, :cto, :approve)
+    {:ok, decision} = Voting.finalize(decision.id)
+
+    assert %Decision{status: :escalated, escalated_to: "human"} = decision
+    assert [%{"from" => "ceo", "to" => "human", "reason" => "No quorum"}] = decision.escalation_hops
+    assert decision.outcome["reason"] == "no_quorum"
+
+    # Announced once, after the vote's transaction committed
+    assert_receive {:redix_pubsub, _, _, :message, %{channel: "decisions:escalated", payload: payload}}
+    assert {:ok, %{"escalated_by" => "ceo", "reason" => "No quorum"}} = MessageBus.decode_message("decisions:escalated", payload)
+    refute_receive {:redix_pubsub, _, _, :message, %{channel: "decisions:escalated"}}, 100
+  end
+
+  test "only participants of open collaborative decisions may vote", %{decision: decision} do
+    assert {:error, :not_participant} = Voting.cast_vote(decision.id, :test_lead, :approve)
+    assert {:error, %Ecto.Changeset{}} = Voting.cast_vote(decision.id, :cto, :approve, confidence: 2.0)
+    assert {:error, :not_found} = Voting.cast_vote(Ecto.UUID.generate(), :cto, :approve)
+    assert {:error, :not_found} = Voting.cast_vote("not-a-uuid", :cto, :approve)
+  end
+end
@@ -0,0 +1,193 @@
+defmodule EchoShared.MCP.HTTPTransportTest do
+  use ExUnit.Case, async: false
+
+  import Plug.Conn
+  import Plug.Test
+
+  alias EchoShared.MCP.{HTTPTransport, Protocol, TestServer}
+  alias EchoShared.MCP.HTTPTransport.Sessions
+
+  @registry EchoShared.MCP.HTTPTransport.Registry
+
+  setup do
+    # The transport's children, minus the listener; requests go through call/2
+    start_supervised!({Registry, keys: :duplicate, name: @registry})
+    start_supervised!(Sessions)
+    start_supervised!({Task.Supervisor, name: EchoShared.MCP.HTTPTransport.TaskSupervisor})
+    :ok
+  end
+
+  defp request(method, body \\ "", headers \\ []) do
+    conn = put_req_header(conn(method, "/mcp", body), "content-type", "application/json")
+
+    headers
+    |> Enum.reduce(conn, fn {name, value}, conn -> put_req_header(conn, name, value) end)
+    |> HTTPTransport.call(HTTPTransport.init(server: TestServer))
+  end
+
+  defp rpc(session_id, message, headers \\ []) do
+    request(:post, Jason.encode!(message), [{"mcp-session-id", session_id} | headers])
+  end
+
+  defp initialize do
+    message = %{
+      jsonrpc: "2.0",
+      id: 1,
+      method: "initialize",
+      params: %{protocolVersion: "2025-06-18", capabilities: %{}, clientInfo: %{name: "test", version: "1.0"}}
+    }
+
+    conn = request(:post, Jason.encode!(message))
+    assert conn.status == 200
+    assert %{"result" => %{"protocolVersion" => "2025-06-18"}} = Jason.decode!(conn.resp_body)
+
+    [session_id] = get_resp_header(conn, "mcp-session-id")
+    session_id
+  end
+
+  defp call(id, tool, arguments, meta \\ %{}) do
+    %{jsonrpc: "2.0", id: id, method: "tools/call", params: %{name: tool, arguments: arguments, _meta: meta}}
+  end
+
+  defp events(body) do
+    for "data: " <> data <- String.split(body, "\n"), do: Jason.decode!(data)
+  end
+
+  describe "POST /mcp" do
+    test "initialize opens a session that later requests must carry" do
+      session_id = initialize()
+
+      conn = rpc(session_id, %{jsonrpc: "2.0", id: 2, method: "tools/list"})
+      assert conn.status == 200
+      assert %{"result" => %{"tools" => tools}} = Jason.decode!(conn.resp_body)
+      assert Enum.any?(tools, &(&1["name"] == "echo"))
+
+      assert request(:post, Jason.encode!(%{jsonrpc: "2.0", id: 3, method: "ping"})).status == 400
+      assert rpc("mcp_unknown", %{jsonrpc: "2.0", id: 4, method: "ping"}).status == 404
+    end
+
+    test "tool calls are answered as JSON, or as an event stream with progress first" do
+      session_id = initialize()
+
+      conn = rpc(session_id, call(2, "echo", %{text: "hello"}))
+      assert conn.status == 200
+      assert %{"id" => 2, "result" => %{"content" => [%{"text" => "hello"}]}} = Jason.decode!(conn.resp_body)
+
+      accept = {"accept", "application/json, text/event-stream"}
+      conn = rpc(session_id, call(3, "progress", %{}, %{progressToken: "tok"}), [accept])
+      assert conn.status == 200
+      assert [content_type] = get_resp_header(conn, "content-type")
+      assert content_type =~ "text/event-stream"
+
+      assert [
+               %{"method" => "notifications/progress", "params" => %{"progress" => 1}},
+               %{"method" => "notifications/progress", "params" => %{"progress" => 2}},
+               %{"id" => 3, "result" => %{"content" => [%{"text" => "done"}]}}
+             ] = events(conn.resp_body)
+    end
+
+    test "notifications are accepted without a body" do
+      session_id = initialize()
+
+      conn = rpc(session_id, %{jsonrpc: "2.0", method: "notifications/initialized"})
+      assert conn.status == 202
+      assert conn.resp_body == ""
+    end
+
+    test "batches and other non-object bodies are invalid requests" do
+      session_id = initialize()
+
+      for body <- [[%{jsonrpc: "2.0", id: 2, method: "ping"}], "ping", 42] do
+        conn = rpc(session_id, body)
+        assert conn.status == 400
+        assert %{"id" => nil, "error" => %{"code" => -32600}} = Jason.decode!(conn.resp_body)
+      end
+    end
+
+    test "unsupported protocol version headers are rejected" do
+      session_id = initialize()
+
+      conn = rpc(session_id, %{jsonrpc: "2.0", id: 2, method: "ping"}, [{"mcp-protocol-version", "1999-01-01"}])
+      assert conn.status == 400
+
+      conn = rpc(session_id, %{jsonrpc: "2.0", id: 3, method: "ping"}, [{"mcp-protocol-version", Protocol.latest_version()}])
+      assert conn.status == 200
+    end
+  end
+
+  describe "GET /mcp" do
+    test "requires an event-stream Accept header and a session" do
+      session_id = initialize()
+
+      assert request(:get, "", [{"mcp-session-id", session_id}]).status == 406
+      assert request(:get, "", [{"accept", "text/event-stream"}, {"mcp-session-id", "mcp_unknown"}]).status == 404
+    end
+
+    test "registers a notification stream for the session" do
+      session_id = initialize()
+
+      stream =
+        Task.async(fn ->
+          request(:get, "", [{"accept", "text/event-stream"}, {"mcp-session-id", session_id}])
+        end)
+
+      assert wait_until(fn -> Registry.lookup(@registry, :streams) == [{stream.pid, session_id}] end)
+
+      Task.shutdown(stream, :brutal_kill)
+    end
+  end
+
+  describe "DELETE /mcp" do
+    test "ends the session" do
+      session_id = initialize()
+
+      assert request(:delete, "", [{"mcp-session-id", session_id}]).status == 200
+      assert rpc(session_id, %{jsonrpc: "2.0", id: 2, method: "ping"}).status == 404
+    end
+  end
+
+  describe "Sessions" do
+    test "idle sessions expire on the next sweep" do
+      session_id = initialize()
+
+      Application.put_env(:echo_shared, :mcp_http_session_idle_seconds, 0)
+      on_exit(fn -> Application.delete_env(:echo_shared, :mcp_http_session_idle_seconds) end)
+      Process.sleep(10)
+
+      send(Sessions, :sweep)
+      assert Sessions.touch(session_id) == :error
+      assert rpc(session_id, %{jsonrpc: "2.0", id: 2, method: "ping"}).status == 404
+    end
+  end
+
+  describe "Origin" do
+    test "browser requests from foreign origins are rejected" do
+      ping = Jason.encode!(%{jsonrpc: "2.0", id: 1, method: "ping"})
+      conn = request(:post, ping, [{"origin", "https://evil.example"}])
+
+      assert conn.status == 403
+    end
+
+    test "local origins are allowed" do
+      session_id = initialize()
+
+      conn = rpc(session_id, %{jsonrpc: "2.0", id: 2, method: "ping"}, [{"origin", "http://localhost:3000"}])
+
+      assert conn.status == 200
+    end
+  end
+
+  defp wait_until(fun, attempts \\ 50) do
+    cond do
+      fun.() ->
+        true
+
+      attempts == 0 ->
+        false
+
+      true ->
+        Process.sleep(10)
+        wait_until(fun, attempts - 1)
+    end
+  end
+end
@@ -0,0 +1,66 @@
+defmodule EchoShared.MCP.LoggingTest do
+  use ExUnit.Case, async: false
+
+  import ExUnit.CaptureLog
+  require Logger
+
+  alias EchoShared.MCP.{Logging, TestServer}
+
+  setup do
+    session = "session-#{System.unique_integer([:positive])}"
+    :ok = Logging.attach(self(), session)
+    on_exit(fn -> Logging.forget(session) end)
+    {:ok, session: session}
+  end
+
+  defp log(level, message), do: capture_log(fn -> Logger.log(level, message) end)
+
+  test "sessions get warnings and above by default", %{session: session} do
+    log(:warning, "Disk almost full #{session}")
+
+    assert_receive {:mcp_notification,
+                    %{method: "notifications/message", params: %{level: "warning", data: "Disk almost full " <> _}}}
+  end
+
+  test "logging/setLevel changes the level for the calling session only", %{session: session} do
+    test = self()
+    other = spawn_link(fn -> relay(test) end)
+    :ok = Logging.attach(other, "other-#{session}")
+    on_exit(fn -> Logging.forget("other-#{session}") end)
+
+    request = %{jsonrpc: "2.0", id: 1, method: "logging/setLevel", params: %{"level" => "error"}}
+    assert %{result: %{}} = TestServer.handle_rpc(request, session)
+
+    log(:warning, "Filtered #{session}")
+    log(:error, "Delivered #{session}")
+
+    assert_receive {:mcp_notification, %{params: %{level: "error", data: "Delivered " <> _}}}
+    refute_received {:mcp_notification, %{params: %{data: "Filtered " <> _}}}
+    assert_receive {:relayed, %{params: %{level: "warning", data: "Filtered " <> _}}}
+  end
+
+  test "invalid levels are rejected with the valid ones", %{session: session} do
+    request = %{jsonrpc: "2.0", id: 1, method: "logging/setLevel", params: %{"level" => "loud"}}
+
+    assert %{error: %{data: %{levels: levels}}} = TestServer.handle_rpc(request, session)
+    assert levels == Logging.levels()
+    assert Logging.set_level("loud", session) == {:error, :invalid_level}
+  end
+
+  test "forgotten sessions get nothing", %{session: session} do
+    :ok = Logging.forget(session)
+
+    log(:error, "After forget #{session}")
+
+    refute_receive {:mcp_notification, %{params: %{data: "After forget " <> _}}}, 100
+  end
+
+  # Stands in for another client's transport process
+  defp relay(test) do
+    receive do
+      {:mcp_notification, notification} ->
+        send(test, {:relayed, notification})
+        relay(test)
+    end
+  end
+end
@@ -0,0 +1,42 @@
+defmodule EchoShared.MCP.ProgressTest do
+  use ExUnit.Case, async: false
+
+  alias EchoShared.MCP.{Progress, TestServer}
+
+  test "reports go to the registered server as increasing notifications/progress" do
+    :ok = Progress.register("tok-1", self())
+
+    Progress.report(0, "Loading model")
+    Progress.report(0, "Still loading")
+    Progress.report(120, "Generated 120 tokens", 500)
+
+    assert_received {:mcp_notification,
+                     %{method: "notifications/progress", params: %{progressToken: "tok-1", progress: 0, message: "Loading model"}}}
+
+    assert_received {:mcp_notification,
+                     %{params: %{progressToken: "tok-1", progress: 120, total: 500, message: "Generated 120 tokens"}}}
+
+    refute_received {:mcp_notification, _}
+  end
+
+  test "reporting without a progress token is a no-op" do
+    refute Progress.active?()
+    assert Progress.report(1, "Nobody listening") == :ok
+    refute_received {:mcp_notification, _}
+  end
+
+  test "the stdio loop writes a tool's progress before its response" do
+    request = %{
+      jsonrpc: "2.0",
+      id: 1,
+      method: "tools/call",
+      params: %{name: "progress", arguments: %{}, _meta: %{progressToken: "tok-2"}}
+    }
+
+    assert [
+             %{"method" => "notifications/progress", "params" => %{"progressToken" => "tok-2", "progress" => 1}},
+             %{"method" => "notifications/progress", "params" => %{"progress" => 2, "total" => 2}},
+             %{"id" => 1, "result" => %{"content" => [%{"text" => "done"}]}}
+           ] = TestServer.run_stdio([request])
+  end
+end
@@ -0,0 +1,43 @@
+defmodule EchoShared.MCP.PromptsTest do
+  use ExUnit.Case, async: true
+
+  alias EchoShared.MCP.{Prompts, Resources}
+
+  describe "Prompts" do
+    test "lists strategic_review with a required topic argument" do
+      prompt = Enum.find(Prompts.list(), &(&1.name == "strategic_review"))
+      assert prompt != nil
+      assert %{name: "topic", required: true} = Enum.find(prompt.arguments, &(&1.name == "topic"))
+    end
+
+    test "renders strategic_review for a role" do
+      {:ok, prompt} = Prompts.get(:cto, "strategic_review", %{"topic" => "Move to Kubernetes"})
+
+      assert prompt.description =~ "Move to Kubernetes"
+      assert [%{role: "user", content: %{type: "text", text: text}}] = prompt.messages
+      assert text =~ "CTO"
+      assert text =~ "Topic: Move to Kubernetes"
+    end
+
+    test "rejects missing required arguments" do
+      assert {:error, {:invalid_params, message}} = Prompts.get(:ceo, "strategic_review", %{})
+      assert message =~ "topic"
+    end
+
+    test "unknown prompts are not found" do
+      assert {:error, :prompt_not_found} = Prompts.get(:ceo, "nonexistent", %{})
+    end
+  end
+
+  describe "Resources.parse_uri/1" do
+    test "splits collection and key" do
+      assert Resources.parse_uri("echo://decisions/recent") == {"decisions", "recent"}
+      assert Resources.parse_uri("echo://memories") == {"memories", nil}
+      assert Resources.parse_uri("echo://memories/project%2Fgoals") == {"memories", "project/goals"}
+    end
+
+    test "rejects foreign schemes" do
+      assert Resources.parse_uri("file:///etc/passwd") == :error
+    end
+  end
+end
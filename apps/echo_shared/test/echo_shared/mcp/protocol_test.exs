@@ -0,0 +1,42 @@
+defmodule EchoShared.MCP.ProtocolTest do
+  use ExUnit.Case, async: true
+
+  import ExUnit.CaptureLog
+
+  alias EchoShared.MCP.{Protocol, TestServer}
+
+  describe "negotiate_version/1" do
+    test "keeps every supported version" do
+      for version <- Protocol.supported_versions() do
+        assert Protocol.negotiate_version(version) == version
+      end
+    end
+
+    test "offers the latest version for unknown or missing versions" do
+      assert Protocol.latest_version() == hd(Protocol.supported_versions())
+      assert Protocol.negotiate_version("2099-01-01") == Protocol.latest_version()
+      assert Protocol.negotiate_version(nil) == Protocol.latest_version()
+    end
+  end
+
+  describe "initialize" do
+    defp initialize(version) do
+      request = %{jsonrpc: "2.0", id: 1, method: "initialize", params: %{"protocolVersion" => version}}
+      %{result: result} = TestServer.handle_rpc(request)
+      result
+    end
+
+    test "answers with the negotiated version" do
+      assert %{protocolVersion: "2025-03-26"} = initialize("2025-03-26")
+
+      log = capture_log(fn -> assert initialize("1999-01-01").protocolVersion == Protocol.latest_version() end)
+      assert log =~ "unsupported MCP version"
+    end
+
+    test "only advertises capabilities that exist in the negotiated version" do
+      assert Map.has_key?(initialize("2025-06-18").capabilities, :completions)
+      refute Map.has_key?(initialize("2024-11-05").capabilities, :completions)
+      assert Map.has_key?(initialize("2024-11-05").capabilities, :tools)
+    end
+  end
+end
@@ -0,0 +1,87 @@
+defmodule EchoShared.MCP.SamplingTest do
+  use ExUnit.Case, async: true
+
+  alias EchoShared.MCP.{Sampling, TestServer}
+
+  # Samples from a task whose client channel is the test process, which
+  # plays the transport and the client
+  defp sample(messages, opts \\ %{}) do
+    channel = self()
+
+    Task.async(fn ->
+      Sampling.register(channel, true)
+      Sampling.create_message(messages, opts)
+    end)
+  end
+
+  test "round-trips sampling/createMessage through the client channel" do
+    messages = [
+      %{role: "system", content: "You are the CTO."},
+      %{role: "system", content: "Be brief."},
+      %{role: "user", content: "Kubernetes or Nomad?"}
+    ]
+
+    task = sample(messages, %{max_tokens: 100, model: "claude", speed_priority: 0.8})
+
+    assert_receive {:mcp_client_request, from, ref, "sampling/createMessage", params}
+    assert from == task.pid
+
+    assert params == %{
+             systemPrompt: "You are the CTO.\n\nBe brief.",
+             messages: [%{role: "user", content: %{type: "text", text: "Kubernetes or Nomad?"}}],
+             maxTokens: 100,
+             modelPreferences: %{hints: [%{name: "claude"}], speedPriority: 0.8}
+           }
+
+    result = %{"role" => "assistant", "content" => %{"type" => "text", "text" => "Nomad."}, "model" => "claude"}
+    send(from, {:mcp_client_response, ref, {:ok, result}})
+
+    assert Task.await(task) == {:ok, "Nomad."}
+  end
+
+  test "tools sample through the channel their transport registered" do
+    channel = self()
+    params = %{"name" => "sample", "arguments" => %{"prompt" => "Hi"}}
+
+    task =
+      Task.async(fn ->
+        Sampling.register(channel, true)
+        TestServer.handle_rpc(%{jsonrpc: "2.0", id: 1, method: "tools/call", params: params})
+      end)
+
+    assert_receive {:mcp_client_request, from, ref, "sampling/createMessage", %{messages: [%{content: %{text: "Hi"}}]}}
+    send(from, {:mcp_client_response, ref, {:ok, %{"content" => %{"type" => "text", "text" => "Hello"}}}})
+
+    assert %{id: 1, result: %{content: [%{text: "Hello"}]}} = Task.await(task)
+  end
+
+  test "client errors and non-text content are returned as errors" do
+    task = sample([%{role: "user", content: "Hi"}])
+    assert_receive {:mcp_client_request, from, ref, _method, _params}
+    send(from, {:mcp_client_response, ref, {:error, %{"code" => -1, "message" => "User rejected sampling"}}})
+    assert {:error, {:client_error, %{"code" => -1}}} = Task.await(task)
+
+    task = sample([%{role: "user", content: "Draw a cat"}])
+    assert_receive {:mcp_client_request, from, ref, _method, _params}
+    image = %{"content" => %{"type" => "image", "data" => "", "mimeType" => "image/png"}}
+    send(from, {:mcp_client_response, ref, {:ok, image}})
+    assert {:error, {:unsupported_content, ^image}} = Task.await(task)
+  end
+
+  test "a client that never answers times out" do
+    task = sample([%{role: "user", content: "Hi"}], %{timeout: 50})
+
+    assert_receive {:mcp_client_request, _from, _ref, _method, _params}
+    assert Task.await(task) == {:error, :timeout}
+  end
+
+  test "sampling is unavailable outside a tool call or without client support" do
+    refute Sampling.available?()
+    assert Sampling.create_message([%{role: "user", content: "Hi"}]) == {:error, :sampling_unavailable}
+
+    :ok = Sampling.register(self(), false)
+    refute Sampling.available?()
+    assert Sampling.create_message([%{role: "user", content: "Hi"}]) == {:error, :sampling_unavailable}
+    refute_received {:mcp_client_request, _, _, _, _}
+  end
+end
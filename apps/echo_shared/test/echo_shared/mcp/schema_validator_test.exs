@@ -0,0 +1,48 @@
+defmodule EchoShared.MCP.SchemaValidatorTest do
+  use ExUnit.Case, async: true
+
+  alias EchoShared.MCP.SchemaValidator
+
+  @schema %{
+    type: "object",
+    properties: %{
+      recipient_role: %{type: "string"},
+      amount: %{type: "number", minimum: 0},
+      urgency: %{type: "string", enum: ["low", "medium", "high"]},
+      conditions: %{type: "array", items: %{type: "string"}}
+    },
+    required: ["recipient_role", "amount"]
+  }
+
+  test "accepts valid arguments" do
+    assert :ok =
+             SchemaValidator.validate(
+               %{"recipient_role" => "cto", "amount" => 50_000, "conditions" => ["quarterly review"]},
+               @schema
+             )
+  end
+
+  test "reports missing required fields" do
+    assert {:error, errors} = SchemaValidator.validate(%{"amount" => 10}, @schema)
+    assert %{field: "recipient_role", message: "is required"} in errors
+  end
+
+  test "reports per-field type, enum and bound errors" do
+    args = %{"recipient_role" => "cto", "amount" => -5, "urgency" => "now", "conditions" => ["ok", 3]}
+
+    assert {:error, errors} = SchemaValidator.validate(args, @schema)
+    fields = Enum.map(errors, & &1.field)
+
+    assert "amount" in fields
+    assert "urgency" in fields
+    assert "conditions[1]" in fields
+  end
+
+  test "treats optional nulls as absent" do
+    assert :ok = SchemaValidator.validate(%{"recipient_role" => "cto", "amount" => 1, "urgency" => nil}, @schema)
+  end
+
+  test "rejects non-object arguments" do
+    assert {:error, [%{field: "arguments"}]} = SchemaValidator.validate("nope", @schema)
+  end
+end
@@ -0,0 +1,45 @@
+defmodule EchoShared.MCP.ServerTest do
+  use ExUnit.Case, async: false
+
+  alias EchoShared.MCP.TestServer
+
+  defp call(id, tool, arguments \\ %{}) do
+    %{jsonrpc: "2.0", id: id, method: "tools/call", params: %{name: tool, arguments: arguments}}
+  end
+
+  defp responses(output), do: Enum.filter(output, &Map.has_key?(&1, "id"))
+
+  test "a slow tool call doesn't block other requests" do
+    output = TestServer.run_stdio([call(1, "slow", %{ms: 300}), %{jsonrpc: "2.0", id: 2, method: "ping"}])
+
+    assert [%{"id" => 2, "result" => %{}}, %{"id" => 1, "result" => %{"content" => [%{"text" => "done"}]}}] =
+             responses(output)
+  end
+
+  test "a cancelled tool call is stopped and gets no response" do
+    cancel = %{
+      jsonrpc: "2.0",
+      method: "notifications/cancelled",
+      params: %{requestId: 1, reason: "User pressed stop"}
+    }
+
+    # The slow call sleeps far longer than run_stdio waits, so it must have been killed
+    output = TestServer.run_stdio([call(1, "slow"), cancel, call(2, "echo", %{text: "still here"})], 2_000)
+
+    assert [%{"id" => 2, "result" => %{"content" => [%{"text" => "still here"}]}}] = responses(output)
+  end
+
+  test "invalid requests get -32600 and the loop keeps going" do
+    output = TestServer.run_stdio([%{id: 1, method: "ping"}, %{jsonrpc: "2.0", id: 2, method: "ping"}])
+
+    assert [%{"id" => nil, "error" => %{"code" => -32600}}, %{"id" => 2, "result" => %{}}] = responses(output)
+  end
+
+  test "cancelling an unknown request is ignored" do
+    cancel = %{jsonrpc: "2.0", method: "notifications/cancelled", params: %{requestId: 99}}
+
+    output = TestServer.run_stdio([cancel, call(1, "echo", %{text: "hi"})])
+
+    assert [%{"id" => 1, "result" => %{"content" => [%{"text" => "hi"}]}}] = responses(output)
+  end
+end
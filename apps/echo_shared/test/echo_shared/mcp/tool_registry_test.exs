@@ -0,0 +1,50 @@
+defmodule EchoShared.MCP.ToolRegistryTest do
+  use ExUnit.Case, async: false
+
+  alias EchoShared.MCP.{TestServer, ToolRegistry}
+
+  setup do
+    :ok = ToolRegistry.subscribe(TestServer)
+    on_exit(fn -> TestServer.reset_tool("echo") end)
+    :ok
+  end
+
+  defp names(tools), do: Enum.map(tools, &(&1[:name] || &1["name"]))
+
+  test "disabling a tool hides it and notifies subscribers" do
+    :ok = TestServer.disable_tool("echo")
+
+    assert_receive {:mcp_notification, %{method: "notifications/tools/list_changed"}}
+    refute "echo" in names(TestServer.available_tools())
+
+    request = %{jsonrpc: "2.0", id: 1, method: "tools/call", params: %{"name" => "echo", "arguments" => %{"text" => "hi"}}}
+    assert %{error: %{message: "Unknown tool: echo"}} = TestServer.handle_rpc(request)
+  end
+
+  test "resetting an override makes tool_enabled?/1 decide again" do
+    :ok = TestServer.disable_tool("echo")
+    assert_receive {:mcp_notification, %{method: "notifications/tools/list_changed"}}
+
+    :ok = TestServer.reset_tool("echo")
+    assert_receive {:mcp_notification, %{method: "notifications/tools/list_changed"}}
+    assert ToolRegistry.override(TestServer, "echo") == nil
+    assert "echo" in names(TestServer.available_tools())
+  end
+
+  test "changes that don't change anything send no notification" do
+    :ok = TestServer.enable_tool("echo")
+    assert_receive {:mcp_notification, %{method: "notifications/tools/list_changed"}}
+
+    :ok = TestServer.enable_tool("echo")
+    :ok = TestServer.reset_tool("slow")
+    refute_receive {:mcp_notification, _}, 100
+  end
+
+  test "only subscribers of the changed server are notified" do
+    :ok = ToolRegistry.disable(EchoShared.MCP.OtherServer, "echo")
+    on_exit(fn -> ToolRegistry.reset(EchoShared.MCP.OtherServer, "echo") end)
+
+    refute_receive {:mcp_notification, _}, 100
+    assert "echo" in names(TestServer.available_tools())
+  end
+end
@@ -0,0 +1,39 @@
+defmodule EchoShared.MessageBus.Adapters.LocalTest do
+  use ExUnit.Case, async: true
+
+  alias EchoShared.MessageBus.Adapters.Local
+
+  test "delivers published payloads in the Redix.PubSub shape" do
+    channel = "test:#{System.unique_integer([:positive])}"
+    {:ok, ref} = Local.subscribe([channel], self())
+
+    assert_receive {:redix_pubsub, _pid, ^ref, :subscribed, %{channel: ^channel}}
+    assert {:ok, 1} = Local.publish(channel, "hello")
+    assert_receive {:redix_pubsub, _pid, ^ref, :message, %{channel: ^channel, payload: "hello"}}
+  end
+
+  test "publishing without subscribers reaches nobody" do
+    assert {:ok, 0} = Local.publish("test:nobody", "hello")
+  end
+
+  test "keeps a reply pushed before anyone waits" do
+    key = "replies:#{System.unique_integer([:positive])}"
+
+    :ok = Local.push_reply(key, "early", 60)
+    assert {:ok, "early"} = Local.await_reply(key, 100)
+  end
+
+  test "wakes a waiting requester" do
+    key = "replies:#{System.unique_integer([:positive])}"
+
+    task = Task.async(fn -> Local.await_reply(key, 1_000) end)
+    Process.sleep(50)
+    :ok = Local.push_reply(key, "late", 60)
+
+    assert {:ok, "late"} = Task.await(task)
+  end
+
+  test "times out when no reply arrives" do
+    assert {:error, :timeout} = Local.await_reply("replies:never", 50)
+  end
+end
@@ -0,0 +1,50 @@
+defmodule EchoShared.MessageBus.Adapters.PostgresTest do
+  # NOTIFY is only sent on commit, which the sandbox never does, so these
+  # cover what doesn't depend on it: subscribing and the replies table
+  use EchoShared.DataCase
+
+  alias EchoShared.MessageBus.Adapters.Postgres
+
+  setup do
+    start_supervised!(Postgres)
+    :ok
+  end
+
+  defp reply_key, do: "replies:#{System.unique_integer([:positive])}"
+
+  test "acknowledges subscriptions in the Redix.PubSub shape" do
+    {:ok, ref} = Postgres.subscribe(["messages:cto", "messages:all"], self())
+
+    assert_receive {:redix_pubsub, _pid, ^ref, :subscribed, %{channel: "messages:cto"}}
+    assert_receive {:redix_pubsub, _pid, ^ref, :subscribed, %{channel: "messages:all"}}
+  end
+
+  test "keeps a reply pushed before anyone waits, for one taker" do
+    key = reply_key()
+
+    :ok = Postgres.push_reply(key, "early", 60)
+
+    assert {:ok, "early"} = Postgres.await_reply(key, 1_000)
+    assert {:error, :timeout} = Postgres.await_reply(key, 100)
+  end
+
+  test "replies are kept per key" do
+    key = reply_key()
+
+    :ok = Postgres.push_reply(key, "first", 60)
+    :ok = Postgres.push_reply(reply_key(), "other", 60)
+    :ok = Postgres.push_reply(key, "second", 60)
+
+    assert {:ok, "first"} = Postgres.await_reply(key, 1_000)
+    assert {:ok, "second"} = Postgres.await_reply(key, 1_000)
+  end
+
+  test "times out when no reply arrives" do
+    assert {:error, :timeout} = Postgres.await_reply(reply_key(), 100)
+  end
+
+  test "rejects payloads NOTIFY can't carry" do
+    assert {:error, :payload_too_large} = Postgres.publish("messages:cto", String.duplicate("x", 8_000))
+    assert {:ok, 1} = Postgres.publish("messages:cto", "hello")
+  end
+end
@@ -0,0 +1,53 @@
+defmodule EchoShared.MessageBus.PolicyTest do
+  use EchoShared.DataCase
+
+  alias EchoShared.MessageBus
+  alias EchoShared.MessageBus.Policy
+
+  test "developers may request the architect and test lead only" do
+    assert Policy.allowed?(:senior_developer, :senior_architect, :request)
+    assert Policy.allowed?(:senior_developer, :test_lead, :request)
+    refute Policy.allowed?(:senior_developer, :uiux_engineer, :request)
+    refute Policy.allowed?(:senior_developer, :ceo, :request)
+  end
+
+  test "only the C-suite may publish to leadership" do
+    assert Policy.allowed?(:cto, :leadership, :request)
+    refute Policy.allowed?(:product_manager, :leadership, :notification)
+    refute Policy.allowed?(:test_lead, :leadership, :response)
+  end
+
+  test "anyone may escalate to the C-suite" do
+    assert Policy.allowed?(:test_lead, :ceo, :escalation)
+    assert Policy.allowed?("uiux_engineer", "cto", "notification")
+  end
+
+  test "responses must answer a message the recipient sent to the responder" do
+    {:ok, request} = MessageBus.store_message_in_db(:uiux_engineer, :senior_developer, :request, "Specs?", %{})
+    {:ok, _} = MessageBus.store_message_in_db(:cto, :uiux_engineer, :request, "Review", %{correlation_id: "corr_1"})
+
+    assert Policy.allowed?(:senior_developer, :uiux_engineer, :response, %{in_reply_to: request.id})
+    assert Policy.allowed?(:uiux_engineer, :cto, :response, %{"correlation_id" => "corr_1"})
+
+    refute Policy.allowed?(:senior_developer, :uiux_engineer, :response)
+    refute Policy.allowed?(:test_lead, :uiux_engineer, :response, %{in_reply_to: request.id})
+    refute Policy.allowed?(:senior_developer, :product_manager, :response, %{in_reply_to: request.id})
+    refute Policy.allowed?(:uiux_engineer, :test_lead, :response, %{correlation_id: "corr_1"})
+  end
+
+  test "individual contributors may escalate to their manager only" do
+    assert Policy.allowed?(:senior_developer, :senior_architect, :escalation)
+    assert Policy.allowed?(:uiux_engineer, :product_manager, :escalation)
+    refute Policy.allowed?(:senior_developer, :test_lead, :escalation)
+  end
+
+  test "orchestrators may route work to any agent" do
+    assert Policy.allowed?(:workflow_engine, :senior_developer, :request)
+    assert Policy.allowed?("delegator", "test_lead", "request")
+  end
+
+  test "unknown senders are not orchestrators" do
+    refute Policy.allowed?(:workflow, :senior_developer, :request)
+    refute Policy.allowed?("intruder", "ceo", "request")
+  end
+end
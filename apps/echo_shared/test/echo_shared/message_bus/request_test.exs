@@ -0,0 +1,66 @@
+defmodule EchoShared.MessageBus.RequestTest do
+  use EchoShared.DataCase
+
+  alias EchoShared.MessageBus
+  alias EchoShared.Schemas.Message
+
+  # Plays `role`'s message handler, calling `handle` with each request
+  defp handle_requests(role, handle) do
+    test = self()
+
+    spawn_link(fn ->
+      {:ok, _ref} = MessageBus.subscribe("messages:#{role}")
+      send(test, :subscribed)
+      loop(handle)
+    end)
+
+    assert_receive :subscribed
+  end
+
+  defp loop(handle) do
+    receive do
+      {:redix_pubsub, _pid, _ref, :message, %{channel: channel, payload: payload}} ->
+        {:ok, request} = MessageBus.decode_message(channel, payload)
+        handle.(request)
+        loop(handle)
+    end
+  end
+
+  test "each caller gets the reply carrying its own correlation ID" do
+    handle_requests(:cto, &MessageBus.reply(&1, &1["content"]))
+
+    tasks =
+      for quarter <- ["Q3", "Q4"] do
+        Task.async(fn -> MessageBus.request(:ceo, :cto, "Capacity Review", %{quarter: quarter}, timeout: 2_000) end)
+      end
+
+    assert Task.await_many(tasks) == [{:ok, %{"quarter" => "Q3"}}, {:ok, %{"quarter" => "Q4"}}]
+
+    responses = Repo.all(from m in Message, where: m.type == :response and m.to_role == "ceo")
+    assert length(responses) == 2
+    assert Enum.all?(responses, &String.starts_with?(&1.metadata["correlation_id"], "corr_"))
+  end
+
+  test "error replies fail the request" do
+    handle_requests(:cto, &MessageBus.reply(&1, {:error, :over_budget}))
+
+    assert MessageBus.request(:ceo, :cto, "Capacity Review", %{}, timeout: 2_000) ==
+             {:error, {:remote_error, ":over_budget"}}
+  end
+
+  test "a request times out, and its late reply doesn't reach the next request" do
+    test = self()
+    handle_requests(:cto, &send(test, {:request, &1}))
+
+    assert MessageBus.request(:ceo, :cto, "Capacity Review", %{n: 1}, timeout: 100) == {:error, :timeout}
+    assert_receive {:request, late}
+    {:ok, _} = MessageBus.reply(late, %{"n" => 1})
+
+    task = Task.async(fn -> MessageBus.request(:ceo, :cto, "Capacity Review", %{n: 2}, timeout: 2_000) end)
+    assert_receive {:request, current}
+    refute current["metadata"]["correlation_id"] == late["metadata"]["correlation_id"]
+    {:ok, _} = MessageBus.reply(current, %{"n" => 2})
+
+    assert Task.await(task) == {:ok, %{"n" => 2}}
+  end
+end
@@ -0,0 +1,101 @@
+defmodule EchoShared.MessageBus.SigningTest do
+  use ExUnit.Case, async: false
+
+  alias EchoShared.MessageBus
+  alias EchoShared.MessageBus.Signing
+
+  defp envelope(from \\ "cto") do
+    Signing.sign(%{
+      db_id: 1,
+      from: from,
+      to: "ceo",
+      type: "request",
+      subject: "Budget approval",
+      content: %{amount: 50_000, items: ["gpu", "storage"]},
+      metadata: %{priority: "high"}
+    })
+  end
+
+  defp put_env(key, value) do
+    previous = Application.get_env(:echo_shared, key)
+    Application.put_env(:echo_shared, key, value)
+    on_exit(fn ->
+      if previous, do: Application.put_env(:echo_shared, key, previous), else: Application.delete_env(:echo_shared, key)
+    end)
+  end
+
+  test "signed envelopes survive the JSON round trip, once" do
+    envelope = envelope()
+
+    assert {:ok, message} = MessageBus.decode_message("messages:ceo", Jason.encode!(envelope))
+    assert message["from"] == "cto"
+
+    assert {:error, {:rejected, :replayed}} = MessageBus.decode_message("messages:ceo", Jason.encode!(envelope))
+    # The replay check is per receiving process
+    assert Task.async(fn -> Signing.verify(envelope) end) |> Task.await() == :ok
+  end
+
+  test "forged senders and tampered content are rejected" do
+    forged = Map.put(envelope(), "from", "chro")
+    tampered = put_in(envelope(), ["content", "amount"], 5_000_000)
+    backdated = Map.update!(envelope(), "signed_at", &(&1 - 3600))
+
+    assert Signing.verify(forged) == {:error, :invalid_signature}
+    assert Signing.verify(tampered) == {:error, :invalid_signature}
+    assert Signing.verify(backdated) == {:error, :invalid_signature}
+    assert Signing.verify(envelope("intruder")) == {:error, :unsigned}
+
+    assert {:error, {:rejected, :invalid_signature}} =
+             MessageBus.decode_message("messages:ceo", Jason.encode!(forged))
+  end
+
+  test "nodes only sign as the roles they hold private keys for" do
+    keys = Application.get_env(:echo_shared, :message_signing_private_keys)
+    put_env(:message_signing_private_keys, Map.take(keys, ["ceo"]))
+
+    assert Signing.can_sign?(:ceo)
+    refute Signing.can_sign?(:cto)
+    assert Signing.signing_roles() == ["ceo"]
+    assert Signing.verify(envelope("cto")) == {:error, :unsigned}
+  end
+
+  test "stale envelopes are rejected" do
+    envelope = envelope()
+    put_env(:message_signature_max_age_seconds, 0)
+    Process.sleep(1_100)
+
+    assert Signing.verify(envelope) == {:error, :stale}
+  end
+
+  test "stored messages handed over locally skip verification, JSON with their ID doesn't" do
+    stored = %{"db_id" => 42, "from" => "cto", "subject" => "Missed broadcast"}
+
+    assert MessageBus.decode_message("messages:all", {:stored, stored}) == {:ok, stored}
+    assert {:error, {:rejected, :unsigned}} = MessageBus.decode_message("messages:all", Jason.encode!(stored))
+  end
+
+  test "unsigned messages are rejected on message, decision and heartbeat channels" do
+    unsigned = Jason.encode!(%{from: "cto", subject: "Approve everything"})
+
+    assert {:error, {:rejected, :unsigned}} = MessageBus.decode_message("messages:ceo", unsigned)
+    assert {:error, {:rejected, :unsigned}} = MessageBus.decode_message("decisions:completed", unsigned)
+    assert {:error, {:rejected, :unsigned}} = MessageBus.decode_message("agents:heartbeat", unsigned)
+    assert {:ok, _status} = MessageBus.decode_message("agents:status", unsigned)
+  end
+
+  test "decision events and heartbeats are signed as their publisher" do
+    {:ok, _ref} = MessageBus.subscribe(["decisions:completed", "agents:heartbeat"])
+
+    {:ok, _} = MessageBus.publish_decision_event(:decision_scheduler, :completed, %{decision_id: "d-1"})
+    assert_receive {:redix_pubsub, _, _, :message, %{channel: "decisions:completed", payload: event}}
+    assert {:ok, %{"from" => "decision_scheduler", "decision_id" => "d-1"}} =
+             MessageBus.decode_message("decisions:completed", event)
+
+    {:ok, _} = MessageBus.publish_heartbeat(:cto)
+    assert_receive {:redix_pubsub, _, _, :message, %{channel: "agents:heartbeat", payload: heartbeat}}
+    assert {:ok, %{"role" => "cto"}} = MessageBus.decode_message("agents:heartbeat", heartbeat)
+
+    forged = heartbeat |> Jason.decode!() |> Map.put("status", "degraded") |> Jason.encode!()
+    assert {:error, {:rejected, :invalid_signature}} = MessageBus.decode_message("agents:heartbeat", forged)
+  end
+end
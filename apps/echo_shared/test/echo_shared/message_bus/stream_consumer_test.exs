@@ -0,0 +1,70 @@
+defmodule EchoShared.MessageBus.StreamConsumerTest do
+  use ExUnit.Case, async: false
+
+  # Needs Redis (REDIS_HOST, REDIS_PORT): mix test --include redis
+  @moduletag :redis
+
+  alias EchoShared.MessageBus.StreamConsumer
+
+  setup do
+    # StreamConsumer.ack/1 XACKs on the shared connection
+    start_supervised!({Redix, [name: :redix] ++ redis()})
+
+    role = "stream_test_#{System.unique_integer([:positive])}"
+    key = StreamConsumer.stream_key("messages:#{role}")
+
+    on_exit(fn ->
+      {:ok, conn} = Redix.start_link(redis())
+      # The broadcast stream is shared; only drop this role's group on it
+      Redix.command(conn, ["DEL", key])
+      Redix.command(conn, ["XGROUP", "DESTROY", StreamConsumer.stream_key("messages:all"), role])
+      Redix.stop(conn)
+    end)
+
+    {:ok, role: role, key: key}
+  end
+
+  defp redis do
+    [host: System.get_env("REDIS_HOST", "localhost"), port: String.to_integer(System.get_env("REDIS_PORT", "6383"))]
+  end
+
+  defp start_consumer(role) do
+    start_supervised!({StreamConsumer, role: role, subscriber: self()})
+  end
+
+  defp add(key, db_id) do
+    {:ok, _id} = Redix.command(:redix, ["XADD", key, "*", "payload", Jason.encode!(%{db_id: db_id})])
+  end
+
+  defp pending(key, role) do
+    {:ok, [count | _]} = Redix.command(:redix, ["XPENDING", key, role])
+    count
+  end
+
+  test "delivers entries in the pub/sub shape and keeps them pending until acked", %{role: role, key: key} do
+    start_consumer(role)
+    add(key, 1)
+
+    channel = "messages:#{role}"
+    assert_receive {:redix_pubsub, _pid, _ref, :message, %{channel: ^channel, payload: payload}}, 2_000
+    assert Jason.decode!(payload) == %{"db_id" => 1}
+    assert pending(key, role) == 1
+
+    :ok = StreamConsumer.ack(1)
+    assert pending(key, role) == 0
+  end
+
+  test "entries left unacked or added while down are delivered on restart", %{role: role, key: key} do
+    start_consumer(role)
+    add(key, 1)
+    assert_receive {:redix_pubsub, _, _, :message, _}, 2_000
+
+    stop_supervised!(StreamConsumer)
+    add(key, 2)
+    start_consumer(role)
+
+    assert_receive {:redix_pubsub, _, _, :message, %{payload: first}}, 2_000
+    assert_receive {:redix_pubsub, _, _, :message, %{payload: second}}, 2_000
+    assert Enum.map([first, second], &Jason.decode!(&1)["db_id"]) == [1, 2]
+  end
+end
@@ -0,0 +1,53 @@
+defmodule EchoShared.DataCase do
+  @moduledoc """
+  Test case for tests that touch the database.
+
+  Checks out a sandboxed connection, shared with the processes the test
+  starts (so these tests can't run async), and imports the factories
+  below.
+  """
+
+  use ExUnit.CaseTemplate
+
+  alias EchoShared.Repo
+  alias EchoShared.Schemas.Decision
+
+  using do
+    quote do
+      import Ecto.Query
+      import EchoShared.DataCase
+
+      alias EchoShared.Repo
+    end
+  end
+
+  setup do
+    :ok = Ecto.Adapters.SQL.Sandbox.checkout(Repo)
+    Ecto.Adapters.SQL.Sandbox.mode(Repo, {:shared, self()})
+    :ok
+  end
+
+  @doc """
+  Insert a decision: a pending collaborative budget decision initiated by
+  the CEO and voted on by the rest of the C-suite, overridden by `attrs`.
+  """
+  def insert_decision(attrs \\ %{}) do
+    {:ok, decision} =
+      %Decision{}
+      |> Decision.changeset(
+        Map.merge(
+          %{
+            decision_type: "budget",
+            initiator_role: "ceo",
+            participants: ["cto", "chro", "operations_head"],
+            mode: :collaborative,
+            context: %{"amount" => 250_000}
+          },
+          Map.new(attrs)
+        )
+      )
+      |> Repo.insert()
+
+    decision
+  end
+end
@@ -0,0 +1,99 @@
+defmodule EchoShared.MCP.TestServer do
+  @moduledoc """
+  Minimal MCP server for transport tests.
+
+  Its tools stand in for agent tools, so tests can drive the MCP
+  transports without an agent, a database or an LLM.
+  """
+
+  use EchoShared.MCP.Server
+
+  alias EchoShared.MCP.{Progress, Sampling}
+
+  @impl true
+  def agent_info do
+    %{name: "echo-test", version: "0.1.0", role: :cto}
+  end
+
+  @impl true
+  def tools do
+    [
+      %{
+        name: "echo",
+        description: "Return the given text",
+        inputSchema: %{
+          type: "object",
+          properties: %{text: %{type: "string"}},
+          required: ["text"]
+        }
+      },
+      %{
+        name: "slow",
+        description: "Sleep before answering",
+        inputSchema: %{
+          type: "object",
+          properties: %{ms: %{type: "integer"}}
+        }
+      },
+      %{
+        name: "progress",
+        description: "Report progress, then answer",
+        inputSchema: %{type: "object", properties: %{}}
+      },
+      %{
+        name: "sample",
+        description: "Ask the client's model",
+        inputSchema: %{
+          type: "object",
+          properties: %{prompt: %{type: "string"}},
+          required: ["prompt"]
+        }
+      }
+    ]
+  end
+
+  @impl true
+  def execute_tool("echo", %{"text" => text}), do: {:ok, text}
+
+  def execute_tool("slow", args) do
+    Process.sleep(args["ms"] || 10_000)
+    {:ok, "done"}
+  end
+
+  def execute_tool("progress", _args) do
+    Progress.report(1, "first")
+    # Not past the last value, so dropped
+    Progress.report(1, "again")
+    Progress.report(2, "second", 2)
+    {:ok, "done"}
+  end
+
+  def execute_tool("sample", %{"prompt" => prompt}) do
+    Sampling.create_message([%{role: "user", content: prompt}], %{timeout: 1_000})
+  end
+
+  def execute_tool(name, _args), do: {:error, {:unknown_tool, name}}
+
+  @doc """
+  Run the stdio loop over `messages` and return the decoded output lines.
+
+  Stdin closes after the last message, so the loop returns once every
+  in-flight tool call has answered.
+  """
+  def run_stdio(messages, timeout \\ 5_000) do
+    {:ok, io} = StringIO.open(Enum.map_join(messages, &(Jason.encode!(&1) <> "\n")))
+
+    :ok =
+      Task.async(fn ->
+        Process.group_leader(self(), io)
+        start()
+      end)
+      |> Task.await(timeout)
+
+    {_input, output} = StringIO.contents(io)
+
+    output
+    |> String.split("\n", trim: true)
+    |> Enum.map(&Jason.decode!/1)
+  end
+end
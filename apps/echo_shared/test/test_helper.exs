@@ -1,4 +1,5 @@
-ExUnit.start()
+# Tests tagged :redis need a Redis server; run them with --include redis
+ExUnit.start(exclude: [:redis])
 
 # Start the application (test.exs configures Sandbox pool)
 {:ok, _} = Application.ensure_all_started(:echo_shared)
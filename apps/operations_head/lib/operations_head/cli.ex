@@ -2,8 +2,9 @@ defmodule OperationsHead.CLI do
   @moduledoc """
   Escript entry point for the OPERATIONS_HEAD agent.
 
-  Supports two modes:
+  Supports three modes:
   - MCP mode (default): Runs as MCP server listening on stdin/stdout
+  - HTTP mode (--http [port]): Runs as MCP server over streamable HTTP, serving many clients
   - Autonomous mode (--autonomous): Runs indefinitely processing Redis messages
   """
 
@@ -20,6 +21,14 @@ defmodule OperationsHead.CLI do
         IO.puts("OPERATIONS_HEAD application started successfully")
         Process.sleep(:infinity)
 
+      ["--http" | rest] ->
+        # Run as a long-lived MCP server over HTTP
+        opts = if port = List.first(rest), do: [port: String.to_integer(port)], else: []
+        {:ok, _} = OperationsHead.start_http(opts)
+
+        IO.puts("OPERATIONS_HEAD MCP HTTP server started")
+        Process.sleep(:infinity)
+
       _ ->
         # Run as MCP server
         OperationsHead.start()
@@ -31,7 +31,7 @@ defmodule OperationsHead.MessageHandler do
     {:ok, _} = MessageBus.subscribe_to_role(:operations_head)
 
     # Subscribe to decision events
-    Redix.PubSub.subscribe(:redix_pubsub, ["decisions:new", "decisions:escalated"], self())
+    MessageBus.subscribe(["decisions:new", "decisions:escalated"])
 
     {:ok, %{recent_broadcasts: MapSet.new()}}
   end
@@ -40,9 +40,16 @@ defmodule OperationsHead.MessageHandler do
   def handle_info({:redix_pubsub, _pid, _ref, :message, %{channel: channel, payload: payload}}, state) do
     Logger.info("OPERATIONS_HEAD received Redis message on channel: #{channel}")
 
-    new_state = case Jason.decode(payload) do
+    new_state = case MessageBus.decode_message(channel, payload) do
       {:ok, message} ->
-        handle_message(channel, message, state)
+        updated_state = handle_message(channel, message, state)
+
+        # Record this role's receipt so the broadcast isn't replayed on restart
+        if channel == "messages:all" and message["db_id"] do
+          MessageBus.mark_message_processed(message["db_id"], :operations_head)
+        end
+
+        updated_state
 
       {:error, reason} ->
         Logger.error("Failed to decode message: #{inspect(reason)}")
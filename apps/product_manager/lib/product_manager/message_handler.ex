@@ -32,7 +32,7 @@ defmodule ProductManager.MessageHandler do
     {:ok, _} = MessageBus.subscribe_to_role(:product_manager)
 
     # Subscribe to decision events
-    Redix.PubSub.subscribe(:redix_pubsub, ["decisions:new", "decisions:escalated"], self())
+    MessageBus.subscribe(["decisions:new", "decisions:escalated"])
 
     # Initialize state with recent_broadcasts tracking
     {:ok, %{recent_broadcasts: MapSet.new()}}
@@ -42,9 +42,16 @@ defmodule ProductManager.MessageHandler do
   def handle_info({:redix_pubsub, _pid, _ref, :message, %{channel: channel, payload: payload}}, state) do
     Logger.info("PRODUCT_MANAGER received Redis message on channel: #{channel}")
 
-    new_state = case Jason.decode(payload) do
+    new_state = case MessageBus.decode_message(channel, payload) do
       {:ok, message} ->
-        handle_message(channel, message, state)
+        updated_state = handle_message(channel, message, state)
+
+        # Record this role's receipt so the broadcast isn't replayed on restart
+        if channel == "messages:all" and message["db_id"] do
+          MessageBus.mark_message_processed(message["db_id"], :product_manager)
+        end
+
+        updated_state
 
       {:error, reason} ->
         Logger.error("Failed to decode message: #{inspect(reason)}")
@@ -194,7 +201,8 @@ defmodule ProductManager.MessageHandler do
           String.to_atom(from_agent),
           :response,
           "Re: #{subject}",
-          response_content
+          response_content,
+          %{in_reply_to: message["db_id"]}
         )
 
         Logger.info("Response sent to #{from_agent}")
@@ -215,7 +223,8 @@ defmodule ProductManager.MessageHandler do
           String.to_atom(from_agent),
           :response,
           "Re: #{subject}",
-          fallback_content
+          fallback_content,
+          %{in_reply_to: message["db_id"]}
         )
     end
   end
@@ -270,7 +279,7 @@ defmodule ProductManager.MessageHandler do
       content: response_content,
       participation_type: participation_type,
       timestamp: DateTime.utc_now() |> DateTime.to_iso8601()
-    })
+    }, %{in_reply_to: message["db_id"]})
 
     # Store in database
     MessageBus.store_message_in_db("product_manager", from, "response", "Re: #{subject}", %{
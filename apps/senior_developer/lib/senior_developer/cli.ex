@@ -2,8 +2,9 @@ defmodule SeniorDeveloper.CLI do
   @moduledoc """
   Escript entry point for the SENIOR_DEVELOPER agent.
 
-  Supports two modes:
+  Supports three modes:
   - MCP mode (default): Runs as MCP server listening on stdin/stdout
+  - HTTP mode (--http [port]): Runs as MCP server over streamable HTTP, serving many clients
   - Autonomous mode (--autonomous): Runs indefinitely processing Redis messages
   """
 
@@ -20,6 +21,14 @@ defmodule SeniorDeveloper.CLI do
         IO.puts("SENIOR_DEVELOPER application started successfully")
         Process.sleep(:infinity)
 
+      ["--http" | rest] ->
+        # Run as a long-lived MCP server over HTTP
+        opts = if port = List.first(rest), do: [port: String.to_integer(port)], else: []
+        {:ok, _} = SeniorDeveloper.start_http(opts)
+
+        IO.puts("SENIOR_DEVELOPER MCP HTTP server started")
+        Process.sleep(:infinity)
+
       _ ->
         # Run as MCP server
         SeniorDeveloper.start()
@@ -37,7 +37,7 @@ defmodule SeniorDeveloper.MessageHandler do
     end
 
     # Subscribe to decision events
-    case Redix.PubSub.subscribe(:redix_pubsub, ["decisions:new", "decisions:escalated"], self()) do
+    case MessageBus.subscribe(["decisions:new", "decisions:escalated"]) do
       {:ok, ref} ->
         Logger.info("✓ Subscribed to decision events (ref: #{inspect(ref)})")
       error ->
@@ -52,9 +52,16 @@ defmodule SeniorDeveloper.MessageHandler do
   def handle_info({:redix_pubsub, _pid, _ref, :message, %{channel: channel, payload: payload}}, state) do
     Logger.info("SENIOR_DEVELOPER received Redis message on channel: #{channel}")
 
-    new_state = case Jason.decode(payload) do
+    new_state = case MessageBus.decode_message(channel, payload) do
       {:ok, message} ->
-        handle_message(channel, message, state)
+        updated_state = handle_message(channel, message, state)
+
+        # Record this role's receipt so the broadcast isn't replayed on restart
+        if channel == "messages:all" and message["db_id"] do
+          MessageBus.mark_message_processed(message["db_id"], :senior_developer)
+        end
+
+        updated_state
 
       {:error, reason} ->
         Logger.error("Failed to decode message: #{inspect(reason)}")
@@ -199,7 +206,8 @@ defmodule SeniorDeveloper.MessageHandler do
           String.to_atom(from_agent),
           :response,
           "Re: #{subject}",
-          response_content
+          response_content,
+          %{in_reply_to: message["db_id"]}
         )
 
         Logger.info("Response sent to #{from_agent}")
@@ -220,7 +228,8 @@ defmodule SeniorDeveloper.MessageHandler do
           String.to_atom(from_agent),
           :response,
           "Re: #{subject}",
-          fallback_content
+          fallback_content,
+          %{in_reply_to: message["db_id"]}
         )
     end
   end
@@ -272,7 +281,7 @@ defmodule SeniorDeveloper.MessageHandler do
       content: response_content,
       participation_type: participation_type,
       timestamp: DateTime.utc_now() |> DateTime.to_iso8601()
-    })
+    }, %{in_reply_to: message["db_id"]})
 
     MessageBus.store_message_in_db("senior_developer", from, "response", "Re: #{subject}", %{
       content: response_content,
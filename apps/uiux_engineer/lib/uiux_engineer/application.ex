@@ -11,11 +11,6 @@ defmodule UiuxEngineer.Application do
     Logger.info("Starting UI_UX_ENGINEER Agent...")
 
     children = [
-      %{
-        id: Redix.PubSub,
-        start: {Redix.PubSub, :start_link, [[name: :redix_pubsub, host: redis_host(), port: redis_port()]]}
-      },
-
       # Heartbeat worker
       {EchoShared.HeartbeatWorker, role: :uiux_engineer, metadata: %{version: "1.0.0"}},
 
@@ -26,7 +21,4 @@ defmodule UiuxEngineer.Application do
     opts = [strategy: :one_for_one, name: UiuxEngineer.Supervisor]
     Supervisor.start_link(children, opts)
   end
-
-  defp redis_host, do: System.get_env("REDIS_HOST", "localhost")
-  defp redis_port, do: String.to_integer(System.get_env("REDIS_PORT", "6383"))
 end
@@ -31,16 +31,23 @@ defmodule UiuxEngineer.MessageHandler do
     {:ok, _} = MessageBus.subscribe_to_role(:uiux_engineer)
 
     # Subscribe to decision events
-    Redix.PubSub.subscribe(:redix_pubsub, ["decisions:new", "decisions:escalated"], self())
+    MessageBus.subscribe(["decisions:new", "decisions:escalated"])
 
     {:ok, %{recent_broadcasts: MapSet.new()}}
   end
 
   @impl true
   def handle_info({:redix_pubsub, _pid, _ref, :message, %{channel: channel, payload: payload}}, state) do
-    new_state = case Jason.decode(payload) do
+    new_state = case MessageBus.decode_message(channel, payload) do
       {:ok, message} ->
-        handle_message(channel, message, state)
+        updated_state = handle_message(channel, message, state)
+
+        # Record this role's receipt so the broadcast isn't replayed on restart
+        if channel == "messages:all" and message["db_id"] do
+          MessageBus.mark_message_processed(message["db_id"], :uiux_engineer)
+        end
+
+        updated_state
 
       {:error, reason} ->
         Logger.error("Failed to decode message: #{inspect(reason)}")
@@ -161,7 +168,7 @@ defmodule UiuxEngineer.MessageHandler do
       content: response_content,
       participation_type: participation_type,
       timestamp: DateTime.utc_now() |> DateTime.to_iso8601()
-    })
+    }, %{in_reply_to: message["db_id"]})
 
     MessageBus.store_message_in_db("uiux_engineer", from, "response", "Re: #{subject}", %{
       content: response_content,